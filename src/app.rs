@@ -1,45 +1,130 @@
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 use std::fmt::Write;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
 use chrono::Duration as ChronoDuration;
-use chrono::{DateTime, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono::{DateTime, Local, LocalResult, SubsecRound, TimeZone, Utc};
+use futures::FutureExt;
+use tokio::task::{self, AbortHandle, JoinHandle};
 use tui_input::Input as SingleLineInput;
-use tui_textarea::TextArea;
+use tui_textarea::{CursorMove, TextArea};
 
-use crate::aws_profiles;
-use crate::defaults::{default_app_values, AppDefaults};
-use crate::log_fetcher::QueryParams;
-use crate::presentation::{format_modal_message, format_modal_value, FormattedResults};
+use crate::aws_profiles::ProfileDiscovery;
+use crate::block_edit::BlockCaret;
+use crate::cloudtrail::{self, CloudTrailPane};
+use crate::clusters::{self, ClusterView};
+use crate::column_stats::{self, ColumnStats};
+use crate::computed::{self, ComputedColumn};
+use crate::config::{self, Config, EnvironmentPreset, ProfileGroup};
+use crate::console_url::{self, ConsoleLink, LinkRange};
+use crate::defaults::{default_app_values, AppDefaults, DEFAULT_QUERY};
+use crate::drill_down;
+use crate::durations;
+use crate::enrich::StreamEnricher;
+use crate::explain::{self, ExplainPane, ExplainRequest};
+use crate::export::{self, ExportRows, ExportTable};
+use crate::filter_clause;
+use crate::focus::FocusField;
+use crate::highlight::{self, CellRule, HighlightColor};
+use crate::hooks::QueryCompletion;
+use crate::i18n;
+use crate::input_panes::InputPane;
+use crate::key_macro::KeyMacro;
+use crate::keymap;
+use crate::links;
+use crate::log_fetcher::{
+    CallWatch, QueryEngine, QueryLimiter, QueryOutcome, QueryParams, QueuePlace, S3ExportRequest,
+};
+use crate::metadata_cache::{self, Metadata, MetadataKey};
+use crate::opensearch::IndexSource;
+use crate::palette::PaletteState;
+use crate::presentation::{format_modal_message, format_modal_value, large_cell_preview};
+use crate::query_diff::QueryDiff;
+use crate::query_docs;
+use crate::query_lint;
+use crate::relative_ranges::{
+    days, hours, minutes, relative_range_index, RelativeRangeOption, QUICK_RELATIVE_RANGES,
+    RELATIVE_RANGE_OPTIONS,
+};
+use crate::renderers::{self, RenderRequest, Rendered, RendererPlugin};
+use crate::resource_groups::{self, Resource, ResourceLookup};
+use crate::result_cache::{self, ResultCache};
+use crate::search_index::{IndexStop, SearchIndex};
+use crate::share::ShareSummary;
+use crate::snapshot::{ResultSnapshot, SnapshotQuery};
+use crate::snippets::{self, SnippetSession};
+use crate::spill::{SpillFile, SpillSlot};
+use crate::theme;
+use crate::time_format;
+use crate::timeline::{self, TimelineMarker};
+use crate::value_picker::{self, ValuePicker};
+use crate::variables;
 use crate::widgets::column_picker::ColumnPickerState;
+use crate::widgets::date_picker::DatePickerState;
+use crate::widgets::tab_bar::TabState;
 
+/// Typing pause before the table re-filters; the filter title previews the count meanwhile.
 pub const FILTER_DEBOUNCE_MS: u64 = 80;
+/// Result sets larger than this are filtered on a worker thread.
+const BACKGROUND_FILTER_ROWS: usize = 20_000;
+/// Rows a worker filters between checks for a newer filter.
+const FILTER_CANCEL_CHECK: usize = 1024;
+const TAB_FLASH_DURATION: Duration = Duration::from_secs(3);
+/// Characters of the explained message shown above its explanation.
+const EXPLAIN_SUBJECT_CHARS: usize = 120;
+/// Re-runs the query even when cached results exist.
+pub const REFRESH_KEY_HINT: &str = "Shift+F5";
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum FocusField {
-    AwsRegion,
-    AwsProfile,
-    TimeMode,
-    RelativeRange,
-    From,
-    To,
-    LogGroup,
-    Query,
-    Results,
-    Filter,
-}
-
+#[derive(Clone)]
 pub struct ResultRow {
-    pub cells: Vec<String>,
-    pub searchable: String,
+    /// Empty once the row is spilled to disk; read through `QueryResults::cells`.
+    cells: Vec<String>,
+    /// Lowercased text of the whole row, built the first time a filter needs it.
+    searchable: OnceLock<String>,
+    /// Table previews of cells over `LARGE_CELL_BYTES`, by column, built once
+    /// so drawing never copies the full text.
+    previews: HashMap<usize, String>,
+    spilled: Option<SpillSlot>,
 }
 
 impl ResultRow {
     fn new(cells: Vec<String>) -> Self {
-        let searchable = cells.join(" ").to_ascii_lowercase();
-        Self { cells, searchable }
+        let previews = cells
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cell)| Some((idx, large_cell_preview(cell)?)))
+            .collect();
+        Self {
+            cells,
+            searchable: OnceLock::new(),
+            previews,
+            spilled: None,
+        }
+    }
+
+    /// Truncated table text for a large cell; the modal still shows it in full.
+    pub fn preview(&self, column: usize) -> Option<&str> {
+        self.previews.get(&column).map(String::as_str)
+    }
+
+    /// Rough heap size of the row, counted against `query.results_memory_mb`.
+    /// Cells count twice to leave room for the filter's lowercased copy.
+    fn memory_bytes(&self) -> usize {
+        2 * self.cells.iter().map(String::len).sum::<usize>()
+            + self.previews.values().map(String::len).sum::<usize>()
+    }
+
+    /// Move the cells to `file`, keeping only the previews in memory.
+    fn spill(&mut self, file: &SpillFile) -> Result<(), String> {
+        self.spilled = Some(file.append(&self.cells)?);
+        self.cells = Vec::new();
+        self.searchable = OnceLock::new();
+        Ok(())
     }
 }
 
@@ -49,15 +134,406 @@ pub enum StatusKind {
     Error,
 }
 
-#[derive(Default)]
+/// What the production prompt sends once confirmed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PendingRun {
+    Query,
+    CloudTrail,
+    ValueSample,
+}
+
+/// Workspace-level command raised by a key or palette action and carried out by the UI loop.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceCommand {
+    Open,
+    Close,
+    Next,
+    Previous,
+    /// Jump to the next tab whose results arrived while it was in the background.
+    NextUnseen,
+    /// Open a tab running `App::drill_down` and switch to it.
+    DrillDown,
+}
+
+/// Status update sent back to the UI loop by a background task.
+pub struct Notice {
+    pub kind: StatusKind,
+    pub message: String,
+}
+
+impl Notice {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            kind: StatusKind::Info,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            kind: StatusKind::Error,
+            message: message.into(),
+        }
+    }
+}
+
+/// Work over a copy of the results on a worker thread. Dropping it cancels
+/// the work.
+pub struct RowsJob<T> {
+    /// The rows worked on, to spot results replaced or edited meanwhile.
+    rows: Arc<Vec<Arc<ResultRow>>>,
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<Option<T>>,
+}
+
+impl<T: Send + 'static> RowsJob<T> {
+    fn spawn(
+        results: &QueryResults,
+        work: impl FnOnce(QueryResults, &AtomicBool) -> Option<T> + Send + 'static,
+    ) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let copy = results.clone();
+        let worker_cancel = Arc::clone(&cancel);
+        Self {
+            rows: Arc::clone(&results.rows),
+            cancel,
+            handle: task::spawn_blocking(move || work(copy, &worker_cancel)),
+        }
+    }
+
+    /// `None` while running; once finished, how the work ended.
+    fn poll(&mut self, rows: &Arc<Vec<Arc<ResultRow>>>) -> Option<JobOutcome<T>> {
+        let outcome = (&mut self.handle).now_or_never()?;
+        Some(match outcome {
+            Err(_) => JobOutcome::Failed,
+            Ok(Some(value)) if Arc::ptr_eq(&self.rows, rows) => JobOutcome::Done(value),
+            Ok(_) => JobOutcome::Stale,
+        })
+    }
+}
+
+/// How a finished `RowsJob` ended.
+enum JobOutcome<T> {
+    Done(T),
+    /// Cancelled, or `rows` are no longer the ones it ran on; worth running
+    /// again for the current rows.
+    Stale,
+    /// The worker panicked; running it again would most likely do the same.
+    Failed,
+}
+
+impl<T> Drop for RowsJob<T> {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A filter running on a worker thread.
+pub struct FilterJob {
+    terms: FilterTerms,
+    job: RowsJob<Vec<usize>>,
+}
+
+/// Cloning is cheap: rows and the spill file are shared, so a background
+/// filter can work on a copy. Each row is shared on its own too, so editing
+/// one while a copy is held copies that row, not all of them.
+#[derive(Clone, Default)]
 pub struct QueryResults {
     pub headers: Vec<String>,
-    pub rows: Vec<ResultRow>,
+    /// Headers added by computed columns, after the ones the query returned.
+    computed: Vec<String>,
+    pub rows: Arc<Vec<Arc<ResultRow>>>,
+    /// Cells of the rows past the memory limit.
+    spill: Option<Arc<SpillFile>>,
+    /// The last filter run and the rows it matched, narrowed rather than
+    /// recomputed while the user keeps typing.
+    last_filter: Option<(FilterTerms, Vec<usize>)>,
+    /// Trigram index of the rows' filter text, once built.
+    index: Option<Arc<SearchIndex>>,
+    /// Bytes of `query.results_memory_mb` the rows left for the index.
+    index_budget: usize,
+    /// The index did not fit its budget, so it is not tried again until the
+    /// rows change; filters scan the rows instead.
+    index_skipped: bool,
+}
+
+/// Quick filter tokens: a row matches when it contains any include token (or
+/// there are none) and no exclude token.
+#[derive(Clone, PartialEq, Eq)]
+struct FilterTerms {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl FilterTerms {
+    fn parse(raw: &str, disabled: &HashSet<String>) -> Self {
+        let mut terms = Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+        };
+        for token in raw.split_whitespace() {
+            if disabled.contains(token) {
+                continue;
+            }
+            let (list, rest) = match (token.strip_prefix('+'), token.strip_prefix('-')) {
+                (Some(rest), _) => (&mut terms.include, rest),
+                (_, Some(rest)) => (&mut terms.exclude, rest),
+                _ => (&mut terms.include, token),
+            };
+            let normalized = rest.trim();
+            if !normalized.is_empty() {
+                list.push(normalized.to_ascii_lowercase());
+            }
+        }
+        terms
+    }
+
+    fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    fn matches(&self, haystack: &str) -> bool {
+        !self
+            .exclude
+            .iter()
+            .any(|token| haystack.contains(token.as_str()))
+            && (self.include.is_empty()
+                || self
+                    .include
+                    .iter()
+                    .any(|token| haystack.contains(token.as_str())))
+    }
+
+    /// True when every row these terms match was also matched by `previous`:
+    /// include tokens only grew longer (or appeared where there were none)
+    /// and exclude tokens were only added.
+    fn narrows(&self, previous: &Self) -> bool {
+        let includes_narrow = previous.include.is_empty()
+            || (self.include.len() == previous.include.len()
+                && self
+                    .include
+                    .iter()
+                    .zip(&previous.include)
+                    .all(|(token, before)| token.contains(before.as_str())));
+        includes_narrow
+            && previous
+                .exclude
+                .iter()
+                .all(|before| self.exclude.contains(before))
+    }
+}
+
+impl QueryResults {
+    /// Replace the rows, spilling every row past `memory_limit` bytes to a
+    /// temporary file; 0 keeps them all in memory. Returns why rows could
+    /// not be spilled, if they could not.
+    fn fill(
+        &mut self,
+        rows: impl IntoIterator<Item = Vec<String>>,
+        memory_limit: usize,
+    ) -> Option<String> {
+        self.spill = None;
+        self.last_filter = None;
+        self.index = None;
+        self.index_skipped = false;
+        let rows = rows.into_iter();
+        let mut kept = Vec::with_capacity(rows.size_hint().0);
+        let mut used = 0;
+        let mut failure = None;
+        for cells in rows {
+            let mut row = ResultRow::new(cells);
+            let over = memory_limit > 0 && used + row.memory_bytes() > memory_limit;
+            if over && failure.is_none() {
+                let spilled = match self.spill.as_ref() {
+                    Some(file) => row.spill(file),
+                    None => SpillFile::create()
+                        .and_then(|file| row.spill(self.spill.insert(Arc::new(file)))),
+                };
+                failure = spilled.err();
+            }
+            used += row.memory_bytes();
+            kept.push(Arc::new(row));
+        }
+        self.rows = Arc::new(kept);
+        self.index_budget = match memory_limit {
+            0 => usize::MAX,
+            limit => limit.saturating_sub(used),
+        };
+        failure
+    }
+
+    /// A row's cells, read back from disk when the row was spilled.
+    pub fn cells(&self, row: usize) -> Option<Cow<'_, [String]>> {
+        row_cells(self.rows.get(row)?, self.spill.as_deref())
+    }
+
+    /// Reads a row's cells like `cells`, without borrowing the results, so
+    /// exports can read rows (and the spill file) as they write them.
+    fn row_reader(&self) -> impl Fn(usize) -> Option<Vec<String>> + Send + Sync + 'static {
+        let rows = Arc::clone(&self.rows);
+        let spill = self.spill.clone();
+        move |row| row_cells(rows.get(row)?, spill.as_deref()).map(Cow::into_owned)
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Option<Cow<'_, str>> {
+        match self.cells(row)? {
+            Cow::Borrowed(cells) => cells.get(column).map(|cell| Cow::Borrowed(cell.as_str())),
+            Cow::Owned(mut cells) => {
+                (column < cells.len()).then(|| Cow::Owned(cells.swap_remove(column)))
+            }
+        }
+    }
+
+    /// Rows matching `terms` on a worker thread, for result sets too large
+    /// to filter between keystrokes.
+    fn spawn_filter(&self, terms: FilterTerms) -> FilterJob {
+        let worker_terms = terms.clone();
+        FilterJob {
+            terms,
+            job: RowsJob::spawn(self, move |results, cancel| {
+                results.matching(&worker_terms, cancel)
+            }),
+        }
+    }
+
+    /// Build a search index of the rows on a worker thread, from their
+    /// cells rather than the filter's lowercased copies, which would stay.
+    fn spawn_index(&self) -> RowsJob<Result<SearchIndex, IndexStop>> {
+        RowsJob::spawn(self, |results, cancel| {
+            Some(SearchIndex::build(
+                (0..results.rows.len()).map(|row| results.cells(row).unwrap_or_default()),
+                results.index_budget,
+                cancel,
+            ))
+        })
+    }
+
+    /// Rows holding every trigram of some include token, ascending; `None`
+    /// without an index or when a token is too short to look up.
+    fn indexed_candidates(&self, terms: &FilterTerms) -> Option<Vec<usize>> {
+        let index = self.index.as_ref()?;
+        let mut rows = Vec::new();
+        for token in &terms.include {
+            rows.extend(index.candidates(token)?);
+        }
+        if terms.include.len() > 1 {
+            rows.sort_unstable();
+            rows.dedup();
+        }
+        (!terms.include.is_empty()).then_some(rows)
+    }
+
+    /// Rows matching `terms`, scanning only the previous matches when the
+    /// terms narrow the last filter run.
+    fn filter(&mut self, terms: FilterTerms) -> Vec<usize> {
+        let matches = self
+            .matching(&terms, &AtomicBool::new(false))
+            .unwrap_or_default();
+        self.last_filter = Some((terms, matches.clone()));
+        matches
+    }
+
+    /// The rows `filter` would return; `None` once `cancel` is set.
+    fn matching(&self, terms: &FilterTerms, cancel: &AtomicBool) -> Option<Vec<usize>> {
+        if terms.is_empty() {
+            return Some((0..self.rows.len()).collect());
+        }
+        let narrowed = self
+            .last_filter
+            .as_ref()
+            .filter(|(previous, _)| terms.narrows(previous));
+        // Both are supersets of the answer; scan whichever is smaller.
+        let candidates: Box<dyn Iterator<Item = usize>> =
+            match (narrowed, self.indexed_candidates(terms)) {
+                (Some((_, matched)), Some(rows)) if rows.len() < matched.len() => {
+                    Box::new(rows.into_iter())
+                }
+                (Some((_, matched)), _) => Box::new(matched.iter().copied()),
+                (None, Some(rows)) => Box::new(rows.into_iter()),
+                (None, None) => Box::new(0..self.rows.len()),
+            };
+        let mut matches = Vec::new();
+        for (checked, row) in candidates.enumerate() {
+            if checked % FILTER_CANCEL_CHECK == 0 && cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            if terms.matches(&self.searchable(row)) {
+                matches.push(row);
+            }
+        }
+        Some(matches)
+    }
+
+    /// Lowercased text of the whole row, for the quick filter.
+    fn searchable(&self, row: usize) -> Cow<'_, str> {
+        match self.rows.get(row) {
+            Some(result) if result.spilled.is_some() => Cow::Owned(
+                self.cells(row)
+                    .map(|cells| cells.join(" ").to_ascii_lowercase())
+                    .unwrap_or_default(),
+            ),
+            Some(result) => Cow::Borrowed(
+                result
+                    .searchable
+                    .get_or_init(|| result.cells.join(" ").to_ascii_lowercase()),
+            ),
+            None => Cow::Borrowed(""),
+        }
+    }
+
+    /// Change every row's cells and previews in place. Rows shared with a
+    /// worker or the result cache are copied first, and spilled rows are
+    /// written to a fresh spill file, so repeated edits do not grow the old one.
+    fn update_rows(
+        &mut self,
+        mut change: impl FnMut(&mut Vec<String>, &mut HashMap<usize, String>),
+    ) {
+        self.last_filter = None;
+        self.index = None;
+        self.index_skipped = false;
+        let old_spill = self.spill.take();
+        // Without a new file the spilled rows just stay in memory.
+        let new_spill = old_spill
+            .is_some()
+            .then(SpillFile::create)
+            .and_then(Result::ok);
+        for result in Arc::make_mut(&mut self.rows).iter_mut() {
+            let Some(mut cells) = row_cells(result, old_spill.as_deref()).map(Cow::into_owned)
+            else {
+                continue;
+            };
+            let result = Arc::make_mut(result);
+            change(&mut cells, &mut result.previews);
+            result.searchable = OnceLock::new();
+            result.cells = cells;
+            if result.spilled.take().is_some() {
+                if let Some(file) = new_spill.as_ref() {
+                    let _ = result.spill(file);
+                }
+            }
+        }
+        self.spill = new_spill.map(Arc::new);
+    }
+
+    /// Every row and column, ignoring the filter and hidden columns.
+    pub fn export_table(&self) -> ExportTable {
+        ExportTable {
+            headers: self.headers.clone(),
+            rows: ExportRows::lazy(self.rows.len(), self.row_reader()),
+        }
+    }
+
+    pub fn spilled_rows(&self) -> usize {
+        self.rows.iter().filter(|row| row.spilled.is_some()).count()
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum SaveDialogMode {
     Save,
+    Snapshot,
+    Export,
+    S3Export,
 }
 
 pub struct SaveDialogState {
@@ -153,7 +629,14 @@ pub struct QueryFileEntry {
     pub searchable: String,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OpenDialogMode {
+    Query,
+    Snapshot,
+}
+
 pub struct OpenDialogState {
+    pub mode: OpenDialogMode,
     pub entries: Vec<QueryFileEntry>,
     pub filtered_indices: Vec<usize>,
     pub selected_filtered_index: Option<usize>,
@@ -162,8 +645,9 @@ pub struct OpenDialogState {
 }
 
 impl OpenDialogState {
-    pub fn new(entries: Vec<QueryFileEntry>) -> Self {
+    pub fn new(mode: OpenDialogMode, entries: Vec<QueryFileEntry>) -> Self {
         let mut state = Self {
+            mode,
             entries,
             filtered_indices: Vec::new(),
             selected_filtered_index: None,
@@ -250,7 +734,7 @@ impl OpenDialogState {
     }
 }
 
-fn resolve_default_region() -> String {
+pub fn resolve_default_region() -> String {
     fn env_region(key: &str) -> Option<String> {
         env::var(key)
             .ok()
@@ -263,109 +747,132 @@ fn resolve_default_region() -> String {
         .unwrap_or_else(|| "eu-west-1".to_string())
 }
 
-pub struct RelativeRangeOption {
-    pub label: &'static str,
-    pub seconds: i64,
+/// How `[`, `]`, `{` and `}` reshape the query window.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum WindowNudge {
+    Earlier,
+    Later,
+    Narrower,
+    Wider,
+}
+
+/// The profile picker's selection, kept by name so it stays put when the
+/// profiles are read again.
+#[derive(Clone, PartialEq, Eq)]
+pub enum ProfileChoice {
+    Profile(String),
+    Group(String),
+}
+
+/// Where the results on screen came from, kept after the status line moves on.
+#[derive(Clone)]
+pub struct LastRun {
+    pub finished_at: DateTime<Local>,
+    pub elapsed: Duration,
+    pub row_count: usize,
+    pub region: String,
+    pub log_group: String,
+    /// Inputs as they were when the query was submitted.
+    pub inputs: InputSnapshot,
+}
+
+/// The editable inputs that decide what a query returns.
+#[derive(Clone, PartialEq, Eq)]
+pub struct InputSnapshot {
+    query: String,
+    log_group: String,
+    region: String,
+    profile: Option<String>,
+    time_range: (bool, usize, String, String),
 }
 
-const fn minutes(value: i64) -> i64 {
-    value * 60
+impl InputSnapshot {
+    /// A relative window ends at submit time, so the same inputs later cover
+    /// a different range.
+    pub fn is_relative(&self) -> bool {
+        self.time_range.0
+    }
 }
 
-const fn hours(value: i64) -> i64 {
-    minutes(value * 60)
+impl LastRun {
+    pub fn summary(&self) -> String {
+        format!(
+            "Last run: {} · {:.1}s · {} rows · {}/{}",
+            self.finished_at.format("%H:%M:%S"),
+            self.elapsed.as_secs_f64(),
+            group_thousands(self.row_count),
+            self.region,
+            self.log_group
+        )
+    }
 }
 
-const fn days(value: i64) -> i64 {
-    hours(value * 24)
+/// `900` → `15m`, `86400` → `1d`.
+fn compact_duration(seconds: i64) -> String {
+    match seconds {
+        s if s % days(1) == 0 => format!("{}d", s / days(1)),
+        s if s % hours(1) == 0 => format!("{}h", s / hours(1)),
+        s if s % minutes(1) == 0 => format!("{}m", s / minutes(1)),
+        s => format!("{s}s"),
+    }
 }
 
-pub const RELATIVE_RANGE_OPTIONS: [RelativeRangeOption; 17] = [
-    RelativeRangeOption {
-        label: "1 minute",
-        seconds: minutes(1),
-    },
-    RelativeRangeOption {
-        label: "5 minutes",
-        seconds: minutes(5),
-    },
-    RelativeRangeOption {
-        label: "10 minutes",
-        seconds: minutes(10),
-    },
-    RelativeRangeOption {
-        label: "15 minutes",
-        seconds: minutes(15),
-    },
-    RelativeRangeOption {
-        label: "30 minutes",
-        seconds: minutes(30),
-    },
-    RelativeRangeOption {
-        label: "1 hour",
-        seconds: hours(1),
-    },
-    RelativeRangeOption {
-        label: "2 hours",
-        seconds: hours(2),
-    },
-    RelativeRangeOption {
-        label: "3 hours",
-        seconds: hours(3),
-    },
-    RelativeRangeOption {
-        label: "5 hours",
-        seconds: hours(5),
-    },
-    RelativeRangeOption {
-        label: "12 hours",
-        seconds: hours(12),
-    },
-    RelativeRangeOption {
-        label: "1 day",
-        seconds: days(1),
-    },
-    RelativeRangeOption {
-        label: "2 days",
-        seconds: days(2),
-    },
-    RelativeRangeOption {
-        label: "3 days",
-        seconds: days(3),
-    },
-    RelativeRangeOption {
-        label: "5 days",
-        seconds: days(5),
-    },
-    RelativeRangeOption {
-        label: "7 days",
-        seconds: days(7),
-    },
-    RelativeRangeOption {
-        label: "14 days",
-        seconds: days(14),
-    },
-    RelativeRangeOption {
-        label: "30 days",
-        seconds: days(30),
-    },
-];
+fn group_thousands(value: usize) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
 
 pub struct App {
+    pub config: Config,
+    pub linear_mode: bool,
+    pub keyboard_enhanced: bool,
+    /// Show Alt+letter jump hints in block titles.
+    pub show_mnemonics: bool,
+    /// Keys recorded with `q` in the results, replayed with `@`.
+    pub key_macro: KeyMacro,
     pub focus: FocusField,
     pub aws_profiles: Vec<String>,
-    pub selected_profile_index: Option<usize>,
+    /// Profiles are still being read from `~/.aws`; the picker says so.
+    pub profiles_loading: bool,
+    pub selected_profile: Option<ProfileChoice>,
     pub aws_region_input: SingleLineInput,
     pub inputs_collapsed: bool,
+    /// Input panes left out of the layout and the Tab order.
+    pub hidden_inputs: HashSet<InputPane>,
+    /// Dialog choosing which input panes are shown, while open.
+    pub input_pane_picker: Option<ColumnPickerState>,
+    /// The query editor fills the screen (F11); everything else waits.
+    pub query_zoomed: bool,
     pub relative_mode: bool,
     pub selected_relative_index: usize,
     pub from_input: SingleLineInput,
     pub to_input: SingleLineInput,
     pub log_group_input: SingleLineInput,
     pub query_area: TextArea<'static>,
+    /// Column caret across several query lines while block editing.
+    pub query_block: Option<BlockCaret>,
+    /// Tab stops left to visit in the last expanded snippet.
+    pub snippet_session: Option<SnippetSession>,
     pub query_scroll_row: u16,
     pub query_scroll_col: u16,
     pub saved_query_path: Option<PathBuf>,
+    /// Query text as last saved or loaded, to detect unsaved edits.
+    pub query_baseline: String,
+    /// The query is a configured default the app filled in, so it may follow
+    /// the profile and log group to another default. Query text from the
+    /// command line, stdin, a link, a snapshot, or the user clears it.
+    pub query_defaulted: bool,
+    /// Unified diff of the query against its saved file, while it is shown.
+    pub query_diff: Option<QueryDiff>,
+    pub last_query: Option<QueryParams>,
+    pub last_s3_destination: Option<String>,
     pub results: QueryResults,
     pub column_visibility: Vec<bool>,
     pub column_visibility_overrides: HashMap<String, bool>,
@@ -373,21 +880,144 @@ pub struct App {
     pub results_initialized: bool,
     pub status_kind: StatusKind,
     pub filtered_indices: Vec<usize>,
+    /// Show only every Nth filtered row; `None` is the full view.
+    pub sample_every: Option<usize>,
     pub filter_input: SingleLineInput,
+    /// Rows the edited filter would match and the total, until the debounce applies it.
+    pub filter_preview: Option<(usize, usize)>,
+    /// Filter token under the cursor while the chip row above the table has focus.
+    pub chip_cursor: Option<usize>,
+    /// Filter tokens switched off from the chip row; they stay in the filter text.
+    pub disabled_chips: HashSet<String>,
     pub filter_active: bool,
     pub filter_dirty: bool,
+    /// Filter running on a worker thread; replaced or dropped on newer edits.
+    pub filter_job: Option<FilterJob>,
+    /// Search index being built for the current results.
+    pub index_job: Option<RowsJob<Result<SearchIndex, IndexStop>>>,
     pub last_filter_edit: Option<Instant>,
     pub status: String,
     pub results_navigation: bool,
     pub selected_filtered_index: Option<usize>,
+    /// Result rows (indices into `results.rows`) marked as zoom endpoints.
+    pub marked_rows: Vec<usize>,
+    /// Notes on result rows, keyed by row index; saved with snapshots and added to exports.
+    pub row_notes: BTreeMap<usize, String>,
+    /// Deploys and other known events, drawn between the rows they fall between.
+    pub timeline_markers: Vec<TimelineMarker>,
+    /// Show duration values as `12.5 s` instead of the stored number.
+    pub humanize_durations: bool,
+    pub cell_rules: Vec<CellRule>,
+    /// Columns computed from each row, appended to every result set in order.
+    pub computed_columns: Vec<ComputedColumn>,
+    pub renderers: Arc<Vec<RendererPlugin>>,
+    /// Plugin rendering of the row the detail view was opened on, by row index.
+    pub modal_rendered: Option<(usize, Rendered)>,
+    /// Row to offer the renderer plugins, for the UI loop to run.
+    pub render_request: Option<RenderRequest>,
+    /// Free-form investigation notes for this workspace; saved with snapshots.
+    pub scratchpad: TextArea<'static>,
+    pub scratchpad_open: bool,
+    /// Row being annotated and the note text being edited.
+    pub note_input: Option<(usize, SingleLineInput)>,
     pub modal_open: bool,
+    /// Field under the cursor in the row detail modal.
+    pub modal_field: usize,
+    /// Show values exactly as stored instead of prettified.
+    pub modal_raw: bool,
+    pub modal_wrap: bool,
+    /// Columns scrolled right while wrapping is off.
+    pub modal_hscroll: u16,
     pub help_open: bool,
     pub results_scroll: usize,
     pub results_view_height: usize,
     pub submitting: bool,
+    /// When the in-flight query was submitted, for the spinner and elapsed time.
+    pub query_started_at: Option<Instant>,
+    pub query_limiter: Arc<QueryLimiter>,
+    /// Adds `[enrich]` columns to query results; shared by every workspace.
+    pub stream_enricher: Arc<StreamEnricher>,
+    /// Queue positions of the in-flight Insights query, one per profile it
+    /// runs under.
+    pub query_tickets: Vec<QueuePlace>,
+    /// Aborts the in-flight query task.
+    pub query_task: Option<AbortHandle>,
+    /// AWS calls made by the in-flight or last query.
+    pub query_watch: CallWatch,
+    /// Scroll offset of the last query trace popover, while it is open.
+    pub trace_scroll: Option<usize>,
+    pub last_run: Option<LastRun>,
+    pub submitted_inputs: Option<InputSnapshot>,
+    pub result_cache: ResultCache,
+    /// Set while the results on screen came from `result_cache`.
+    pub results_cached_at: Option<Instant>,
+    /// Suggestions for the profile and region in `metadata_key`.
+    pub metadata: Metadata,
+    pub metadata_key: Option<MetadataKey>,
+    /// Stale log group list waiting to be refreshed from AWS.
+    pub metadata_refresh: Option<MetadataKey>,
     pub column_modal: Option<ColumnPickerState>,
+    /// Statistics popover: the column index and its summary over the filtered rows.
+    pub column_stats: Option<(usize, ColumnStats)>,
+    /// Similar messages grouped by fingerprint, open as a popover.
+    pub clusters: Option<ClusterView>,
+    /// Message column and fingerprints of the run later results are compared with.
+    pub baseline: Option<(String, HashSet<String>)>,
+    /// Result rows whose message pattern the baseline run did not have.
+    pub new_pattern_rows: HashSet<usize>,
+    /// Prompt naming the field whose values to sample.
+    pub value_field_input: Option<SingleLineInput>,
+    /// Prompt for a `Name = expression` computed column.
+    pub computed_input: Option<SingleLineInput>,
+    pub value_picker: Option<ValuePicker>,
+    /// Sampling query waiting for the event loop to run it.
+    pub value_sample: Option<QueryParams>,
+    /// CloudTrail events beside the results, while the pane is open.
+    pub cloudtrail: Option<CloudTrailPane>,
+    /// CloudTrail query waiting for the event loop to run it, by number.
+    pub cloudtrail_request: Option<(u64, QueryParams)>,
+    /// CloudTrail queries sent so far, numbering the next one.
+    pub cloudtrail_requests: u64,
+    /// Explanation of the selected row's error beside the results.
+    pub explain: Option<ExplainPane>,
+    /// Redacted row waiting for the event loop to send it to `[explain]`.
+    pub explain_request: Option<ExplainRequest>,
+    /// Explain requests made so far, numbering the next one.
+    pub explain_requests: u64,
+    /// `fn:`/`ecs:` entry whose log groups the UI loop should look up.
+    pub resource_lookup: Option<ResourceLookup>,
     pub save_dialog: Option<SaveDialogState>,
     pub open_dialog: Option<OpenDialogState>,
+    pub palette: Option<PaletteState>,
+    /// Calendar popup editing the From or To field.
+    pub date_picker: Option<(FocusField, DatePickerState)>,
+    /// Pending "quit anyway?" question.
+    pub quit_prompt: Option<String>,
+    /// Pending confirmation before the session's first production query.
+    pub run_prompt: Option<String>,
+    /// What to send once `run_prompt` is confirmed.
+    pub pending_run: PendingRun,
+    /// Set once a production query was confirmed; shared by every workspace.
+    pub production_confirmed: Arc<AtomicBool>,
+    /// Account ID per profile, for matching `[production] accounts`.
+    pub profile_accounts: HashMap<String, String>,
+    pub workspace_command: Option<WorkspaceCommand>,
+    /// Tab title and raw-events query for the stats row being drilled into.
+    pub drill_down: Option<(String, QueryParams)>,
+    /// Values substituted for `{{name}}` in the query and log group when a query runs.
+    pub variables: BTreeMap<String, String>,
+    /// Dialog editing `variables` as `name = value; ...`.
+    pub variables_input: Option<SingleLineInput>,
+    /// Title set through the rename dialog; otherwise one is derived from the inputs.
+    pub workspace_name: Option<String>,
+    pub rename_input: Option<SingleLineInput>,
+    /// The last query in this workspace ended in an error.
+    pub query_failed: bool,
+    /// When results arrived while this workspace was in the background; cleared once viewed.
+    pub results_unseen_since: Option<Instant>,
+    /// Index into `config.presets` of the environment last switched to.
+    pub active_preset: Option<usize>,
+    pub query_engine: QueryEngine,
 }
 
 impl App {
@@ -418,6 +1048,9 @@ impl App {
     }
 
     fn focus_order(&self) -> Vec<FocusField> {
+        if self.query_zoomed {
+            return vec![FocusField::Query];
+        }
         let mut order = Vec::new();
         if !self.inputs_collapsed {
             order.push(FocusField::AwsRegion);
@@ -438,9 +1071,78 @@ impl App {
         if self.filter_active && !self.inputs_collapsed {
             order.push(FocusField::Filter);
         }
+        if self.scratchpad_open {
+            order.push(FocusField::Scratchpad);
+        }
+        order.retain(|&field| !self.is_hidden_input(field));
         order
     }
 
+    /// Whether `field` sits in an input pane the user has hidden.
+    pub fn is_hidden_input(&self, field: FocusField) -> bool {
+        InputPane::of(field).is_some_and(|pane| self.hidden_inputs.contains(&pane))
+    }
+
+    /// Move focus off a hidden pane, e.g. after a jump key or hiding it.
+    pub fn ensure_focus_shown(&mut self) {
+        if self.query_zoomed {
+            self.focus = FocusField::Query;
+        } else if self.is_hidden_input(self.focus) {
+            self.focus = FocusField::Results;
+        }
+    }
+
+    pub fn toggle_query_zoom(&mut self) {
+        self.query_zoomed = !self.query_zoomed;
+        if self.query_zoomed {
+            self.focus = FocusField::Query;
+            self.set_status(i18n::text("status.query_zoomed"));
+        } else if self.is_hidden_input(FocusField::Query) || self.inputs_collapsed {
+            self.focus = FocusField::Results;
+        }
+    }
+
+    pub fn open_input_pane_picker(&mut self) {
+        let shown = InputPane::ALL
+            .iter()
+            .map(|pane| !self.hidden_inputs.contains(pane))
+            .collect();
+        self.input_pane_picker = Some(ColumnPickerState::new(shown));
+    }
+
+    pub fn apply_input_pane_picker(&mut self) {
+        let Some(state) = self.input_pane_picker.take() else {
+            return;
+        };
+        self.hidden_inputs = InputPane::ALL
+            .into_iter()
+            .zip(state.into_selections())
+            .filter(|(_, shown)| !shown)
+            .map(|(pane, _)| pane)
+            .collect();
+        self.ensure_focus_shown();
+        self.set_status(match self.hidden_inputs.len() {
+            0 => "Showing every input pane".to_string(),
+            count => format!(
+                "{count} input panes hidden; Ctrl+K \"Show or hide input panes\" brings them back"
+            ),
+        });
+    }
+
+    /// Run binding shown in hints, e.g. `Ctrl+Enter/F5`.
+    pub fn run_key_hint(&self) -> String {
+        let primary = keymap::primary_run_chord(self.config.keys.run, self.keyboard_enhanced);
+        format!("{primary}/F5")
+    }
+
+    pub fn quit_key_hint(&self) -> &'static str {
+        if self.config.keys.esc_quits {
+            "Ctrl+Q/Esc"
+        } else {
+            "Ctrl+Q"
+        }
+    }
+
     pub fn set_status(&mut self, message: impl Into<String>) {
         self.status = message.into();
         self.status_kind = StatusKind::Info;
@@ -451,37 +1153,266 @@ impl App {
         self.status_kind = StatusKind::Error;
     }
 
+    pub fn apply_notice(&mut self, notice: Notice) {
+        match notice.kind {
+            StatusKind::Info => self.set_status(notice.message),
+            StatusKind::Error => self.set_error(notice.message),
+        }
+    }
+
     pub fn query_text(&self) -> String {
         self.query_area.lines().join("\n")
     }
 
     pub fn replace_query_text(&mut self, text: String) {
+        self.query_defaulted = false;
         self.query_area = TextArea::from(text.lines().map(|line| line.to_string()));
+        self.query_block = None;
+        self.snippet_session = None;
         self.query_scroll_row = 0;
         self.query_scroll_col = 0;
     }
 
+    pub fn scratchpad_text(&self) -> String {
+        self.scratchpad.lines().join("\n").trim_end().to_string()
+    }
+
+    /// Show the scratchpad and type into it, or hide it and return to the results.
+    pub fn toggle_scratchpad(&mut self) {
+        self.scratchpad_open = !self.scratchpad_open;
+        if self.scratchpad_open {
+            self.modal_open = false;
+            self.focus = FocusField::Scratchpad;
+        } else if self.focus == FocusField::Scratchpad {
+            self.focus = FocusField::Results;
+        }
+    }
+
     pub fn show_profile_picker(&self) -> bool {
-        !self.aws_profiles.is_empty()
+        self.profiles_loading
+            || !self.aws_profiles.is_empty()
+            || !self.config.profile_groups.is_empty()
+    }
+
+    /// Take the profiles read in the background, selecting `AWS_PROFILE`,
+    /// then `default`, then the first one.
+    pub fn apply_profile_discovery(&mut self, discovery: ProfileDiscovery) {
+        self.profiles_loading = false;
+        self.profile_accounts = discovery.accounts;
+        self.aws_profiles = discovery.profiles;
+        if self.selected_profile.is_none() && self.profile_choices() > 0 {
+            let env_profile = env::var("AWS_PROFILE").unwrap_or_default();
+            let listed = |name: &str| self.aws_profiles.iter().any(|p| p == name);
+            let name = [env_profile.trim(), "default"]
+                .into_iter()
+                .find(|name| listed(name));
+            self.selected_profile = match name {
+                Some(name) => Some(ProfileChoice::Profile(name.to_string())),
+                None => self.profile_choice(0),
+            };
+        }
+        self.apply_default_query();
+        self.sync_metadata();
     }
 
+    /// The selected profile; with a group selected, its first profile stands
+    /// in wherever a single profile is needed, such as suggestions and exports.
     pub fn selected_profile_name(&self) -> Option<&str> {
-        self.selected_profile_index
-            .and_then(|idx| self.aws_profiles.get(idx))
-            .map(|s| s.as_str())
+        match self.selected_profile.as_ref()? {
+            ProfileChoice::Profile(name) => Some(name),
+            ProfileChoice::Group(_) => self.selected_profile_group()?.profiles.first(),
+        }
+        .map(|s| s.as_str())
+    }
+
+    /// Profile group picked after the single profiles in the picker.
+    pub fn selected_profile_group(&self) -> Option<&ProfileGroup> {
+        let Some(ProfileChoice::Group(name)) = self.selected_profile.as_ref() else {
+            return None;
+        };
+        self.config
+            .profile_groups
+            .iter()
+            .find(|group| &group.name == name)
+    }
+
+    /// The picker entry at `idx`: profiles, then groups.
+    fn profile_choice(&self, idx: usize) -> Option<ProfileChoice> {
+        match idx.checked_sub(self.aws_profiles.len()) {
+            None => Some(ProfileChoice::Profile(self.aws_profiles[idx].clone())),
+            Some(group) => Some(ProfileChoice::Group(
+                self.config.profile_groups.get(group)?.name.clone(),
+            )),
+        }
+    }
+
+    /// Where the selected profile or group sits in the picker, if listed.
+    pub fn selected_profile_position(&self) -> Option<usize> {
+        match self.selected_profile.as_ref()? {
+            ProfileChoice::Profile(name) => self.aws_profiles.iter().position(|p| p == name),
+            ProfileChoice::Group(name) => self
+                .config
+                .profile_groups
+                .iter()
+                .position(|group| &group.name == name)
+                .map(|idx| self.aws_profiles.len() + idx),
+        }
+    }
+
+    /// Why `action` cannot run with a profile group selected: it works in one
+    /// account and is not fanned out over the group's profiles.
+    pub fn single_profile_required(&self, action: &str) -> Result<(), String> {
+        match self.selected_profile_group() {
+            Some(group) => Err(format!(
+                "{action} runs in one account; select a single profile instead of group {}",
+                group.name
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Entries in the profile picker: profiles, then groups.
+    pub fn profile_choices(&self) -> usize {
+        self.aws_profiles.len() + self.config.profile_groups.len()
+    }
+
+    /// Account of `profile` when the AWS config names one, else the profile.
+    pub fn profile_account(&self, profile: &str) -> String {
+        self.profile_accounts
+            .get(profile)
+            .cloned()
+            .unwrap_or_else(|| profile.to_string())
+    }
+
+    /// Selected profile for plain-text output, or why there is none.
+    pub fn profile_label(&self) -> &str {
+        if let Some(group) = self.selected_profile_group() {
+            return &group.name;
+        }
+        match self.selected_profile_name() {
+            Some(name) => name,
+            None if self.profiles_loading => "loading profiles…",
+            None => "auto",
+        }
     }
 
     pub fn move_profile_selection(&mut self, delta: i32) {
         if !self.show_profile_picker() {
             return;
         }
-        let len = self.aws_profiles.len() as i32;
+        let len = self.profile_choices() as i32;
         if len == 0 {
             return;
         }
-        let current = self.selected_profile_index.unwrap_or(0) as i32;
+        let current = self.selected_profile_position().unwrap_or(0) as i32;
         let next = (current + delta).clamp(0, len - 1);
-        self.selected_profile_index = Some(next as usize);
+        self.selected_profile = self.profile_choice(next as usize);
+        self.apply_default_query();
+        self.sync_metadata();
+    }
+
+    /// Switch the region field to the applied profile's region, keeping the
+    /// current one when the profile sets none. Returns the region to check
+    /// credentials in.
+    pub fn apply_profile_region(
+        &mut self,
+        profile: &str,
+        region: Option<String>,
+    ) -> Option<String> {
+        if let Some(region) = region {
+            self.aws_region_input = SingleLineInput::new(region);
+        }
+        let region = self.aws_region_input.value().trim().to_string();
+        if region.is_empty() {
+            self.set_error(i18n::fill(
+                "error.profile_without_region",
+                &[("profile", profile)],
+            ));
+            return None;
+        }
+        self.apply_default_query();
+        self.sync_metadata();
+        Some(region)
+    }
+
+    pub fn cycle_query_engine(&mut self) {
+        self.query_engine = self.query_engine.next();
+        self.set_status(i18n::fill(
+            "status.query_engine",
+            &[("engine", self.query_engine.label())],
+        ));
+    }
+
+    /// Switch to the next configured environment preset.
+    pub fn cycle_preset(&mut self) {
+        let count = self.config.presets.len();
+        if count == 0 {
+            self.set_status(i18n::text("status.no_presets"));
+            return;
+        }
+        let next = self.active_preset.map_or(0, |idx| (idx + 1) % count);
+        let preset = self.config.presets[next].clone();
+        self.active_preset = Some(next);
+        if let Some(region) = preset.region.as_ref() {
+            self.aws_region_input = SingleLineInput::new(region.clone());
+        }
+        if let Some(log_group) = preset.log_group.as_ref() {
+            self.log_group_input = SingleLineInput::new(log_group.clone());
+        }
+        if let Some(profile) = preset.profile.as_ref() {
+            if !self.aws_profiles.contains(profile) {
+                self.set_error(i18n::fill(
+                    "error.preset_profile_missing",
+                    &[("preset", &preset.name), ("profile", profile)],
+                ));
+                self.apply_default_query();
+                return;
+            }
+            self.selected_profile = Some(ProfileChoice::Profile(profile.clone()));
+        }
+        self.apply_default_query();
+        self.sync_metadata();
+        self.set_status(i18n::fill(
+            "status.preset_applied",
+            &[("preset", &preset.name)],
+        ));
+    }
+
+    /// The active preset, as long as the profile and region still match it.
+    pub fn current_preset(&self) -> Option<&EnvironmentPreset> {
+        let preset = self.config.presets.get(self.active_preset?)?;
+        let profile_matches = preset
+            .profile
+            .as_deref()
+            .is_none_or(|profile| self.selected_profile_name() == Some(profile));
+        let region_matches = preset
+            .region
+            .as_deref()
+            .is_none_or(|region| self.aws_region_input.value().trim() == region);
+        (profile_matches && region_matches).then_some(preset)
+    }
+
+    /// Load the configured default query for the current profile and log group,
+    /// unless the query was supplied from elsewhere or has been edited.
+    pub fn apply_default_query(&mut self) {
+        if !self.query_defaulted
+            || self.saved_query_path.is_some()
+            || self.query_has_unsaved_changes()
+        {
+            return;
+        }
+        let log_group = self.log_group_input.value().trim();
+        let query = self
+            .config
+            .default_query_for(self.selected_profile_name(), log_group)
+            .unwrap_or(DEFAULT_QUERY)
+            .to_string();
+        if query.trim() == self.query_text().trim() {
+            return;
+        }
+        self.replace_query_text(query);
+        self.query_baseline = self.query_text();
+        self.query_defaulted = true;
     }
 
     pub fn relative_options(&self) -> &'static [RelativeRangeOption] {
@@ -510,6 +1441,23 @@ impl App {
         self.selected_relative_index = next as usize;
     }
 
+    /// Switch to the numbered quick range without moving focus; returns its label.
+    pub fn select_quick_range(&mut self, slot: usize) -> Option<&'static str> {
+        let seconds = *QUICK_RELATIVE_RANGES.get(slot)?;
+        let index = self
+            .relative_options()
+            .iter()
+            .position(|option| option.seconds == seconds)?;
+        let focus = self.focus;
+        self.set_relative_mode(true);
+        self.selected_relative_index = index;
+        self.focus = match focus {
+            FocusField::From | FocusField::To => FocusField::RelativeRange,
+            other => other,
+        };
+        Some(self.current_relative_option().label)
+    }
+
     pub fn toggle_relative_mode(&mut self) {
         let new_value = !self.relative_mode;
         self.set_relative_mode(new_value);
@@ -527,7 +1475,12 @@ impl App {
                 self.focus = FocusField::RelativeRange;
             }
         } else {
-            self.refresh_absolute_range();
+            // Keep a previously typed window; the relative selection is likewise
+            // left untouched so toggling back restores it.
+            if self.from_input.value().trim().is_empty() || self.to_input.value().trim().is_empty()
+            {
+                self.refresh_absolute_range();
+            }
             if !self.inputs_collapsed {
                 self.focus = FocusField::From;
             }
@@ -537,21 +1490,61 @@ impl App {
     fn refresh_absolute_range(&mut self) {
         let now = Local::now();
         let start = now - ChronoDuration::days(1);
-        let from = start.format("%Y-%m-%d %H:%M:%S").to_string();
-        let to = now.format("%Y-%m-%d %H:%M:%S").to_string();
+        let from = time_format::format(start.naive_local(), false);
+        let to = time_format::format(now.naive_local(), false);
         self.from_input = SingleLineInput::new(from);
         self.to_input = SingleLineInput::new(to);
     }
 
-    pub fn set_results(&mut self, data: FormattedResults) {
+    /// Show new results, spilling rows past `query.results_memory_mb` to
+    /// disk as they are taken from `rows`.
+    pub fn set_results(
+        &mut self,
+        headers: Vec<String>,
+        rows: impl IntoIterator<Item = Vec<String>>,
+    ) {
+        let mut results = QueryResults {
+            headers,
+            ..QueryResults::default()
+        };
+        let memory_limit = self.config.query.results_memory_mb as usize * 1024 * 1024;
+        let failure = results.fill(rows, memory_limit);
+        self.show_results(results);
+        if let Some(err) = failure {
+            self.set_error(i18n::fill("error.rows_kept_in_memory", &[("err", &err)]));
+        }
+    }
+
+    fn show_results(&mut self, results: QueryResults) {
         self.results_navigation = false;
         self.selected_filtered_index = None;
+        self.marked_rows.clear();
+        self.row_notes.clear();
+        self.note_input = None;
+        self.column_stats = None;
+        self.clusters = None;
         self.modal_open = false;
         self.column_modal = None;
         self.save_dialog = None;
         self.open_dialog = None;
-        self.results.headers = data.headers;
-        self.results.rows = data.rows.into_iter().map(ResultRow::new).collect();
+        self.results_cached_at = None;
+        self.results = results;
+        let mut clashes = Vec::new();
+        for column in &self.computed_columns {
+            if !add_computed_cells(&mut self.results, column) {
+                clashes.push(column.name.clone());
+            }
+        }
+        if !clashes.is_empty() {
+            self.set_error(i18n::fill(
+                "error.computed_clashes",
+                &[("columns", &clashes.join(", "))],
+            ));
+        }
+        self.refresh_new_pattern_rows();
+        let auto_above = self.config.sampling.auto_above;
+        self.sample_every = (auto_above > 0 && self.results.rows.len() > auto_above)
+            .then(|| self.config.sampling.every.max(2));
         self.sync_column_visibility();
         self.results_initialized = true;
         self.apply_filter_now();
@@ -589,12 +1582,20 @@ impl App {
         self.filtered_indices.clear();
         self.results_navigation = false;
         self.selected_filtered_index = None;
+        self.marked_rows.clear();
+        self.row_notes.clear();
+        self.note_input = None;
+        self.column_stats = None;
+        self.clusters = None;
         self.modal_open = false;
         self.column_modal = None;
         self.results_scroll = 0;
         self.results_view_height = self.results_view_height.max(1);
         self.results_initialized = false;
         self.column_visibility.clear();
+        self.last_run = None;
+        self.results_cached_at = None;
+        self.new_pattern_rows.clear();
     }
 
     pub fn activate_filter(&mut self) {
@@ -606,78 +1607,697 @@ impl App {
 
     pub fn schedule_filter_update(&mut self) {
         self.filter_dirty = true;
+        self.filter_job = None;
         self.last_filter_edit = Some(Instant::now());
+        let tokens: HashSet<&str> = self.filter_input.value().split_whitespace().collect();
+        self.disabled_chips
+            .retain(|token| tokens.contains(token.as_str()));
+        // Counting a huge result set per keystroke would stall typing.
+        self.filter_preview = (self.results.rows.len() <= BACKGROUND_FILTER_ROWS)
+            .then(|| (self.filter_matches().len(), self.results.rows.len()));
     }
 
-    pub fn apply_filter_now(&mut self) {
-        self.filter_dirty = false;
-        let total_rows = self.results.rows.len();
-        if total_rows == 0 {
-            self.filtered_indices.clear();
-            self.exit_results_navigation();
-            return;
+    /// Filter tokens shown as chips above the table, with whether each applies.
+    pub fn filter_chips(&self) -> Vec<(&str, bool)> {
+        if !self.filter_active {
+            return Vec::new();
         }
+        self.filter_input
+            .value()
+            .split_whitespace()
+            .map(|token| (token, !self.disabled_chips.contains(token)))
+            .collect()
+    }
 
-        let raw_filter = self.filter_input.value();
-        let mut include_tokens: Vec<String> = Vec::new();
-        let mut exclude_tokens: Vec<String> = Vec::new();
+    pub fn focus_chips(&mut self) {
+        if self.filter_chips().is_empty() {
+            self.set_status(i18n::text("status.no_filter_terms"));
+        } else {
+            self.chip_cursor = Some(0);
+        }
+    }
 
-        for token in raw_filter.split_whitespace() {
-            if let Some(rest) = token.strip_prefix('+') {
-                let normalized = rest.trim();
-                if !normalized.is_empty() {
-                    include_tokens.push(normalized.to_ascii_lowercase());
-                }
-            } else if let Some(rest) = token.strip_prefix('-') {
-                let normalized = rest.trim();
-                if !normalized.is_empty() {
-                    exclude_tokens.push(normalized.to_ascii_lowercase());
-                }
-            } else {
-                let normalized = token.trim();
-                if !normalized.is_empty() {
-                    include_tokens.push(normalized.to_ascii_lowercase());
-                }
-            }
+    pub fn move_chip_cursor(&mut self, delta: i32) {
+        let len = self.filter_chips().len();
+        if let Some(cursor) = self.chip_cursor.as_mut().filter(|_| len > 0) {
+            *cursor = (*cursor as i32 + delta).clamp(0, len as i32 - 1) as usize;
+        }
+    }
+
+    /// Switch the chip under the cursor off or back on.
+    pub fn toggle_chip(&mut self) {
+        let Some(token) = self.chip_token() else {
+            return;
+        };
+        if !self.disabled_chips.remove(&token) {
+            self.disabled_chips.insert(token);
+        }
+        self.apply_filter_now();
+    }
+
+    /// Turn an include chip into an exclude chip and back.
+    pub fn flip_chip(&mut self) {
+        let Some(cursor) = self.chip_cursor else {
+            return;
+        };
+        let mut tokens = self.filter_tokens();
+        let Some(token) = tokens.get_mut(cursor) else {
+            return;
+        };
+        let was_disabled = self.disabled_chips.remove(token.as_str());
+        *token = match token.strip_prefix('-') {
+            Some(rest) => format!("+{rest}"),
+            None => format!("-{}", token.trim_start_matches('+')),
+        };
+        if was_disabled {
+            self.disabled_chips.insert(token.clone());
         }
+        self.set_filter_tokens(&tokens);
+    }
 
-        if include_tokens.is_empty() && exclude_tokens.is_empty() {
-            self.filtered_indices = (0..total_rows).collect();
+    pub fn remove_chip(&mut self) {
+        let Some(cursor) = self.chip_cursor else {
+            return;
+        };
+        let mut tokens = self.filter_tokens();
+        if cursor >= tokens.len() {
+            return;
+        }
+        tokens.remove(cursor);
+        self.chip_cursor = if tokens.is_empty() {
+            None
         } else {
-            self.filtered_indices = self
-                .results
-                .rows
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, row)| {
-                    let haystack = &row.searchable;
-                    if exclude_tokens.iter().any(|token| haystack.contains(token)) {
-                        return None;
-                    }
-                    if include_tokens.is_empty()
-                        || include_tokens.iter().any(|token| haystack.contains(token))
-                    {
-                        Some(idx)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+            Some(cursor.min(tokens.len() - 1))
+        };
+        self.set_filter_tokens(&tokens);
+    }
+
+    /// Move the switched-on filter terms into the query as `filter` stages and
+    /// clear the table filter; false when there were no terms to move.
+    pub fn push_filter_to_query(&mut self) -> bool {
+        let terms: Vec<String> = self
+            .filter_chips()
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(token, _)| token.to_string())
+            .collect();
+        let stages = filter_clause::from_terms(terms.iter().map(String::as_str));
+        if stages.is_empty() {
+            self.set_status(i18n::text("status.no_filter_terms_to_apply"));
+            return false;
+        }
+        let query = filter_clause::insert_stages(&self.query_text(), &stages);
+        self.replace_query_text(query);
+        self.filter_input = SingleLineInput::new(String::new());
+        self.disabled_chips.clear();
+        self.chip_cursor = None;
+        self.apply_filter_now();
+        true
+    }
+
+    fn chip_token(&self) -> Option<String> {
+        self.filter_tokens().get(self.chip_cursor?).cloned()
+    }
+
+    fn filter_tokens(&self) -> Vec<String> {
+        self.filter_input
+            .value()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn set_filter_tokens(&mut self, tokens: &[String]) {
+        self.filter_input = SingleLineInput::new(tokens.join(" "));
+        let remaining: HashSet<&str> = tokens.iter().map(String::as_str).collect();
+        self.disabled_chips
+            .retain(|token| remaining.contains(token.as_str()));
+        self.apply_filter_now();
+    }
+
+    pub fn apply_filter_now(&mut self) {
+        self.filter_dirty = false;
+        self.filter_job = None;
+        self.filter_preview = None;
+        if self.results.rows.is_empty() {
+            self.filtered_indices.clear();
+            self.exit_results_navigation();
+            return;
         }
+        let matches = self.filter_matches();
+        self.show_filtered(matches);
+    }
 
+    fn show_filtered(&mut self, matches: Vec<usize>) {
+        self.filtered_indices = match self.sample_every {
+            Some(every) => matches.into_iter().step_by(every).collect(),
+            None => matches,
+        };
         self.sync_selection_after_filter();
     }
 
-    pub fn on_tick(&mut self) {
+    /// Start filtering on a worker thread; the table keeps the previous
+    /// matches until `finish_filter_job` swaps the new ones in.
+    fn start_filter_job(&mut self) {
+        self.filter_dirty = false;
+        self.filter_preview = None;
+        let terms = FilterTerms::parse(self.filter_input.value(), &self.disabled_chips);
+        self.filter_job = Some(self.results.spawn_filter(terms));
+    }
+
+    /// Apply a finished worker filter; returns true when one was applied.
+    fn finish_filter_job(&mut self) -> bool {
+        let Some(outcome) = self
+            .filter_job
+            .as_mut()
+            .and_then(|filter| filter.job.poll(&self.results.rows))
+        else {
+            return false;
+        };
+        let Some(filter) = self.filter_job.take() else {
+            return false;
+        };
+        match outcome {
+            JobOutcome::Done(matches) => {
+                self.results.last_filter = Some((filter.terms, matches.clone()));
+                self.show_filtered(matches);
+            }
+            // Rows edited meanwhile leave the matches stale; filter them
+            // again on a worker rather than blocking on every row here.
+            JobOutcome::Stale => self.start_filter_job(),
+            // Keep the rows shown until the filter text changes again.
+            JobOutcome::Failed => self.set_error(i18n::text("error.filter_failed")),
+        }
+        true
+    }
+
+    /// Index results large enough to be worth it, one build at a time;
+    /// edited or replaced results drop their index and get a new one.
+    fn sync_search_index(&mut self) {
+        if let Some(outcome) = self
+            .index_job
+            .as_mut()
+            .and_then(|job| job.poll(&self.results.rows))
+        {
+            self.index_job = None;
+            match outcome {
+                JobOutcome::Done(Ok(index)) => self.results.index = Some(Arc::new(index)),
+                JobOutcome::Done(Err(IndexStop::OverBudget)) => self.results.index_skipped = true,
+                // Filters scan the rows instead until the results change.
+                JobOutcome::Failed => {
+                    self.results.index_skipped = true;
+                    self.set_error(i18n::text("error.index_failed"));
+                }
+                // Edited or replaced meanwhile; build again for the new rows.
+                JobOutcome::Done(Err(IndexStop::Cancelled)) | JobOutcome::Stale => {}
+            }
+        }
+        let threshold = self.config.query.search_index_rows;
+        let wanted = threshold > 0
+            && self.results.rows.len() >= threshold
+            && self.results.index.is_none()
+            && !self.results.index_skipped;
+        if !wanted {
+            self.index_job = None;
+        } else if self.index_job.is_none() {
+            self.index_job = Some(self.results.spawn_index());
+        }
+    }
+
+    /// Result rows matching the filter text's +include and -exclude tokens.
+    fn filter_matches(&mut self) -> Vec<usize> {
+        let terms = FilterTerms::parse(self.filter_input.value(), &self.disabled_chips);
+        self.results.filter(terms)
+    }
+
+    /// Run deferred work; returns true when the screen needs a redraw.
+    pub fn on_tick(&mut self) -> bool {
+        self.sync_search_index();
+        if self.finish_filter_job() {
+            return true;
+        }
         if self.filter_dirty {
             let ready = self
                 .last_filter_edit
                 .map(|instant| instant.elapsed() >= Duration::from_millis(FILTER_DEBOUNCE_MS))
                 .unwrap_or(true);
             if ready {
-                self.apply_filter_now();
+                if self.results.rows.len() > BACKGROUND_FILTER_ROWS {
+                    self.start_filter_job();
+                } else {
+                    self.apply_filter_now();
+                }
+                return true;
+            }
+        }
+        // Keep the spinner, elapsed counter, and tab flash moving.
+        self.submitting || self.tab_flashing()
+    }
+
+    pub fn begin_submission(&mut self) {
+        self.submitting = true;
+        self.query_failed = false;
+        self.query_started_at = Some(Instant::now());
+        self.submitted_inputs = Some(self.input_snapshot());
+        self.sync_metadata();
+    }
+
+    /// Load cached suggestions for the current profile and region, queueing a
+    /// refresh when the copy on disk is older than the configured TTL.
+    pub fn sync_metadata(&mut self) {
+        let region = self.aws_region_input.value().trim();
+        // Without the profile the cache key is unknown; discovery syncs again.
+        if region.is_empty() || self.profiles_loading {
+            return;
+        }
+        // One profile's log groups and fields would be wrong for the others.
+        if self.selected_profile_group().is_some() {
+            self.metadata = Metadata::default();
+            self.metadata_key = None;
+            return;
+        }
+        let key = MetadataKey {
+            profile: self.selected_profile_name().map(str::to_string),
+            region: region.to_string(),
+        };
+        if self.metadata_key.as_ref() == Some(&key) {
+            return;
+        }
+        self.metadata = metadata_cache::load(&key);
+        let ttl = Duration::from_secs(self.config.cache.metadata_ttl_hours * 3600);
+        if !self.metadata.is_fresh(ttl) {
+            self.metadata_refresh = Some(key.clone());
+        }
+        self.metadata_key = Some(key);
+    }
+
+    pub fn take_metadata_refresh(&mut self) -> Option<MetadataKey> {
+        self.metadata_refresh.take()
+    }
+
+    /// Store a fresh log group list; suggestions are best-effort, so failures are dropped.
+    pub fn apply_log_group_listing(
+        &mut self,
+        key: MetadataKey,
+        listing: Result<Vec<String>, String>,
+    ) {
+        let Ok(log_groups) = listing else {
+            return;
+        };
+        let current = self.metadata_key.as_ref() == Some(&key);
+        // The profile or region may have changed meanwhile; the list still belongs on disk.
+        let mut metadata = if current {
+            std::mem::take(&mut self.metadata)
+        } else {
+            metadata_cache::load(&key)
+        };
+        metadata.log_groups = log_groups;
+        metadata.fetched_at = Utc::now().timestamp();
+        let _ = metadata_cache::save(&key, &metadata);
+        if current {
+            self.metadata = metadata;
+        }
+    }
+
+    /// Remember the columns of the results on screen for field completion.
+    pub fn remember_result_fields(&mut self) {
+        let Some(key) = self.metadata_key.as_ref() else {
+            return;
+        };
+        if self.metadata.add_fields(&self.results.headers) {
+            let _ = metadata_cache::save(key, &self.metadata);
+        }
+    }
+
+    /// Rest of the first cached log group that extends what has been typed.
+    pub fn log_group_completion(&self) -> Option<&str> {
+        let value = self.log_group_input.value();
+        if self.log_group_input.cursor() != value.chars().count() {
+            return None;
+        }
+        // In a list, only the entry being typed is completed.
+        let typed = value.rsplit(',').next().unwrap_or(value).trim_start();
+        if typed.is_empty() {
+            return None;
+        }
+        self.metadata
+            .log_groups
+            .iter()
+            .find_map(|group| group.strip_prefix(typed).filter(|rest| !rest.is_empty()))
+            .or_else(|| resource_groups::suggestion(typed, &self.metadata.log_groups))
+    }
+
+    /// Look up the log groups of a complete `fn:` or `ecs:` entry at the end
+    /// of the field, to put them in its place.
+    fn start_resource_lookup(&mut self) -> bool {
+        let value = self.log_group_input.value();
+        if self.log_group_input.cursor() != value.chars().count() {
+            return false;
+        }
+        let typed = value.rsplit(',').next().unwrap_or(value);
+        let Some(resource) = Resource::parse(typed) else {
+            return false;
+        };
+        let region = self.aws_region_input.value().trim().to_string();
+        if region.is_empty() {
+            self.set_error(i18n::text("error.region_required"));
+            return true;
+        }
+        self.set_status(i18n::fill(
+            "status.resource_lookup",
+            &[("resource", &resource.to_string())],
+        ));
+        self.resource_lookup = Some(ResourceLookup {
+            resource,
+            region,
+            profile: self.selected_profile_name().map(str::to_string),
+        });
+        true
+    }
+
+    /// Put the groups a lookup found in place of its entry, if the field
+    /// still holds it.
+    pub fn apply_resource_lookup(
+        &mut self,
+        resource: Resource,
+        outcome: Result<Vec<String>, String>,
+    ) {
+        let groups = match outcome {
+            Ok(groups) => groups,
+            Err(err) => return self.set_error(err),
+        };
+        let entries: Vec<String> = self
+            .log_group_input
+            .value()
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+            .collect();
+        if !entries
+            .iter()
+            .any(|entry| Resource::parse(entry).as_ref() == Some(&resource))
+        {
+            return;
+        }
+        let mut value: Vec<String> = Vec::new();
+        for entry in entries {
+            let found = if Resource::parse(&entry).as_ref() == Some(&resource) {
+                groups.clone()
+            } else {
+                vec![entry]
+            };
+            for name in found {
+                if !value.contains(&name) {
+                    value.push(name);
+                }
+            }
+        }
+        self.log_group_input = SingleLineInput::new(value.join(", "));
+        self.apply_default_query();
+        self.set_status(i18n::fill(
+            "status.resource_groups",
+            &[
+                ("resource", &resource.to_string()),
+                ("groups", &groups.join(", ")),
+            ],
+        ));
+    }
+
+    /// Entries in the Log group field, which holds one name or a comma-separated list.
+    pub fn log_group_count(&self) -> usize {
+        self.log_group_input
+            .value()
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .count()
+    }
+
+    /// Merge a pasted list of log groups (one per line, or comma/tab/semicolon separated)
+    /// into the field, dropping duplicates and entries that cannot be log group names.
+    pub fn paste_log_groups(&mut self, text: &str) {
+        let mut names: Vec<String> = self
+            .log_group_input
+            .value()
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let before = names.len();
+        let mut skipped = 0;
+        for entry in text.split(['\n', '\r', ',', ';', '\t']) {
+            let entry = entry.trim().trim_matches(['"', '\'']).trim();
+            if entry.is_empty() {
+                continue;
+            }
+            if !is_log_group_name(entry) {
+                skipped += 1;
+                continue;
+            }
+            if !names.iter().any(|name| name == entry) {
+                names.push(entry.to_string());
             }
         }
+        self.log_group_input = SingleLineInput::new(names.join(", "));
+        let added = names.len() - before;
+        let mut status = format!("Added {added} log groups ({} in the list)", names.len());
+        if skipped > 0 {
+            let _ = write!(
+                status,
+                "; skipped {skipped} entries that are not log group names"
+            );
+        }
+        self.set_status(status);
+    }
+
+    pub fn accept_log_group_completion(&mut self) -> bool {
+        if self.log_group_completion().is_none() {
+            return self.start_resource_lookup();
+        }
+        let Some(rest) = self.log_group_completion() else {
+            return false;
+        };
+        let value = format!("{}{rest}", self.log_group_input.value());
+        self.log_group_input = SingleLineInput::new(value);
+        self.apply_default_query();
+        true
+    }
+
+    /// Complete the field name before the query cursor from fields seen in earlier results.
+    pub fn complete_query_field(&mut self) {
+        let (row, col) = self.query_area.cursor();
+        let before: Vec<char> = self.query_area.lines()[row].chars().take(col).collect();
+        let start = before
+            .iter()
+            .rposition(|c| !(c.is_alphanumeric() || matches!(c, '@' | '_' | '.' | '$')))
+            .map_or(0, |idx| idx + 1);
+        let prefix: String = before[start..].iter().collect();
+        if prefix.is_empty() {
+            self.set_status(i18n::text("status.field_prefix_required"));
+            return;
+        }
+        let matches: Vec<&String> = self
+            .metadata
+            .fields
+            .iter()
+            .filter(|field| field.len() > prefix.len() && field.starts_with(&prefix))
+            .collect();
+        let Some(first) = matches.first() else {
+            self.set_status(i18n::fill("status.no_field_match", &[("prefix", &prefix)]));
+            return;
+        };
+        // Extend to the longest prefix every match shares.
+        let common = matches.iter().fold(first.as_str(), |common, field| {
+            let shared = common
+                .char_indices()
+                .zip(field.chars())
+                .take_while(|((_, a), b)| a == b)
+                .last()
+                .map_or(0, |((idx, c), _)| idx + c.len_utf8());
+            &common[..shared]
+        });
+        let rest = common[prefix.len()..].to_string();
+        self.query_area.insert_str(rest);
+        if matches.len() > 1 {
+            let names: Vec<&str> = matches.iter().take(6).map(|field| field.as_str()).collect();
+            self.set_status(i18n::fill(
+                "status.field_matches",
+                &[("fields", &names.join(", "))],
+            ));
+        }
+    }
+
+    fn input_snapshot(&self) -> InputSnapshot {
+        let (from, to) = if self.relative_mode {
+            (String::new(), String::new())
+        } else {
+            (
+                self.from_input.value().trim().to_string(),
+                self.to_input.value().trim().to_string(),
+            )
+        };
+        InputSnapshot {
+            query: self.expanded_query().trim().to_string(),
+            log_group: self.log_group_input.value().trim().to_string(),
+            region: self.aws_region_input.value().trim().to_string(),
+            // A group's results are not its first profile's.
+            profile: match self.selected_profile_group() {
+                Some(group) => Some(format!("group {}", group.name)),
+                None => self.selected_profile_name().map(str::to_string),
+            },
+            time_range: (self.relative_mode, self.selected_relative_index, from, to),
+        }
+    }
+
+    /// True when the inputs were edited after the results on screen were fetched.
+    pub fn results_stale(&self) -> bool {
+        !self.submitting
+            && self
+                .last_run
+                .as_ref()
+                .is_some_and(|run| run.inputs != self.input_snapshot())
+    }
+
+    /// Mark the query as finished and return how long it ran.
+    pub fn finish_submission(&mut self) -> Option<Duration> {
+        self.submitting = false;
+        self.query_tickets.clear();
+        self.query_task = None;
+        self.query_started_at
+            .take()
+            .map(|started| started.elapsed())
+    }
+
+    /// Remember what produced the current results; call after `set_results`.
+    pub fn record_last_run(&mut self, elapsed: Option<Duration>) {
+        let inputs = self.submitted_inputs.take();
+        self.last_run = self
+            .last_query
+            .as_ref()
+            .zip(inputs)
+            .map(|(params, inputs)| LastRun {
+                finished_at: Local::now(),
+                elapsed: elapsed.unwrap_or_default(),
+                row_count: self.results.rows.len(),
+                region: params.region.clone(),
+                log_group: params.log_group.clone(),
+                inputs,
+            });
+    }
+
+    /// Keep the results just recorded by `record_last_run` for identical re-runs.
+    pub fn cache_results(&mut self, warning: Option<String>) {
+        if !self.result_cache.is_enabled() {
+            return;
+        }
+        let Some(last_run) = self
+            .last_run
+            .clone()
+            .filter(|run| !run.inputs.is_relative())
+        else {
+            return;
+        };
+        // The cache shares the rows (and spill file) rather than copying them.
+        self.result_cache
+            .insert(self.results.clone(), last_run, warning);
+    }
+
+    /// Show cached results when the current inputs ran recently; false on a miss.
+    pub fn apply_cached_results(&mut self, params: &QueryParams) -> bool {
+        let inputs = self.input_snapshot();
+        if inputs.is_relative() {
+            return false;
+        }
+        let Some(cached) = self.result_cache.get(&inputs).cloned() else {
+            return false;
+        };
+        self.last_query = Some(params.clone());
+        self.show_results(cached.results);
+        self.last_run = Some(cached.last_run);
+        self.results_cached_at = Some(cached.stored_at);
+        let status = format!(
+            "Showing results cached {} ago ({} re-runs the query).",
+            result_cache::format_age(cached.stored_at.elapsed()),
+            REFRESH_KEY_HINT
+        );
+        match cached.warning {
+            Some(warning) => self.set_error(format!("{status} {warning}")),
+            None => self.set_status(status),
+        }
+        true
+    }
+
+    /// Badge text while cached results are on screen, e.g. `cached 2m ago`.
+    pub fn cached_results_badge(&self) -> Option<String> {
+        self.results_cached_at.map(|stored_at| {
+            format!(
+                "cached {} ago",
+                result_cache::format_age(stored_at.elapsed())
+            )
+        })
+    }
+
+    /// Spinner plus elapsed seconds while a query runs, e.g. `⠹ Running 3.4s`.
+    pub fn submission_progress(&self) -> Option<String> {
+        if !self.submitting {
+            return None;
+        }
+        let elapsed = self
+            .query_started_at
+            .map(|started| started.elapsed())
+            .unwrap_or_default();
+        if let Some(waited) = self.query_stalled() {
+            let seconds = waited.as_secs().to_string();
+            return Some(format!(
+                "{} {}",
+                theme::spinner_frame(elapsed),
+                i18n::fill("status.aws_waiting", &[("seconds", &seconds)])
+            ));
+        }
+        // A group's query waits on its last profile to be admitted.
+        let state = match self
+            .query_tickets
+            .iter()
+            .filter_map(|&ticket| self.query_limiter.queued_ahead(ticket))
+            .max()
+        {
+            Some(ahead) => format!("Queued ({ahead} ahead)"),
+            None => "Running".to_string(),
+        };
+        Some(format!(
+            "{} {state} {:.1}s",
+            theme::spinner_frame(elapsed),
+            elapsed.as_secs_f64()
+        ))
+    }
+
+    /// How long the in-flight query's oldest AWS call has gone unanswered,
+    /// once that passes `network.watchdog_seconds`.
+    pub fn query_stalled(&self) -> Option<Duration> {
+        let limit = self.config.network.watchdog_seconds;
+        if !self.submitting || limit == 0 {
+            return None;
+        }
+        self.query_watch
+            .longest_wait()
+            .map(|(_, waited)| waited)
+            .filter(|waited| waited.as_secs() >= limit)
+    }
+
+    /// Give up on the in-flight query; its task is dropped along with any
+    /// AWS call it was waiting on, so no report arrives.
+    pub fn abort_query(&mut self) {
+        let call = self.query_watch.longest_wait().map(|(call, _)| call);
+        if let Some(task) = self.query_task.take() {
+            task.abort();
+        }
+        self.finish_submission();
+        self.submitted_inputs = None;
+        self.query_failed = true;
+        match call {
+            Some(call) => self.set_error(i18n::fill("status.aborted_call", &[("call", call)])),
+            None => self.set_error(i18n::text("status.aborted")),
+        }
     }
 
     fn sync_selection_after_filter(&mut self) {
@@ -756,36 +2376,198 @@ impl App {
             self.modal_open = false;
         } else if self.selected_row_data().is_some() {
             self.modal_open = true;
+            self.modal_field = 0;
+            self.modal_hscroll = 0;
+            self.modal_rendered = None;
+            self.render_request = self.render_selected_row();
         }
     }
 
-    pub fn close_modal(&mut self) {
-        self.modal_open = false;
+    /// Offer the selected row to the renderer plugins, keyed by its log group.
+    fn render_selected_row(&self) -> Option<RenderRequest> {
+        if self.renderers.is_empty() {
+            return None;
+        }
+        let row = *self.filtered_indices.get(self.selected_filtered_index?)?;
+        let fields = self.selected_row_data()?;
+        // `@log` is `account:group`; fall back to the queried log group.
+        let log_group = fields
+            .iter()
+            .find(|(header, _)| header == "@log")
+            .map(|(_, value)| {
+                value
+                    .split_once(':')
+                    .map_or(value.as_str(), |(_, group)| group)
+            })
+            .or_else(|| {
+                self.last_query
+                    .as_ref()
+                    .map(|params| params.log_group.as_str())
+            })
+            .unwrap_or_default()
+            .to_string();
+        Some(RenderRequest {
+            row,
+            log_group,
+            fields,
+            plugins: Arc::clone(&self.renderers),
+        })
     }
 
-    pub fn page_results(&mut self, delta_pages: i32) {
-        if delta_pages == 0 || self.filtered_indices.is_empty() {
-            return;
+    /// Keep a plugin rendering unless the detail view moved to another row
+    /// while it ran.
+    pub fn apply_rendering(&mut self, row: usize, rendered: Option<Rendered>) {
+        let selected = self
+            .selected_filtered_index
+            .and_then(|pos| self.filtered_indices.get(pos));
+        if self.modal_open && selected == Some(&row) {
+            self.modal_rendered = rendered.map(|rendered| (row, rendered));
         }
+    }
 
-        let view = self.results_view_height.max(1);
-        if self.results_navigation {
-            let step = view as i32 * delta_pages;
-            if step != 0 {
-                self.move_selection(step);
-            }
-            return;
+    fn selected_rendering(&self) -> Option<&Rendered> {
+        let (row, rendered) = self.modal_rendered.as_ref()?;
+        let selected = self
+            .selected_filtered_index
+            .and_then(|pos| self.filtered_indices.get(pos));
+        (selected == Some(row)).then_some(rendered)
+    }
+
+    /// Name of the plugin that rendered the open row's message.
+    pub fn modal_renderer(&self) -> Option<&str> {
+        self.selected_rendering()
+            .filter(|rendered| rendered.lines.is_ok() && !self.modal_raw)
+            .map(|rendered| rendered.plugin.as_str())
+    }
+
+    /// On a row of `stats ... by X` results, queue the raw events of that group
+    /// (same range and log group) for a new tab; false for other results.
+    pub fn request_drill_down(&mut self) -> bool {
+        let Some(params) = self.last_query.as_ref() else {
+            return false;
+        };
+        let Some(row) = self
+            .selected_filtered_index
+            .and_then(|pos| self.filtered_indices.get(pos))
+            .and_then(|&row| self.results.cells(row))
+        else {
+            return false;
+        };
+        let headers = &self.results.headers;
+        let Some(query) = drill_down::raw_events_query(&params.query, headers, &row) else {
+            return false;
+        };
+        if self.submitting {
+            self.set_status(i18n::text("status.drill_down_busy"));
+            return true;
         }
+        let title = drill_down::describe(&params.query, headers, &row);
+        let params = QueryParams {
+            query,
+            ..params.clone()
+        };
+        self.drill_down = Some((title, params));
+        self.workspace_command = Some(WorkspaceCommand::DrillDown);
+        true
+    }
 
-        let len = self.filtered_indices.len();
-        if len <= view {
-            self.results_scroll = 0;
+    pub fn toggle_modal_raw(&mut self) {
+        self.modal_raw = !self.modal_raw;
+    }
+
+    pub fn toggle_modal_wrap(&mut self) {
+        self.modal_wrap = !self.modal_wrap;
+        self.modal_hscroll = 0;
+    }
+
+    pub fn scroll_modal_horizontally(&mut self, delta: i32) {
+        if self.modal_wrap {
             return;
         }
+        self.modal_hscroll =
+            (i32::from(self.modal_hscroll) + delta).clamp(0, i32::from(u16::MAX)) as u16;
+    }
 
-        let max_scroll = (len - view) as i32;
-        let current = self.results_scroll as i32;
-        let mut next = current + view as i32 * delta_pages;
+    /// Modal lines for one field: prettified, or split as stored in raw view.
+    pub fn modal_value_lines(&self, header: &str, value: &str) -> Vec<String> {
+        if header == "@message" && !self.modal_raw {
+            let Some(rendered) = self.selected_rendering() else {
+                return format_modal_message(value);
+            };
+            match &rendered.lines {
+                Ok(lines) => lines.clone(),
+                Err(err) => {
+                    let mut lines = vec![format!("({} failed: {err})", rendered.plugin)];
+                    lines.extend(format_modal_message(value));
+                    lines
+                }
+            }
+        } else {
+            format_modal_value(value)
+        }
+    }
+
+    pub fn move_modal_field(&mut self, delta: i32) {
+        let count = self.results.headers.len().max(1);
+        self.modal_field = (self.modal_field as i32 + delta).clamp(0, count as i32 - 1) as usize;
+    }
+
+    /// Header and value of the field under the modal cursor.
+    pub fn selected_modal_field(&self) -> Option<(String, String)> {
+        self.selected_row_data()?.into_iter().nth(self.modal_field)
+    }
+
+    /// URL to open from the modal: the first in the selected field, else the first in the row.
+    pub fn modal_url(&self) -> Option<String> {
+        let row = self.selected_row_data()?;
+        let selected = row
+            .get(self.modal_field)
+            .and_then(|(_, value)| links::find_url(value));
+        selected.or_else(|| row.iter().find_map(|(_, value)| links::find_url(value)))
+    }
+
+    pub fn open_modal_url(&mut self) {
+        let Some(url) = self.modal_url() else {
+            self.set_status(i18n::text("status.no_link"));
+            return;
+        };
+        if !links::is_allowed(&url, &self.config.links.allowed_hosts) {
+            self.set_error(i18n::fill("error.link_not_allowed", &[("url", &url)]));
+            return;
+        }
+        match links::open_in_browser(&url) {
+            Ok(()) => self.set_status(i18n::fill("status.link_opened", &[("url", &url)])),
+            Err(err) => self.set_error(err),
+        }
+    }
+
+    pub fn close_modal(&mut self) {
+        self.modal_open = false;
+    }
+
+    pub fn page_results(&mut self, delta_pages: i32) {
+        if delta_pages == 0 || self.filtered_indices.is_empty() {
+            return;
+        }
+
+        let view = self.results_view_height.max(1);
+        if self.results_navigation {
+            let step = view as i32 * delta_pages;
+            if step != 0 {
+                self.move_selection(step);
+            }
+            return;
+        }
+
+        let len = self.filtered_indices.len();
+        if len <= view {
+            self.results_scroll = 0;
+            return;
+        }
+
+        let max_scroll = (len - view) as i32;
+        let current = self.results_scroll as i32;
+        let mut next = current + view as i32 * delta_pages;
         if next < 0 {
             next = 0;
         } else if next > max_scroll {
@@ -798,10 +2580,10 @@ impl App {
     pub fn selected_row_data(&self) -> Option<Vec<(String, String)>> {
         let filtered_pos = self.selected_filtered_index?;
         let row_idx = *self.filtered_indices.get(filtered_pos)?;
-        let row = self.results.rows.get(row_idx)?;
+        let row = self.results.cells(row_idx)?;
 
         let mut data = Vec::new();
-        for (i, cell) in row.cells.iter().enumerate() {
+        for (i, cell) in row.iter().enumerate() {
             let header = self
                 .results
                 .headers
@@ -884,11 +2666,28 @@ impl App {
         self.clamp_results_scroll();
     }
 
+    /// Slow or expensive patterns in the query text, shown under the editor.
+    pub fn query_lint_hints(&self) -> Vec<String> {
+        let range_days = if self.relative_mode {
+            Some(self.current_relative_option().seconds as f64 / 86_400.0)
+        } else {
+            match (
+                parse_datetime(self.from_input.value()),
+                parse_datetime(self.to_input.value()),
+            ) {
+                (Ok(start), Ok(end)) => Some((end - start).num_seconds() as f64 / 86_400.0),
+                _ => None,
+            }
+        };
+        query_lint::lint(&self.expanded_query(), range_days)
+    }
+
     pub fn prepare_submission(&self) -> Result<QueryParams, String> {
         let log_group = self.log_group_input.value().trim().to_string();
         if log_group.is_empty() {
             return Err("Log group is required".into());
         }
+        let log_group = variables::expand(&log_group, &self.variables)?;
 
         let region = self.aws_region_input.value().trim().to_string();
         if region.is_empty() {
@@ -899,17 +2698,20 @@ impl App {
         if query.is_empty() {
             return Err("Query text cannot be empty".into());
         }
+        let query = variables::expand(&query, &self.variables)?;
 
         if self.relative_mode {
             let option = self.current_relative_option();
             if option.seconds <= 0 {
                 return Err("Relative range must be greater than zero".into());
             }
-            let end = Utc::now();
+            // Whole seconds, so the window needs no sub-second `@timestamp`
+            // filter added to the query.
+            let end = Utc::now().trunc_subsecs(0);
             let start = end - ChronoDuration::seconds(option.seconds);
             return Ok(QueryParams {
-                start_epoch: start.timestamp(),
-                end_epoch: end.timestamp(),
+                start_millis: start.timestamp_millis(),
+                end_millis: end.timestamp_millis(),
                 log_group,
                 query,
                 region,
@@ -925,8 +2727,8 @@ impl App {
         }
 
         Ok(QueryParams {
-            start_epoch: start.timestamp(),
-            end_epoch: end.timestamp(),
+            start_millis: start.timestamp_millis(),
+            end_millis: end.timestamp_millis(),
             log_group,
             query,
             region,
@@ -934,6 +2736,32 @@ impl App {
         })
     }
 
+    /// First screen from `[startup]`: focus, collapsed inputs, filter pane,
+    /// and relative window.
+    fn apply_startup_layout(&mut self) {
+        let startup = self.config.startup.clone();
+        // Checked by `config::load_config`, which drops unknown windows.
+        if let Some(index) = startup
+            .relative_range
+            .as_deref()
+            .and_then(relative_range_index)
+        {
+            self.selected_relative_index = index;
+        }
+        self.filter_active = startup.show_filter;
+        if let Some(focus) = startup.focus {
+            self.scratchpad_open |= focus == FocusField::Scratchpad;
+            self.filter_active |= focus == FocusField::Filter;
+            if self.focus_order().contains(&focus) {
+                self.focus = focus;
+            }
+        }
+        if startup.inputs_collapsed {
+            self.collapse_inputs();
+        }
+        self.ensure_focus_shown();
+    }
+
     pub fn collapse_inputs(&mut self) {
         if self.inputs_collapsed {
             return;
@@ -970,15 +2798,78 @@ impl App {
         }
     }
 
+    pub fn open_palette(&mut self) {
+        self.palette = Some(PaletteState::new());
+        self.help_open = false;
+        self.modal_open = false;
+        self.column_modal = None;
+        self.save_dialog = None;
+        self.open_dialog = None;
+    }
+
+    pub fn close_palette(&mut self) {
+        self.palette = None;
+    }
+
+    pub fn palette_active(&self) -> bool {
+        self.palette.is_some()
+    }
+
     pub fn close_help(&mut self) {
         self.help_open = false;
     }
 
     pub fn query_block_title(&self) -> String {
-        if let Some(name) = self.saved_query_display_name() {
-            format!("Logs Insights query ({name})")
+        let title = if let Some(name) = self.saved_query_display_name() {
+            i18n::fill("title.saved_query", &[("name", &name)])
+        } else {
+            i18n::text("title.query").to_string()
+        };
+        let title = if variables::has_placeholders(&self.query_text()) {
+            format!("{title} — {}", self.variables_summary())
         } else {
-            "Logs Insights query".to_string()
+            title
+        };
+        match self.query_block.as_ref() {
+            Some(caret) => format!(
+                "{title} — block edit, {} lines (Esc ends)",
+                caret.rows().count()
+            ),
+            None => title,
+        }
+    }
+
+    /// Query text with workspace variables filled in where they are set.
+    fn expanded_query(&self) -> String {
+        let query = self.query_text();
+        variables::expand(&query, &self.variables).unwrap_or(query)
+    }
+
+    pub fn variables_summary(&self) -> String {
+        if self.variables.is_empty() {
+            "no variables set".to_string()
+        } else {
+            variables::format(&self.variables)
+        }
+    }
+
+    pub fn open_variables_dialog(&mut self) {
+        self.variables_input = Some(SingleLineInput::new(variables::format(&self.variables)));
+    }
+
+    pub fn submit_variables(&mut self) {
+        let Some(input) = self.variables_input.take() else {
+            return;
+        };
+        match variables::parse(input.value()) {
+            Ok(parsed) => {
+                self.variables = parsed;
+                self.set_status(i18n::fill(
+                    "status.variables",
+                    &[("variables", &self.variables_summary())],
+                ));
+            }
+            Err(err) => self.set_error(err),
         }
     }
 
@@ -999,147 +2890,1331 @@ impl App {
         })
     }
 
-    pub fn set_saved_query_path(&mut self, path: PathBuf) {
-        self.saved_query_path = Some(path);
+    pub fn set_saved_query_path(&mut self, path: PathBuf) {
+        self.saved_query_path = Some(path);
+        self.query_baseline = self.query_text();
+    }
+
+    pub fn query_has_unsaved_changes(&self) -> bool {
+        self.query_text().trim() != self.query_baseline.trim()
+    }
+
+    /// Show how the query differs from `saved`, the contents of its file.
+    pub fn open_query_diff(&mut self, saved: String) {
+        let file = self.saved_query_display_name().unwrap_or_default();
+        let diff = QueryDiff::new(file, saved, &self.query_text());
+        if diff.lines.is_empty() {
+            self.set_status(i18n::fill(
+                "status.query_matches_file",
+                &[("file", &diff.file)],
+            ));
+        } else {
+            self.query_diff = Some(diff);
+        }
+    }
+
+    pub fn scroll_query_diff(&mut self, delta: i32) {
+        if let Some(diff) = self.query_diff.as_mut() {
+            let last = diff.lines.len().saturating_sub(1);
+            diff.scroll = ((diff.scroll as i32 + delta).max(0) as usize).min(last);
+        }
+    }
+
+    /// Discard the edits shown in the diff, putting back the saved file.
+    pub fn revert_query_to_saved(&mut self) {
+        let Some(diff) = self.query_diff.take() else {
+            return;
+        };
+        self.replace_query_text(diff.saved);
+        self.query_baseline = self.query_text();
+        self.set_status(i18n::fill("status.query_reverted", &[("file", &diff.file)]));
+    }
+
+    pub fn toggle_macro_recording(&mut self) {
+        if self.key_macro.is_recording() {
+            let keys = self.key_macro.finish();
+            self.set_status(i18n::fill(
+                "status.macro_recorded",
+                &[("keys", &keys.to_string())],
+            ));
+        } else {
+            self.key_macro.start();
+            self.set_status(i18n::text("status.macro_recording"));
+        }
+    }
+
+    pub fn replay_macro(&mut self) {
+        if self.key_macro.is_replaying() {
+            return;
+        }
+        if self.key_macro.is_recording() {
+            self.key_macro.unrecord();
+            self.set_status(i18n::text("status.macro_still_recording"));
+            return;
+        }
+        match self.key_macro.replay() {
+            0 => self.set_status(i18n::text("status.no_macro")),
+            keys => self.set_status(i18n::fill(
+                "status.macro_replaying",
+                &[("keys", &keys.to_string())],
+            )),
+        }
+    }
+
+    /// Returns true when it is safe to exit now; otherwise asks for confirmation.
+    pub fn request_quit(&mut self) -> bool {
+        if !self.config.general.confirm_quit {
+            return true;
+        }
+        let mut concerns = Vec::new();
+        if self.submitting {
+            concerns.push("Query still running");
+        }
+        if self.query_has_unsaved_changes() {
+            concerns.push("Unsaved query changes");
+        }
+        if concerns.is_empty() {
+            return true;
+        }
+        self.quit_prompt = Some(format!("{} — quit anyway?", concerns.join(" / ")));
+        false
+    }
+
+    /// Banner text while the selected profile, its account, or the active preset is production.
+    pub fn production_banner(&self) -> Option<String> {
+        let is_production = |profile: &&str| {
+            let account = self.profile_accounts.get(*profile);
+            self.config
+                .production
+                .matches(profile, account.map(String::as_str))
+        };
+        // A group counts as production through any of its profiles.
+        let profile = match self.selected_profile_group() {
+            Some(group) => group
+                .profiles
+                .iter()
+                .map(String::as_str)
+                .find(is_production)
+                .or(self.selected_profile_name()),
+            None => self.selected_profile_name(),
+        };
+        let account = profile.and_then(|profile| self.profile_accounts.get(profile));
+        let by_config = profile.as_ref().is_some_and(is_production);
+        let by_preset = self
+            .current_preset()
+            .is_some_and(|preset| preset.is_production());
+        if !by_config && !by_preset {
+            return None;
+        }
+        let profile = profile.unwrap_or("auto");
+        Some(match account {
+            Some(account) => i18n::fill(
+                "status.production_account",
+                &[("profile", profile), ("account", account)],
+            ),
+            None => i18n::fill("status.production", &[("profile", profile)]),
+        })
+    }
+
+    /// Whether `run` may go out now; otherwise the production prompt asks
+    /// first. Side queries (CloudTrail, value sampling) count as queries.
+    pub fn check_production_run(&mut self, run: PendingRun) -> bool {
+        if !self.config.production.confirm_first_query
+            || self.production_confirmed.load(Ordering::Relaxed)
+        {
+            return true;
+        }
+        let Some(banner) = self.production_banner() else {
+            return true;
+        };
+        self.run_prompt = Some(i18n::fill("status.production_run", &[("banner", &banner)]));
+        self.pending_run = run;
+        false
+    }
+
+    pub fn confirm_production_run(&mut self) {
+        self.production_confirmed.store(true, Ordering::Relaxed);
+        self.run_prompt = None;
+    }
+
+    pub fn cancel_quit(&mut self) {
+        self.quit_prompt = None;
+    }
+
+    /// Tab title: the rename, or the log group's last path segment plus the time range.
+    pub fn workspace_title(&self) -> String {
+        if let Some(name) = self.workspace_name.as_ref() {
+            return name.clone();
+        }
+        let log_group = self.log_group_input.value().trim();
+        let count = self.log_group_count();
+        let first = log_group.split(',').next().unwrap_or_default().trim();
+        let group = first
+            .rsplit('/')
+            .find(|segment| !segment.is_empty())
+            .unwrap_or("untitled");
+        let group = if count > 1 {
+            format!("{group} +{}", count - 1)
+        } else {
+            group.to_string()
+        };
+        let range = if self.relative_mode {
+            RELATIVE_RANGE_OPTIONS
+                .get(self.selected_relative_index)
+                .map(|option| compact_duration(option.seconds))
+                .unwrap_or_default()
+        } else {
+            // Down to the minute is enough to tell windows apart.
+            let from: String = self.from_input.value().trim().chars().take(16).collect();
+            format!("from {from}")
+        };
+        format!("{group} · {range}")
+    }
+
+    pub fn workspace_state(&self) -> TabState {
+        if self.submitting {
+            TabState::Running
+        } else if self.query_failed {
+            TabState::Failed
+        } else if self.last_run.is_some() {
+            TabState::Complete
+        } else {
+            TabState::Idle
+        }
+    }
+
+    /// Background results flash the tab for a few seconds when `focus_follows_activity` is set.
+    pub fn tab_flashing(&self) -> bool {
+        self.config.general.focus_follows_activity
+            && self
+                .results_unseen_since
+                .is_some_and(|since| since.elapsed() < TAB_FLASH_DURATION)
+    }
+
+    /// Whether a flashing tab is in its highlighted half-second.
+    pub fn tab_flash_on(&self) -> bool {
+        self.tab_flashing()
+            && self
+                .results_unseen_since
+                .is_some_and(|since| (since.elapsed().as_millis() / 500).is_multiple_of(2))
+    }
+
+    pub fn open_rename_dialog(&mut self) {
+        self.rename_input = Some(SingleLineInput::new(self.workspace_title()));
+    }
+
+    /// Apply the rename; an empty name goes back to the derived title.
+    pub fn apply_rename(&mut self) {
+        if let Some(input) = self.rename_input.take() {
+            let name = input.value().trim();
+            self.workspace_name = (!name.is_empty()).then(|| name.to_string());
+        }
+    }
+
+    pub fn open_save_dialog_with_entries(
+        &mut self,
+        mode: SaveDialogMode,
+        prefill: Option<String>,
+        entries: Vec<QueryFileEntry>,
+    ) {
+        let text = prefill.unwrap_or_default();
+        let input = SingleLineInput::new(text);
+        let state = SaveDialogState::new(mode, input, entries);
+        self.save_dialog = Some(state);
+        self.modal_open = false;
+        self.column_modal = None;
+        self.help_open = false;
+        self.open_dialog = None;
+    }
+
+    pub fn close_save_dialog(&mut self) {
+        self.save_dialog = None;
+    }
+
+    pub fn save_dialog_active(&self) -> bool {
+        self.save_dialog.is_some()
+    }
+
+    pub fn save_dialog_state_mut(&mut self) -> Option<&mut SaveDialogState> {
+        self.save_dialog.as_mut()
+    }
+
+    pub fn open_open_dialog(&mut self, mode: OpenDialogMode, entries: Vec<QueryFileEntry>) {
+        self.open_dialog = Some(OpenDialogState::new(mode, entries));
+        self.modal_open = false;
+        self.column_modal = None;
+        self.help_open = false;
+        self.save_dialog = None;
+    }
+
+    pub fn close_open_dialog(&mut self) {
+        self.open_dialog = None;
+    }
+
+    pub fn open_dialog_active(&self) -> bool {
+        self.open_dialog.is_some()
+    }
+
+    pub fn open_dialog_state_mut(&mut self) -> Option<&mut OpenDialogState> {
+        self.open_dialog.as_mut()
+    }
+
+    pub fn open_dialog_selected_path(&self) -> Option<PathBuf> {
+        self.open_dialog
+            .as_ref()
+            .and_then(|state| state.selected_entry())
+            .map(|entry| entry.path.clone())
+    }
+
+    pub fn open_dialog_mode(&self) -> Option<OpenDialogMode> {
+        self.open_dialog.as_ref().map(|state| state.mode)
+    }
+
+    pub fn save_dialog_mode(&self) -> Option<SaveDialogMode> {
+        self.save_dialog.as_ref().map(|state| state.mode)
+    }
+
+    pub fn results_snapshot(&self) -> Option<ResultSnapshot> {
+        if self.results.rows.is_empty() {
+            return None;
+        }
+        let query = self.last_query.as_ref().map(|params| SnapshotQuery {
+            query: params.query.clone(),
+            log_group: params.log_group.clone(),
+            region: params.region.clone(),
+            profile: params.profile.clone(),
+            start_epoch: params.epoch_seconds().0,
+            end_epoch: params.epoch_seconds().1,
+            start_millis: Some(params.start_millis),
+            end_millis: Some(params.end_millis),
+        });
+        let table = self.results.export_table();
+        let mut snapshot = ResultSnapshot::new(
+            Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            query,
+            table.headers,
+            table.rows,
+        );
+        snapshot.notes = self.row_notes.clone();
+        snapshot.scratchpad = self.scratchpad_text();
+        Some(snapshot)
+    }
+
+    pub fn export_table(&self) -> Option<ExportTable> {
+        if self.filtered_indices.is_empty() {
+            return None;
+        }
+        let columns = self.visible_column_indices();
+        let with_notes = !self.row_notes.is_empty();
+        let mut headers: Vec<String> = columns
+            .iter()
+            .filter_map(|&idx| self.results.headers.get(idx).cloned())
+            .collect();
+        if with_notes {
+            headers.push("note".to_string());
+        }
+        // Rows are read as they are written, so a large export does not
+        // load the spill file back into memory.
+        let read = self.results.row_reader();
+        let filtered = self.filtered_indices.clone();
+        let notes = with_notes.then(|| self.row_notes.clone());
+        let rows = ExportRows::lazy(filtered.len(), move |position| {
+            let idx = *filtered.get(position)?;
+            let row = read(idx)?;
+            let mut cells: Vec<String> = columns
+                .iter()
+                .map(|&col| row.get(col).cloned().unwrap_or_default())
+                .collect();
+            if let Some(notes) = &notes {
+                cells.push(notes.get(&idx).cloned().unwrap_or_default());
+            }
+            Some(cells)
+        });
+        Some(ExportTable { headers, rows })
+    }
+
+    /// The rows and columns on screen, with values as displayed.
+    pub fn visible_table(&self) -> Option<ExportTable> {
+        let end = (self.results_scroll + self.results_view_height.max(1))
+            .min(self.filtered_indices.len());
+        let window = self.filtered_indices.get(self.results_scroll..end)?;
+        if window.is_empty() {
+            return None;
+        }
+        let columns = self.visible_column_indices();
+        let headers = columns
+            .iter()
+            .filter_map(|&idx| self.results.headers.get(idx).cloned())
+            .collect();
+        let rows: Vec<Vec<String>> = window
+            .iter()
+            .filter_map(|&idx| self.results.cells(idx))
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|&col| {
+                        let value = row.get(col).map(String::as_str).unwrap_or_default();
+                        self.display_cell(col, value).into_owned()
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(ExportTable {
+            headers,
+            rows: rows.into(),
+        })
+    }
+
+    /// Build an export task for the last query window, written to `bucket[/prefix]`.
+    pub fn s3_export_request(&self, destination: &str) -> Result<S3ExportRequest, String> {
+        let trimmed = destination.trim();
+        let trimmed = trimmed.strip_prefix("s3://").unwrap_or(trimmed);
+        let (bucket, prefix) = match trimmed.split_once('/') {
+            Some((bucket, prefix)) => (bucket, prefix.trim_matches('/')),
+            None => (trimmed, ""),
+        };
+        if bucket.is_empty() {
+            return Err("Enter a destination as bucket[/prefix]".into());
+        }
+        self.single_profile_required("S3 export")?;
+        let params = match self.last_query.clone() {
+            Some(params) => params,
+            None => self.prepare_submission()?,
+        };
+        // An export task reads exactly one group, so lists, patterns and
+        // services are refused rather than resolved to some other group.
+        if params.spans_log_groups() {
+            return Err(format!(
+                "S3 export needs a single log group, not {}",
+                params.log_group.trim()
+            ));
+        }
+        let log_group = match params.log_group_names().next() {
+            Some(name) => name.to_string(),
+            None => return Err("S3 export needs a log group".into()),
+        };
+        let task_name = format!(
+            "awslogs-{}-{}",
+            log_group.trim_start_matches('/').replace('/', "-"),
+            Local::now().format("%Y%m%d%H%M%S")
+        );
+        Ok(S3ExportRequest {
+            region: params.region,
+            profile: params.profile,
+            log_group,
+            start_millis: params.start_millis,
+            end_millis: params.end_millis,
+            bucket: bucket.to_string(),
+            prefix: (!prefix.is_empty()).then(|| prefix.to_string()),
+            task_name,
+        })
+    }
+
+    pub fn share_summary(&self, top_rows: usize) -> Option<ShareSummary> {
+        let mut table = self.export_table()?;
+        let row_count = table.rows.len();
+        table.rows.truncate(top_rows);
+        let summary = match self.last_query.as_ref() {
+            Some(params) => ShareSummary {
+                query: params.query.clone(),
+                log_group: params.log_group.clone(),
+                region: params.region.clone(),
+                time_range: DateTime::from_timestamp_millis(params.start_millis)
+                    .zip(DateTime::from_timestamp_millis(params.end_millis))
+                    .map(|(start, end)| {
+                        (format_local_timestamp(start), format_local_timestamp(end))
+                    }),
+                row_count,
+                table,
+            },
+            None => ShareSummary {
+                query: self.query_text(),
+                log_group: self.log_group_input.value().trim().to_string(),
+                region: self.aws_region_input.value().trim().to_string(),
+                time_range: None,
+                row_count,
+                table,
+            },
+        };
+        Some(summary)
+    }
+
+    /// Log group, region, and query of the results, for indexed documents.
+    pub fn index_source(&self) -> IndexSource {
+        match self.last_query.as_ref() {
+            Some(params) => IndexSource {
+                log_group: params.log_group.clone(),
+                region: params.region.clone(),
+                query: params.query.clone(),
+            },
+            None => IndexSource {
+                log_group: self.log_group_input.value().trim().to_string(),
+                region: self.aws_region_input.value().trim().to_string(),
+                query: self.query_text(),
+            },
+        }
+    }
+
+    /// What the completion hook is told about the query that just finished.
+    pub fn query_completion(
+        &self,
+        elapsed: Option<Duration>,
+        outcome: Result<QueryResults, String>,
+    ) -> QueryCompletion {
+        let source = self.index_source();
+        QueryCompletion {
+            log_group: source.log_group,
+            region: source.region,
+            query: source.query,
+            window: self
+                .last_query
+                .as_ref()
+                .map(|params| (params.start_millis, params.end_millis)),
+            elapsed,
+            outcome,
+        }
+    }
+
+    pub fn load_snapshot(&mut self, snapshot: ResultSnapshot) {
+        if let Some(meta) = snapshot.query.as_ref() {
+            self.replace_query_text(meta.query.clone());
+            self.log_group_input = SingleLineInput::new(meta.log_group.clone());
+            self.aws_region_input = SingleLineInput::new(meta.region.clone());
+            let start_millis = meta.start_millis.unwrap_or(meta.start_epoch * 1000);
+            let end_millis = meta.end_millis.unwrap_or(meta.end_epoch * 1000);
+            if let (Some(start), Some(end)) = (
+                DateTime::from_timestamp_millis(start_millis),
+                DateTime::from_timestamp_millis(end_millis),
+            ) {
+                self.relative_mode = false;
+                self.from_input = SingleLineInput::new(format_local_timestamp(start));
+                self.to_input = SingleLineInput::new(format_local_timestamp(end));
+            }
+            self.last_query = Some(QueryParams {
+                start_millis,
+                end_millis,
+                log_group: meta.log_group.clone(),
+                query: meta.query.clone(),
+                region: meta.region.clone(),
+                profile: meta.profile.clone(),
+            });
+        } else {
+            self.last_query = None;
+        }
+        // Snapshot results were not fetched in this session.
+        self.last_run = None;
+        self.query_baseline = self.query_text();
+        self.saved_query_path = None;
+        self.set_results(snapshot.headers, snapshot.rows.into_vec());
+        let row_count = self.results.rows.len();
+        self.row_notes = snapshot.notes;
+        self.row_notes.retain(|&row, _| row < row_count);
+        // Keep this workspace's own notes when the snapshot carries none.
+        if !snapshot.scratchpad.is_empty() {
+            self.scratchpad = TextArea::from(snapshot.scratchpad.lines().map(str::to_string));
+            self.scratchpad_open = true;
+        }
+    }
+}
+
+/// Log group names use letters, digits, and `_-/.#`; a trailing `*` makes a prefix pattern.
+/// Pure numbers are rejected too, as they are usually a size or count column from a listing.
+fn is_log_group_name(value: &str) -> bool {
+    let name = value.strip_suffix('*').unwrap_or(value);
+    !name.is_empty()
+        && name.len() <= 512
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '/' | '.' | '#'))
+        && !name.chars().all(|c| c.is_ascii_digit())
+}
+
+/// A row's cells, borrowed, or read back from `spill` when it was spilled.
+fn row_cells<'a>(row: &'a ResultRow, spill: Option<&SpillFile>) -> Option<Cow<'a, [String]>> {
+    match (row.spilled, spill) {
+        (Some(slot), Some(file)) => file.read(slot).ok().map(Cow::Owned),
+        _ => Some(Cow::Borrowed(&row.cells)),
+    }
+}
+
+/// Fill `column` in every row (appending it when new) and refresh the filter
+/// text; false, leaving the results alone, when the query already returned a
+/// column of that name.
+fn add_computed_cells(results: &mut QueryResults, column: &ComputedColumn) -> bool {
+    let existing = results.headers.iter().position(|h| *h == column.name);
+    if existing.is_some() && !results.computed.contains(&column.name) {
+        return false;
+    }
+    if existing.is_none() {
+        results.headers.push(column.name.clone());
+        results.computed.push(column.name.clone());
+    }
+    let width = results.headers.len();
+    let idx = existing.unwrap_or(width - 1);
+    let headers = results.headers.clone();
+    results.update_rows(|cells, previews| {
+        cells.resize(width.max(cells.len()), String::new());
+        let value = column.evaluate(&headers, cells);
+        match large_cell_preview(&value) {
+            Some(preview) => previews.insert(idx, preview),
+            None => previews.remove(&idx),
+        };
+        cells[idx] = value;
+    });
+    true
+}
+
+/// Format for the From/To fields; milliseconds appear only when non-zero.
+pub fn format_local_timestamp(value: DateTime<Utc>) -> String {
+    let local = value.with_timezone(&Local).naive_local();
+    time_format::format(local, value.timestamp_subsec_millis() != 0)
+}
+
+impl Default for App {
+    fn default() -> Self {
+        let (config, config_warning) = config::load_config();
+        time_format::init(config.time.date_order);
+        let locale_warning = i18n::init(config.general.locale.as_deref());
+        let (timeline_markers, timeline_warning) = timeline::load(&config.timeline);
+        let (cell_rules, highlight_warning) = highlight::compile(&config.highlights);
+        let (computed_columns, computed_warning) = computed::compile(&config.computed);
+        let (renderers, renderer_warning) = renderers::load(&config.renderers);
+        let config_warning = config_warning
+            .or(timeline_warning)
+            .or(highlight_warning)
+            .or(computed_warning)
+            .or(renderer_warning)
+            .or(locale_warning);
+        let variables = config.variables.clone();
+        let humanize_durations = config.durations.humanize;
+        let query_engine = config.query.engine;
+        let query_limiter = Arc::new(QueryLimiter::new(config.query.max_concurrent));
+        let stream_enricher = Arc::new(StreamEnricher::new(&config.enrich));
+        let result_cache = ResultCache::new(Duration::from_secs(config.query.cache_minutes * 60));
+        let AppDefaults {
+            from,
+            to,
+            log_group,
+            query,
+        } = default_app_values();
+        let from_input = SingleLineInput::new(from);
+        let to_input = SingleLineInput::new(to);
+        let log_group_input = SingleLineInput::new(log_group.to_string());
+        let query_area = TextArea::from(query.lines().map(|line| line.to_string()));
+        let (initial_status, status_kind) = match config_warning {
+            Some(warning) => (warning, StatusKind::Error),
+            None => (
+                "Ready. Fill in the fields and press Ctrl+Enter to search.".to_string(),
+                StatusKind::Info,
+            ),
+        };
+        let hidden_inputs = config.startup.hidden_inputs.iter().copied().collect();
+        let default_relative_index = RELATIVE_RANGE_OPTIONS
+            .iter()
+            .position(|opt| opt.label == "1 hour")
+            .unwrap_or(0);
+        let mut app = Self {
+            config,
+            linear_mode: false,
+            keyboard_enhanced: false,
+            show_mnemonics: false,
+            key_macro: KeyMacro::default(),
+            focus: FocusField::LogGroup,
+            aws_profiles: Vec::new(),
+            profiles_loading: true,
+            selected_profile: None,
+            aws_region_input: SingleLineInput::new(resolve_default_region()),
+            inputs_collapsed: false,
+            hidden_inputs,
+            input_pane_picker: None,
+            query_zoomed: false,
+            relative_mode: true,
+            selected_relative_index: default_relative_index,
+            from_input,
+            to_input,
+            log_group_input,
+            query_area,
+            query_block: None,
+            snippet_session: None,
+            query_scroll_row: 0,
+            query_scroll_col: 0,
+            saved_query_path: None,
+            query_baseline: query.to_string(),
+            query_defaulted: true,
+            query_diff: None,
+            last_query: None,
+            last_s3_destination: None,
+            results: QueryResults::default(),
+            column_visibility: Vec::new(),
+            column_visibility_overrides: HashMap::new(),
+            column_filter_headers: Vec::new(),
+            results_initialized: false,
+            status_kind,
+            filtered_indices: Vec::new(),
+            sample_every: None,
+            filter_input: SingleLineInput::new(String::new()),
+            filter_preview: None,
+            chip_cursor: None,
+            disabled_chips: HashSet::new(),
+            filter_active: false,
+            filter_dirty: false,
+            filter_job: None,
+            index_job: None,
+            last_filter_edit: None,
+            status: initial_status,
+            results_navigation: false,
+            selected_filtered_index: None,
+            marked_rows: Vec::new(),
+            row_notes: BTreeMap::new(),
+            timeline_markers,
+            humanize_durations,
+            cell_rules,
+            computed_columns,
+            renderers: Arc::new(renderers),
+            modal_rendered: None,
+            render_request: None,
+            scratchpad: TextArea::default(),
+            scratchpad_open: false,
+            note_input: None,
+            modal_open: false,
+            modal_field: 0,
+            column_stats: None,
+            clusters: None,
+            baseline: None,
+            new_pattern_rows: HashSet::new(),
+            value_field_input: None,
+            computed_input: None,
+            value_picker: None,
+            value_sample: None,
+            cloudtrail: None,
+            cloudtrail_request: None,
+            cloudtrail_requests: 0,
+            explain: None,
+            explain_request: None,
+            explain_requests: 0,
+            resource_lookup: None,
+            modal_raw: false,
+            modal_wrap: true,
+            modal_hscroll: 0,
+            help_open: false,
+            results_scroll: 0,
+            results_view_height: 0,
+            submitting: false,
+            query_started_at: None,
+            query_limiter,
+            stream_enricher,
+            query_tickets: Vec::new(),
+            query_task: None,
+            query_watch: CallWatch::default(),
+            trace_scroll: None,
+            last_run: None,
+            submitted_inputs: None,
+            result_cache,
+            results_cached_at: None,
+            metadata: Metadata::default(),
+            metadata_key: None,
+            metadata_refresh: None,
+            column_modal: None,
+            save_dialog: None,
+            open_dialog: None,
+            palette: None,
+            date_picker: None,
+            quit_prompt: None,
+            run_prompt: None,
+            pending_run: PendingRun::Query,
+            production_confirmed: Arc::new(AtomicBool::new(false)),
+            profile_accounts: HashMap::new(),
+            workspace_command: None,
+            workspace_name: None,
+            drill_down: None,
+            variables,
+            variables_input: None,
+            rename_input: None,
+            query_failed: false,
+            results_unseen_since: None,
+            active_preset: None,
+            query_engine,
+        };
+        app.apply_startup_layout();
+        app.apply_default_query();
+        app.sync_metadata();
+        app
+    }
+}
+
+impl App {
+    pub fn ensure_column_visibility_len(&mut self) {
+        self.sync_column_visibility();
+    }
+
+    pub fn toggle_humanize_durations(&mut self) {
+        self.humanize_durations = !self.humanize_durations;
+        self.set_status(if self.humanize_durations {
+            "Showing durations in readable units"
+        } else {
+            "Showing durations as stored"
+        });
+    }
+
+    /// Switch between every row and one in `sampling.every`, keeping the filter.
+    pub fn toggle_sampling(&mut self) {
+        self.sample_every = match self.sample_every {
+            Some(_) => None,
+            None => Some(self.config.sampling.every.max(2)),
+        };
+        self.apply_filter_now();
+        match self.sample_every {
+            Some(every) => self.set_status(i18n::fill(
+                "status.sampling_rows",
+                &[("every", &every.to_string())],
+            )),
+            None => self.set_status(i18n::text("status.showing_every_row")),
+        }
+    }
+
+    pub fn open_query_trace(&mut self) {
+        if self.query_watch.trace().is_empty() {
+            self.set_status(i18n::text("status.no_aws_calls"));
+        } else {
+            self.trace_scroll = Some(0);
+        }
+    }
+
+    pub fn scroll_query_trace(&mut self, delta: i32) {
+        if let Some(scroll) = self.trace_scroll.as_mut() {
+            *scroll = (*scroll as i32 + delta).max(0) as usize;
+        }
+    }
+
+    /// Summarise the modal's selected field, or the first visible column.
+    pub fn open_column_stats(&mut self) {
+        let column = if self.modal_open {
+            Some(self.modal_field)
+        } else {
+            self.visible_column_indices().first().copied()
+        };
+        match column {
+            Some(column) if !self.filtered_indices.is_empty() => self.show_column_stats(column),
+            _ => self.set_status(i18n::text("status.no_results_to_summarise")),
+        }
+    }
+
+    /// Move the popover to the previous or next visible column.
+    pub fn cycle_column_stats(&mut self, delta: i32) {
+        let Some((current, _)) = self.column_stats.as_ref() else {
+            return;
+        };
+        let columns = self.visible_column_indices();
+        if columns.is_empty() {
+            return;
+        }
+        let position = columns
+            .iter()
+            .position(|column| column == current)
+            .unwrap_or(0) as i32;
+        let next = (position + delta).rem_euclid(columns.len() as i32) as usize;
+        self.show_column_stats(columns[next]);
+    }
+
+    fn show_column_stats(&mut self, column: usize) {
+        let header = self
+            .results
+            .headers
+            .get(column)
+            .cloned()
+            .unwrap_or_else(|| format!("Column {}", column + 1));
+        let values: Vec<Cow<str>> = self
+            .filtered_indices
+            .iter()
+            .map(|&row| self.results.cell(row, column).unwrap_or_default())
+            .collect();
+        let stats = column_stats::compute(&header, values.iter().map(|value| value.as_ref()));
+        self.column_stats = Some((column, stats));
+    }
+
+    /// `@message` when the results have it, otherwise the first visible column.
+    fn message_column(&self) -> Option<usize> {
+        self.results
+            .headers
+            .iter()
+            .position(|header| header == "@message")
+            .or_else(|| self.visible_column_indices().first().copied())
+    }
+
+    /// Group the filtered rows' messages by fingerprint.
+    pub fn open_clusters(&mut self) {
+        let column = self.message_column();
+        let Some(column) = column.filter(|_| !self.filtered_indices.is_empty()) else {
+            self.set_status(i18n::text("status.no_results_to_cluster"));
+            return;
+        };
+        let header = self
+            .results
+            .headers
+            .get(column)
+            .cloned()
+            .unwrap_or_else(|| format!("Column {}", column + 1));
+        let messages: Vec<(usize, Cow<str>)> = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&row| Some((row, self.results.cell(row, column)?)))
+            .collect();
+        let messages = messages
+            .iter()
+            .map(|(row, message)| (*row, message.as_ref()));
+        self.clusters = Some(clusters::build(&header, messages));
+    }
+
+    /// Remember the current results' message patterns; later runs highlight rows
+    /// whose pattern is not among them.
+    pub fn set_baseline(&mut self) {
+        let Some(column) = self
+            .message_column()
+            .filter(|_| !self.results.rows.is_empty())
+        else {
+            self.set_status(i18n::text("status.baseline_needs_results"));
+            return;
+        };
+        let header = self
+            .results
+            .headers
+            .get(column)
+            .cloned()
+            .unwrap_or_default();
+        let fingerprints: HashSet<String> = (0..self.results.rows.len())
+            .filter_map(|row| self.results.cell(row, column))
+            .map(|message| clusters::fingerprint(&message))
+            .collect();
+        self.set_status(i18n::fill(
+            "status.baseline_set",
+            &[
+                ("patterns", &fingerprints.len().to_string()),
+                ("header", &header),
+                ("rows", &self.results.rows.len().to_string()),
+            ],
+        ));
+        self.baseline = Some((header, fingerprints));
+        self.refresh_new_pattern_rows();
+    }
+
+    pub fn clear_baseline(&mut self) {
+        self.baseline = None;
+        self.new_pattern_rows.clear();
+        self.set_status(i18n::text("status.baseline_cleared"));
+    }
+
+    fn refresh_new_pattern_rows(&mut self) {
+        self.new_pattern_rows.clear();
+        let Some((header, fingerprints)) = self.baseline.as_ref() else {
+            return;
+        };
+        let Some(column) = self.results.headers.iter().position(|h| h == header) else {
+            return;
+        };
+        self.new_pattern_rows = (0..self.results.rows.len())
+            .filter(|&row| {
+                self.results
+                    .cell(row, column)
+                    .is_some_and(|message| !fingerprints.contains(&clusters::fingerprint(&message)))
+            })
+            .collect();
+    }
+
+    pub fn selected_row_is_new_pattern(&self) -> bool {
+        self.selected_filtered_index
+            .and_then(|position| self.filtered_indices.get(position))
+            .is_some_and(|row| self.new_pattern_rows.contains(row))
+    }
+
+    /// Close the cluster popover and highlight the selected cluster's example row.
+    pub fn jump_to_cluster_example(&mut self) {
+        let Some(view) = self.clusters.take() else {
+            return;
+        };
+        let Some(cluster) = view.selected_cluster() else {
+            return;
+        };
+        if let Some(position) = self
+            .filtered_indices
+            .iter()
+            .position(|&row| row == cluster.example_row)
+        {
+            self.modal_open = false;
+            self.focus = FocusField::Results;
+            self.results_navigation = true;
+            self.selected_filtered_index = Some(position);
+            self.ensure_selection_visible();
+        }
+    }
+
+    /// Ask which field to sample, starting from the modal's field or the word at the query cursor.
+    pub fn open_value_field_dialog(&mut self) {
+        let field = if self.modal_open {
+            self.results.headers.get(self.modal_field).cloned()
+        } else {
+            self.query_word_at_cursor()
+        };
+        self.value_field_input = Some(SingleLineInput::new(field.unwrap_or_default()));
+    }
+
+    pub fn open_computed_dialog(&mut self) {
+        self.computed_input = Some(SingleLineInput::new(String::new()));
+    }
+
+    /// Add (or replace) the computed column typed as `Name = expression`;
+    /// `Name =` alone removes it.
+    pub fn submit_computed_column(&mut self) {
+        let Some(input) = self.computed_input.take() else {
+            return;
+        };
+        let definition = input.value().to_string();
+        if let Some((name, expr)) = definition.split_once('=') {
+            if expr.trim().is_empty() {
+                self.remove_computed_column(name.trim());
+                return;
+            }
+        }
+        let column = match ComputedColumn::parse_definition(&definition) {
+            Ok(column) => column,
+            Err(err) => {
+                self.set_error(err);
+                return;
+            }
+        };
+        let name = column.name.clone();
+        if self.results.headers.contains(&name) && !self.results.computed.contains(&name) {
+            self.set_error(i18n::fill("error.computed_clash", &[("name", &name)]));
+            return;
+        }
+        match self
+            .computed_columns
+            .iter_mut()
+            .find(|existing| existing.name == name)
+        {
+            Some(existing) => *existing = column.clone(),
+            None => self.computed_columns.push(column.clone()),
+        }
+        if !self.results.headers.is_empty() {
+            add_computed_cells(&mut self.results, &column);
+            self.sync_column_visibility();
+            self.apply_filter_now();
+        }
+        self.set_status(i18n::fill("status.computed_added", &[("name", &name)]));
+    }
+
+    fn remove_computed_column(&mut self, name: &str) {
+        let before = self.computed_columns.len();
+        self.computed_columns.retain(|column| column.name != name);
+        if self.computed_columns.len() == before {
+            self.set_status(i18n::fill("status.computed_missing", &[("name", name)]));
+            return;
+        }
+        let computed = self.results.computed.iter().any(|header| header == name);
+        if let Some(idx) = self
+            .results
+            .headers
+            .iter()
+            .position(|h| h == name)
+            .filter(|_| computed)
+        {
+            self.results.headers.remove(idx);
+            self.results.computed.retain(|header| header != name);
+            self.results.update_rows(|cells, previews| {
+                if idx < cells.len() {
+                    cells.remove(idx);
+                }
+                *previews = std::mem::take(previews)
+                    .into_iter()
+                    .filter(|(column, _)| *column != idx)
+                    .map(|(column, preview)| (column - usize::from(column > idx), preview))
+                    .collect();
+            });
+            self.sync_column_visibility();
+            self.apply_filter_now();
+        }
+        self.set_status(i18n::fill("status.computed_removed", &[("name", name)]));
     }
 
-    pub fn open_save_dialog_with_entries(
-        &mut self,
-        mode: SaveDialogMode,
-        prefill: Option<String>,
-        entries: Vec<QueryFileEntry>,
-    ) {
-        let text = prefill.unwrap_or_default();
-        let input = SingleLineInput::new(text);
-        let state = SaveDialogState::new(mode, input, entries);
-        self.save_dialog = Some(state);
-        self.modal_open = false;
-        self.column_modal = None;
-        self.help_open = false;
-        self.open_dialog = None;
+    /// Tab in the query editor: visit the next (or previous) snippet tab stop, or
+    /// expand the snippet trigger before the cursor. False when neither applies.
+    pub fn query_tab(&mut self, forward: bool) -> bool {
+        if let Some(session) = self.snippet_session.as_mut() {
+            if !session.advance(&mut self.query_area, forward) {
+                self.snippet_session = None;
+            }
+            return true;
+        }
+        if !forward {
+            return false;
+        }
+        match snippets::expand(&mut self.query_area, &self.config.snippets) {
+            Some(session) => {
+                self.snippet_session = session.is_active().then_some(session);
+                true
+            }
+            None => false,
+        }
     }
 
-    pub fn close_save_dialog(&mut self) {
-        self.save_dialog = None;
+    /// Reference line for the command or function under the query cursor.
+    pub fn query_function_hint(&self) -> Option<String> {
+        query_docs::describe(&self.query_word_at_cursor()?)
     }
 
-    pub fn save_dialog_active(&self) -> bool {
-        self.save_dialog.is_some()
+    fn query_word_at_cursor(&self) -> Option<String> {
+        let (row, col) = self.query_area.cursor();
+        let line: Vec<char> = self.query_area.lines().get(row)?.chars().collect();
+        let is_field_char = |c: &char| c.is_alphanumeric() || matches!(c, '@' | '_' | '.' | '$');
+        let start = line[..col.min(line.len())]
+            .iter()
+            .rposition(|c| !is_field_char(c))
+            .map_or(0, |idx| idx + 1);
+        let word: String = line[start..]
+            .iter()
+            .take_while(|c| is_field_char(c))
+            .collect();
+        (!word.is_empty()).then_some(word)
     }
 
-    pub fn save_dialog_state_mut(&mut self) -> Option<&mut SaveDialogState> {
-        self.save_dialog.as_mut()
+    /// Open the CloudTrail pane over the window of the results on screen (or
+    /// of the inputs, before any run), or close it.
+    pub fn toggle_cloudtrail(&mut self) {
+        if self.cloudtrail.take().is_some() {
+            self.cloudtrail_request = None;
+            return;
+        }
+        if let Err(err) = self.single_profile_required("CloudTrail lookup") {
+            self.set_error(err);
+            return;
+        }
+        let base = match self.last_query.clone() {
+            Some(params) => params,
+            None => match self.prepare_submission() {
+                Ok(params) => params,
+                Err(err) => {
+                    self.set_error(err);
+                    return;
+                }
+            },
+        };
+        let Some(params) = cloudtrail::companion_params(&base, &self.config.cloudtrail) else {
+            self.set_error(i18n::text("error.no_cloudtrail"));
+            return;
+        };
+        if !self.check_production_run(PendingRun::CloudTrail) {
+            return;
+        }
+        self.set_status(i18n::fill(
+            "status.cloudtrail_querying",
+            &[("log_group", &params.log_group)],
+        ));
+        self.cloudtrail_requests += 1;
+        let id = self.cloudtrail_requests;
+        self.cloudtrail = Some(CloudTrailPane {
+            request: id,
+            log_group: params.log_group.clone(),
+            events: None,
+        });
+        self.cloudtrail_request = Some((id, params));
     }
 
-    pub fn open_open_dialog(&mut self, entries: Vec<QueryFileEntry>) {
-        self.open_dialog = Some(OpenDialogState::new(entries));
-        self.modal_open = false;
-        self.column_modal = None;
-        self.help_open = false;
-        self.save_dialog = None;
+    /// Fill the pane, unless the reply is for a query it was since closed or
+    /// reopened over.
+    pub fn apply_cloudtrail(&mut self, request: u64, outcome: QueryOutcome) {
+        let Some(pane) = self
+            .cloudtrail
+            .as_mut()
+            .filter(|pane| pane.request == request && pane.events.is_none())
+        else {
+            return;
+        };
+        pane.events = Some(match outcome {
+            QueryOutcome::Success(records) => Ok(cloudtrail::events(&records)),
+            QueryOutcome::Error(err) => Err(err),
+        });
     }
 
-    pub fn close_open_dialog(&mut self) {
-        self.open_dialog = None;
+    /// Send the selected row's redacted message to `[explain]`, or close the pane.
+    pub fn toggle_explain(&mut self) {
+        if self.explain.take().is_some() {
+            self.explain_request = None;
+            return;
+        }
+        if !self.config.explain.is_enabled() {
+            self.set_error(i18n::text("error.no_explain"));
+            return;
+        }
+        let Some(row) = self.selected_row_data() else {
+            self.set_status(i18n::text("status.explain_needs_row"));
+            return;
+        };
+        // The message alone, or every field when the query did not select one.
+        let text = match row.iter().find(|(header, _)| header == "@message") {
+            Some((_, message)) => message.clone(),
+            None => row
+                .iter()
+                .map(|(header, value)| format!("{header}: {value}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        let message = explain::redact(&text, &self.config.explain.redact_keys);
+        let first_line = message.lines().next().unwrap_or_default();
+        let mut subject: String = first_line.chars().take(EXPLAIN_SUBJECT_CHARS).collect();
+        if subject.len() < first_line.len() {
+            subject.push('…');
+        }
+        let log_group = self
+            .last_query
+            .as_ref()
+            .map(|params| params.log_group.clone())
+            .unwrap_or_else(|| self.log_group_input.value().to_string());
+        self.set_status(i18n::text("status.explaining"));
+        self.explain_requests += 1;
+        let id = self.explain_requests;
+        self.explain = Some(ExplainPane {
+            request: id,
+            subject,
+            reply: None,
+        });
+        self.explain_request = Some(ExplainRequest {
+            id,
+            log_group,
+            message,
+        });
     }
 
-    pub fn open_dialog_active(&self) -> bool {
-        self.open_dialog.is_some()
+    /// Show the reply to request `id`, unless its pane was closed since.
+    pub fn apply_explanation(&mut self, id: u64, reply: Result<String, String>) {
+        if let Some(pane) = self
+            .explain
+            .as_mut()
+            .filter(|pane| pane.request == id && pane.reply.is_none())
+        {
+            pane.reply = Some(reply);
+        }
     }
 
-    pub fn open_dialog_state_mut(&mut self) -> Option<&mut OpenDialogState> {
-        self.open_dialog.as_mut()
+    /// Queue a `stats count(*) by field` query over the end of the chosen range.
+    pub fn start_value_sample(&mut self) {
+        let Some(input) = self.value_field_input.take() else {
+            return;
+        };
+        let field = input.value().trim().to_string();
+        if field.is_empty() {
+            self.set_status(i18n::text("status.no_field_to_sample"));
+            return;
+        }
+        if let Err(err) = self.single_profile_required("Value sampling") {
+            self.set_error(err);
+            return;
+        }
+        let mut params = match self.prepare_submission() {
+            Ok(params) => params,
+            Err(err) => {
+                self.set_error(err);
+                return;
+            }
+        };
+        if !self.check_production_run(PendingRun::ValueSample) {
+            // Kept open behind the prompt, for confirming to sample it.
+            self.value_field_input = Some(input);
+            return;
+        }
+        let window_millis = value_picker::SAMPLE_WINDOW_MINUTES * 60_000;
+        params.start_millis = params.start_millis.max(params.end_millis - window_millis);
+        params.query = value_picker::sample_query(&field);
+        self.set_status(i18n::fill("status.sampling_values", &[("field", &field)]));
+        self.value_picker = Some(ValuePicker::new(field));
+        self.value_sample = Some(params);
     }
 
-    pub fn open_dialog_selected_path(&self) -> Option<PathBuf> {
-        self.open_dialog
-            .as_ref()
-            .and_then(|state| state.selected_entry())
-            .map(|entry| entry.path.clone())
+    /// The queued sampling query and the field it samples.
+    pub fn take_value_sample(&mut self) -> Option<(String, QueryParams)> {
+        let params = self.value_sample.take()?;
+        let field = self.value_picker.as_ref()?.field.clone();
+        Some((field, params))
     }
-}
 
-impl Default for App {
-    fn default() -> Self {
-        let AppDefaults {
-            from,
-            to,
-            log_group,
-            query,
-        } = default_app_values();
-        let aws_profiles = aws_profiles::discover_profiles();
-        let mut selected_profile_index = None;
-        if !aws_profiles.is_empty() {
-            if let Ok(env_profile) = env::var("AWS_PROFILE") {
-                let trimmed = env_profile.trim();
-                if !trimmed.is_empty() {
-                    if let Some(pos) = aws_profiles.iter().position(|p| p == trimmed) {
-                        selected_profile_index = Some(pos);
-                    }
+    /// Fill the picker with the sampled values, unless it was closed or moved on meanwhile.
+    pub fn apply_value_sample(&mut self, field: &str, outcome: QueryOutcome) {
+        let Some(picker) = self
+            .value_picker
+            .as_mut()
+            .filter(|picker| picker.field == field && picker.values.is_none())
+        else {
+            return;
+        };
+        match outcome {
+            QueryOutcome::Success(records) => {
+                picker.load(&records);
+                if picker.values.as_ref().is_some_and(Vec::is_empty) {
+                    self.value_picker = None;
+                    self.set_status(i18n::fill(
+                        "status.no_sampled_values",
+                        &[
+                            ("field", field),
+                            ("minutes", &value_picker::SAMPLE_WINDOW_MINUTES.to_string()),
+                        ],
+                    ));
                 }
             }
-            if selected_profile_index.is_none() {
-                if let Some(pos) = aws_profiles.iter().position(|p| p == "default") {
-                    selected_profile_index = Some(pos);
-                } else {
-                    selected_profile_index = Some(0);
-                }
+            QueryOutcome::Error(err) => {
+                self.value_picker = None;
+                self.set_error(i18n::fill(
+                    "error.sampling_failed",
+                    &[("field", field), ("err", &err)],
+                ));
             }
         }
-        let from_input = SingleLineInput::new(from);
-        let to_input = SingleLineInput::new(to);
-        let log_group_input = SingleLineInput::new(log_group.to_string());
-        let query_area = TextArea::from(query.lines().map(|line| line.to_string()));
-        let initial_status =
-            "Ready. Fill in the fields and press Ctrl+Enter to search.".to_string();
-        let default_relative_index = RELATIVE_RANGE_OPTIONS
-            .iter()
-            .position(|opt| opt.label == "1 hour")
-            .unwrap_or(0);
-        Self {
-            focus: FocusField::LogGroup,
-            aws_profiles,
-            selected_profile_index,
-            aws_region_input: SingleLineInput::new(resolve_default_region()),
-            inputs_collapsed: false,
-            relative_mode: true,
-            selected_relative_index: default_relative_index,
-            from_input,
-            to_input,
-            log_group_input,
-            query_area,
-            query_scroll_row: 0,
-            query_scroll_col: 0,
-            saved_query_path: None,
-            results: QueryResults::default(),
-            column_visibility: Vec::new(),
-            column_visibility_overrides: HashMap::new(),
-            column_filter_headers: Vec::new(),
-            results_initialized: false,
-            status_kind: StatusKind::Info,
-            filtered_indices: Vec::new(),
-            filter_input: SingleLineInput::new(String::new()),
-            filter_active: false,
-            filter_dirty: false,
-            last_filter_edit: None,
-            status: initial_status,
-            results_navigation: false,
-            selected_filtered_index: None,
-            modal_open: false,
-            help_open: false,
-            results_scroll: 0,
-            results_view_height: 0,
-            submitting: false,
-            column_modal: None,
-            save_dialog: None,
-            open_dialog: None,
+    }
+
+    /// Add the picked values as a `filter` line below the query cursor's line.
+    pub fn insert_value_filter(&mut self) {
+        let Some(picker) = self.value_picker.take() else {
+            return;
+        };
+        let Some(clause) = picker.clause() else {
+            return;
+        };
+        if self.query_text().trim().is_empty() {
+            self.query_area.insert_str(clause);
+        } else {
+            self.query_area.move_cursor(CursorMove::End);
+            self.query_area.insert_newline();
+            self.query_area.insert_str(format!("| {clause}"));
         }
+        self.exit_results_navigation();
+        self.inputs_collapsed = false;
+        self.focus = FocusField::Query;
+        self.set_status(i18n::fill(
+            "status.value_filter_added",
+            &[("field", &picker.field)],
+        ));
     }
-}
 
-impl App {
-    pub fn ensure_column_visibility_len(&mut self) {
-        self.sync_column_visibility();
+    /// Colour of the first highlight rule the cell satisfies.
+    pub fn cell_highlight(&self, column: usize, value: &str) -> Option<HighlightColor> {
+        let header = self.results.headers.get(column)?;
+        self.cell_rules
+            .iter()
+            .find(|rule| rule.applies_to(header) && rule.matches(value))
+            .map(|rule| rule.color)
+    }
+
+    /// Table text for a cell, humanized when it holds a recognised duration.
+    pub fn display_cell<'a>(&self, column: usize, value: &'a str) -> Cow<'a, str> {
+        if !self.humanize_durations {
+            return Cow::Borrowed(value);
+        }
+        let unit = self
+            .results
+            .headers
+            .get(column)
+            .and_then(|header| durations::column_unit(header, &self.config.durations.units));
+        match durations::parse_millis(value, unit) {
+            Some(millis) => Cow::Owned(durations::humanize(millis)),
+            None => Cow::Borrowed(value),
+        }
     }
 
     pub fn visible_column_indices(&self) -> Vec<usize> {
@@ -1214,6 +4289,23 @@ impl App {
         self.column_modal = None;
     }
 
+    /// Whether a popup or overlay is drawn over the panes.
+    pub fn popup_open(&self) -> bool {
+        self.help_open
+            || self.modal_open
+            || self.palette.is_some()
+            || self.column_stats.is_some()
+            || self.clusters.is_some()
+            || self.value_picker.is_some()
+            || self.input_pane_picker.is_some()
+            || self.query_diff.is_some()
+            || self.trace_scroll.is_some()
+            || self.date_picker_active()
+            || self.column_modal_active()
+            || self.save_dialog_active()
+            || self.open_dialog_active()
+    }
+
     pub fn column_modal_active(&self) -> bool {
         self.column_modal.is_some()
     }
@@ -1242,6 +4334,260 @@ impl App {
         self.column_modal.as_mut()
     }
 
+    /// Edit the selected row's note, starting from the one it already has.
+    pub fn open_note_dialog(&mut self) {
+        let Some(row) = self
+            .selected_filtered_index
+            .and_then(|position| self.filtered_indices.get(position).copied())
+        else {
+            self.set_status(i18n::text("status.note_needs_row"));
+            return;
+        };
+        let current = self.row_notes.get(&row).cloned().unwrap_or_default();
+        self.note_input = Some((row, SingleLineInput::new(current)));
+    }
+
+    /// Save the note being edited; an empty note removes it.
+    pub fn apply_note(&mut self) {
+        let Some((row, input)) = self.note_input.take() else {
+            return;
+        };
+        let note = input.value().trim();
+        if note.is_empty() {
+            if self.row_notes.remove(&row).is_some() {
+                self.set_status(i18n::text("status.note_removed"));
+            }
+        } else {
+            self.row_notes.insert(row, note.to_string());
+            self.set_status(i18n::fill(
+                "status.note_saved",
+                &[("count", &self.row_notes.len().to_string())],
+            ));
+        }
+    }
+
+    pub fn selected_row_note(&self) -> Option<&str> {
+        let row = self.filtered_indices.get(self.selected_filtered_index?)?;
+        self.row_notes.get(row).map(String::as_str)
+    }
+
+    fn row_timestamp(&self, column: usize, row: usize) -> Option<DateTime<Utc>> {
+        let value = self.results.cell(row, column)?;
+        export::parse_timestamp(value.trim()).map(|parsed| parsed.and_utc())
+    }
+
+    /// Markers between the filtered row at `position` and the one above it.
+    pub fn markers_before(&self, position: usize) -> Vec<&TimelineMarker> {
+        if self.timeline_markers.is_empty() || position == 0 {
+            return Vec::new();
+        }
+        let Some(column) = self
+            .results
+            .headers
+            .iter()
+            .position(|header| header == "@timestamp")
+        else {
+            return Vec::new();
+        };
+        let stamp = |position: usize| {
+            let row = *self.filtered_indices.get(position)?;
+            self.row_timestamp(column, row)
+        };
+        match (stamp(position - 1), stamp(position)) {
+            (Some(previous), Some(current)) => {
+                timeline::between(&self.timeline_markers, previous, current)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mark or unmark the selected row; only the two most recent marks are kept.
+    pub fn toggle_mark_selected_row(&mut self) -> Option<usize> {
+        let position = self.selected_filtered_index?;
+        let row = *self.filtered_indices.get(position)?;
+        if let Some(existing) = self.marked_rows.iter().position(|&marked| marked == row) {
+            self.marked_rows.remove(existing);
+        } else {
+            if self.marked_rows.len() == 2 {
+                self.marked_rows.remove(0);
+            }
+            self.marked_rows.push(row);
+        }
+        Some(self.marked_rows.len())
+    }
+
+    /// Set an absolute window bracketing the marked rows' `@timestamp` values.
+    pub fn zoom_to_marked_rows(&mut self) -> Result<(), String> {
+        if self.marked_rows.len() < 2 {
+            return Err("Mark two rows with m before zooming".into());
+        }
+        let column = self
+            .results
+            .headers
+            .iter()
+            .position(|header| header == "@timestamp")
+            .ok_or("Results have no @timestamp column to zoom on")?;
+        let mut timestamps = Vec::with_capacity(self.marked_rows.len());
+        for &row in &self.marked_rows {
+            let value = self.results.cell(row, column).unwrap_or_default();
+            let parsed = export::parse_timestamp(value.trim())
+                .ok_or_else(|| format!("Unable to parse timestamp '{value}'"))?;
+            timestamps.push(parsed.and_utc());
+        }
+        let (Some(&first), Some(&last)) = (timestamps.iter().min(), timestamps.iter().max()) else {
+            return Err("Mark two rows with m before zooming".into());
+        };
+        // Both bounds are inclusive, so the marked events sit exactly on the edges.
+        let (start, end) = (first, last);
+        self.relative_mode = false;
+        self.from_input = SingleLineInput::new(format_local_timestamp(start));
+        self.to_input = SingleLineInput::new(format_local_timestamp(end));
+        Ok(())
+    }
+
+    /// Shift or resize the time window, switching a relative range to the
+    /// equivalent absolute one first.
+    pub fn nudge_time_window(&mut self, nudge: WindowNudge) -> Result<(), String> {
+        let (start, end) = if self.relative_mode {
+            let end = Utc::now().trunc_subsecs(0);
+            (
+                end - ChronoDuration::seconds(self.current_relative_option().seconds),
+                end,
+            )
+        } else {
+            let start = parse_datetime(self.from_input.value())?;
+            let end = parse_datetime(self.to_input.value())?;
+            if end <= start {
+                return Err("End time must be after start time".into());
+            }
+            (start, end)
+        };
+        let span = end - start;
+        let (start, end) = match nudge {
+            WindowNudge::Earlier => (start - span, start),
+            WindowNudge::Later => (end, end + span),
+            WindowNudge::Narrower | WindowNudge::Wider => {
+                let center = start + span / 2;
+                let half = if nudge == WindowNudge::Narrower {
+                    (span / 4).max(ChronoDuration::seconds(1))
+                } else {
+                    span
+                };
+                (center - half, center + half)
+            }
+        };
+        self.relative_mode = false;
+        self.from_input = SingleLineInput::new(format_local_timestamp(start));
+        self.to_input = SingleLineInput::new(format_local_timestamp(end));
+        Ok(())
+    }
+
+    /// Pre-fill region, log group, query and time range from a pasted console URL.
+    pub fn apply_console_link(&mut self, link: ConsoleLink) {
+        if let Some(region) = link.region {
+            self.aws_region_input = SingleLineInput::new(region);
+        }
+        if !link.log_groups.is_empty() {
+            self.log_group_input = SingleLineInput::new(link.log_groups.join(", "));
+        }
+        if let Some(query) = link.query {
+            self.replace_query_text(query);
+            self.saved_query_path = None;
+            self.query_baseline = self.query_text();
+        }
+        let window = match link.range {
+            Some(LinkRange::Relative { seconds }) => {
+                match RELATIVE_RANGE_OPTIONS
+                    .iter()
+                    .position(|option| option.seconds == seconds)
+                {
+                    Some(idx) => {
+                        self.relative_mode = true;
+                        self.selected_relative_index = idx;
+                        None
+                    }
+                    None => {
+                        let end = Utc::now().trunc_subsecs(0);
+                        Some((end - ChronoDuration::seconds(seconds), end))
+                    }
+                }
+            }
+            Some(LinkRange::Absolute { start, end }) => Some((start, end)),
+            None => None,
+        };
+        if let Some((start, end)) = window {
+            self.relative_mode = false;
+            self.from_input = SingleLineInput::new(format_local_timestamp(start));
+            self.to_input = SingleLineInput::new(format_local_timestamp(end));
+        }
+        if link.log_groups.len() > 1 {
+            self.set_status(i18n::fill(
+                "status.console_link_groups",
+                &[("count", &link.log_groups.len().to_string())],
+            ));
+        } else {
+            self.set_status(i18n::text("status.console_link_loaded"));
+        }
+    }
+
+    /// Console deep link reproducing the current inputs.
+    pub fn console_link_url(&self) -> Result<String, String> {
+        let params = self.prepare_submission()?;
+        let range = if self.relative_mode {
+            LinkRange::Relative {
+                seconds: self.current_relative_option().seconds,
+            }
+        } else {
+            let bound = |millis| {
+                DateTime::from_timestamp_millis(millis).ok_or("Time range is out of bounds")
+            };
+            LinkRange::Absolute {
+                start: bound(params.start_millis)?,
+                end: bound(params.end_millis)?,
+            }
+        };
+        let log_groups = params.log_group_names().map(str::to_string).collect();
+        Ok(console_url::build(&ConsoleLink {
+            region: Some(params.region),
+            log_groups,
+            query: Some(params.query),
+            range: Some(range),
+        }))
+    }
+
+    pub fn open_date_picker(&mut self, field: FocusField) {
+        let input = match field {
+            FocusField::From => &self.from_input,
+            FocusField::To => &self.to_input,
+            _ => return,
+        };
+        let initial = parse_datetime(input.value())
+            .map(|value| value.with_timezone(&Local).naive_local())
+            .unwrap_or_else(|_| Local::now().naive_local());
+        self.date_picker = Some((field, DatePickerState::new(initial)));
+    }
+
+    pub fn close_date_picker(&mut self) {
+        self.date_picker = None;
+    }
+
+    pub fn date_picker_active(&self) -> bool {
+        self.date_picker.is_some()
+    }
+
+    /// Write the picked value back into the field the picker was opened from.
+    pub fn apply_date_picker(&mut self) {
+        let Some((field, state)) = self.date_picker.take() else {
+            return;
+        };
+        let formatted = time_format::format(state.value(), false);
+        match field {
+            FocusField::From => self.from_input = SingleLineInput::new(formatted),
+            FocusField::To => self.to_input = SingleLineInput::new(formatted),
+            _ => {}
+        }
+    }
+
     pub fn adjust_absolute_input(&mut self, field: FocusField, delta_seconds: i64) {
         if delta_seconds == 0 || self.relative_mode {
             return;
@@ -1257,9 +4603,7 @@ impl App {
         }
         if let Ok(datetime_utc) = parse_datetime(&original) {
             let adjusted = datetime_utc + ChronoDuration::seconds(delta_seconds);
-            let local_dt = adjusted.with_timezone(&Local);
-            let formatted = local_dt.format("%Y-%m-%d %H:%M:%S").to_string();
-            *target = SingleLineInput::new(formatted);
+            *target = SingleLineInput::new(format_local_timestamp(adjusted));
         }
     }
 }
@@ -1270,12 +4614,8 @@ pub fn parse_datetime(input: &str) -> Result<DateTime<Utc>, String> {
         return Err("Time value is required".into());
     }
 
-    let naive = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S")
-        .or_else(|_| NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M"))
-        .or_else(|_| {
-            NaiveDate::parse_from_str(trimmed, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN))
-        })
-        .map_err(|_| "Use YYYY-MM-DD[ HH:MM[:SS]] format".to_string())?;
+    let naive = time_format::parse(trimmed)
+        .ok_or_else(|| format!("Use {}[ HH:MM[:SS[.mmm]]] format", time_format::hint()))?;
 
     match Local.from_local_datetime(&naive) {
         LocalResult::Single(local_dt) => Ok(local_dt.with_timezone(&Utc)),
@@ -1,8 +1,23 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
+/// Profile names and the accounts they name, read together off the UI thread.
+#[derive(Clone, Default)]
+pub struct ProfileDiscovery {
+    pub profiles: Vec<String>,
+    pub accounts: HashMap<String, String>,
+}
+
+/// Read the AWS config and credentials files; may block on slow home directories.
+pub fn discover() -> ProfileDiscovery {
+    ProfileDiscovery {
+        profiles: discover_profiles(),
+        accounts: discover_profile_accounts(),
+    }
+}
+
 /// Collect AWS profile names from credentials and config files.
 pub fn discover_profiles() -> Vec<String> {
     let mut profiles = Vec::new();
@@ -33,6 +48,80 @@ pub fn discover_profiles() -> Vec<String> {
     profiles
 }
 
+/// Account ID of each profile in the config file that names one, through
+/// `sso_account_id` or the account in `role_arn`.
+pub fn discover_profile_accounts() -> HashMap<String, String> {
+    let mut accounts = HashMap::new();
+    for path in config_paths() {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut profile: Option<String> = None;
+        for line in contents.lines() {
+            if line.trim().starts_with('[') {
+                profile = extract_section_name(line, true);
+                continue;
+            }
+            let (Some(name), Some((key, value))) = (profile.as_ref(), line.split_once('=')) else {
+                continue;
+            };
+            let value = value.trim();
+            let account = match key.trim() {
+                "sso_account_id" => Some(value),
+                // arn:aws:iam::123456789012:role/name
+                "role_arn" => value.split(':').nth(4),
+                _ => None,
+            };
+            if let Some(account) = account.filter(|account| !account.is_empty()) {
+                accounts
+                    .entry(name.clone())
+                    .or_insert_with(|| account.to_string());
+            }
+        }
+    }
+    accounts
+}
+
+/// Region and sign-in settings of one profile in the AWS config file.
+#[derive(Default)]
+pub struct ProfileSettings {
+    pub region: Option<String>,
+    /// `sso_session` name or `sso_start_url` of profiles that sign in through
+    /// IAM Identity Center.
+    pub sso: Option<String>,
+}
+
+/// Settings of `profile`, read afresh so edits since startup count.
+pub fn profile_settings(profile: &str) -> ProfileSettings {
+    let mut settings = ProfileSettings::default();
+    for path in config_paths() {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut current = false;
+        for line in contents.lines() {
+            if line.trim().starts_with('[') {
+                current = extract_section_name(line, true).as_deref() == Some(profile);
+                continue;
+            }
+            let (true, Some((key, value))) = (current, line.split_once('=')) else {
+                continue;
+            };
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let slot = match key.trim() {
+                "region" => &mut settings.region,
+                "sso_session" | "sso_start_url" => &mut settings.sso,
+                _ => continue,
+            };
+            slot.get_or_insert_with(|| value.to_string());
+        }
+    }
+    settings
+}
+
 fn credentials_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
     if let Ok(custom) = env::var("AWS_SHARED_CREDENTIALS_FILE") {
@@ -65,7 +154,7 @@ fn config_paths() -> Vec<PathBuf> {
     paths
 }
 
-fn home_dir() -> Option<PathBuf> {
+pub fn home_dir() -> Option<PathBuf> {
     if let Ok(home) = env::var("HOME") {
         if !home.is_empty() {
             return Some(PathBuf::from(home));
@@ -0,0 +1,105 @@
+use std::ops::RangeInclusive;
+
+use tui_textarea::{CursorMove, TextArea};
+
+/// Column caret spanning several query lines, so similar clauses can be
+/// commented out or changed together. Lines shorter than the caret column are
+/// left alone.
+#[derive(Clone, Copy)]
+pub struct BlockCaret {
+    anchor_row: usize,
+    row: usize,
+    col: usize,
+}
+
+impl BlockCaret {
+    /// Start at the editor cursor.
+    pub fn at_cursor(area: &TextArea) -> Self {
+        let (row, col) = area.cursor();
+        Self {
+            anchor_row: row,
+            row,
+            col,
+        }
+    }
+
+    pub fn rows(&self) -> RangeInclusive<usize> {
+        self.anchor_row.min(self.row)..=self.anchor_row.max(self.row)
+    }
+
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    /// Grow or shrink the block a line at a time from its moving end.
+    pub fn extend(&mut self, area: &mut TextArea, delta: i32) {
+        let last = area.lines().len().saturating_sub(1) as i32;
+        self.row = (self.row as i32 + delta).clamp(0, last) as usize;
+        self.place_cursor(area);
+    }
+
+    pub fn move_col(&mut self, area: &mut TextArea, delta: i32) {
+        let widest = self
+            .rows()
+            .filter_map(|row| area.lines().get(row))
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as i32;
+        self.col = (self.col as i32 + delta).clamp(0, widest) as usize;
+        self.place_cursor(area);
+    }
+
+    pub fn insert(&mut self, area: &mut TextArea, text: &str) {
+        for row in self.reachable_rows(area, self.col) {
+            jump(area, row, self.col);
+            area.insert_str(text);
+        }
+        self.col += text.chars().count();
+        self.place_cursor(area);
+    }
+
+    /// Delete the character before the caret on every line.
+    pub fn backspace(&mut self, area: &mut TextArea) {
+        if self.col == 0 {
+            return;
+        }
+        for row in self.reachable_rows(area, self.col) {
+            jump(area, row, self.col);
+            area.delete_char();
+        }
+        self.col -= 1;
+        self.place_cursor(area);
+    }
+
+    /// Delete the character after the caret on every line.
+    pub fn delete(&mut self, area: &mut TextArea) {
+        for row in self.reachable_rows(area, self.col + 1) {
+            jump(area, row, self.col);
+            area.delete_next_char();
+        }
+        self.place_cursor(area);
+    }
+
+    /// Lines in the block with at least `min_len` characters.
+    fn reachable_rows(&self, area: &TextArea, min_len: usize) -> Vec<usize> {
+        self.rows()
+            .filter(|&row| {
+                area.lines()
+                    .get(row)
+                    .is_some_and(|line| line.chars().count() >= min_len)
+            })
+            .collect()
+    }
+
+    fn place_cursor(&self, area: &mut TextArea) {
+        jump(area, self.row, self.col);
+    }
+}
+
+/// Put the editor cursor at `(row, col)`, clamped to the text.
+pub fn jump(area: &mut TextArea, row: usize, col: usize) {
+    area.move_cursor(CursorMove::Jump(
+        row.min(u16::MAX as usize) as u16,
+        col.min(u16::MAX as usize) as u16,
+    ));
+}
@@ -0,0 +1,93 @@
+use std::env;
+use std::io::{self, Write};
+
+use std::borrow::Cow;
+
+use arboard::{Clipboard, ImageData};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    /// Use the system clipboard, falling back to OSC 52 when it is unavailable.
+    #[default]
+    Auto,
+    /// Only use the system clipboard (X11, Wayland, macOS, Windows).
+    System,
+    /// Always emit an OSC 52 escape sequence for the terminal to handle.
+    Osc52,
+}
+
+/// Copy `text`, returning a short description of where it went.
+pub fn copy_text(text: String, backend: ClipboardBackend) -> Result<&'static str, String> {
+    match backend {
+        ClipboardBackend::System => copy_system(text),
+        ClipboardBackend::Osc52 => copy_osc52(&text),
+        ClipboardBackend::Auto => {
+            if prefers_osc52() {
+                return copy_osc52(&text);
+            }
+            copy_system(text.clone()).or_else(|_| copy_osc52(&text))
+        }
+    }
+}
+
+/// Remote sessions have no display server of their own, so the local terminal
+/// is the only clipboard within reach.
+fn prefers_osc52() -> bool {
+    let remote = env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+    let headless = cfg!(all(unix, not(target_os = "macos")))
+        && env::var_os("DISPLAY").is_none()
+        && env::var_os("WAYLAND_DISPLAY").is_none();
+    remote || headless
+}
+
+/// Copy an RGBA image. OSC 52 only carries text, so this needs the system clipboard.
+pub fn copy_image(
+    width: usize,
+    height: usize,
+    rgba: Vec<u8>,
+    backend: ClipboardBackend,
+) -> Result<&'static str, String> {
+    if backend == ClipboardBackend::Osc52 || (backend == ClipboardBackend::Auto && prefers_osc52())
+    {
+        return Err("Images can only be copied to the system clipboard".into());
+    }
+    let mut clipboard =
+        Clipboard::new().map_err(|err| format!("Unable to access clipboard: {err}"))?;
+    clipboard
+        .set_image(ImageData {
+            width,
+            height,
+            bytes: Cow::Owned(rgba),
+        })
+        .map_err(|err| format!("Unable to copy image to clipboard: {err}"))?;
+    Ok("clipboard")
+}
+
+fn copy_system(text: String) -> Result<&'static str, String> {
+    let mut clipboard =
+        Clipboard::new().map_err(|err| format!("Unable to access clipboard: {err}"))?;
+    clipboard
+        .set_text(text)
+        .map_err(|err| format!("Unable to copy to clipboard: {err}"))?;
+    Ok("clipboard")
+}
+
+fn copy_osc52(text: &str) -> Result<&'static str, String> {
+    let encoded = STANDARD.encode(text);
+    let sequence = if env::var_os("TMUX").is_some() {
+        // tmux only forwards escape sequences wrapped in its DCS passthrough.
+        format!("\x1bPtmux;\x1b\x1b]52;c;{encoded}\x07\x1b\\")
+    } else {
+        format!("\x1b]52;c;{encoded}\x07")
+    };
+    let mut stdout = io::stdout();
+    stdout
+        .write_all(sequence.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|err| format!("Unable to write OSC 52 sequence: {err}"))?;
+    Ok("terminal clipboard (OSC 52)")
+}
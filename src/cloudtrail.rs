@@ -0,0 +1,65 @@
+use crate::config::CloudTrailConfig;
+use crate::log_fetcher::{LogRecord, QueryParams};
+
+/// Every call that is not a read, newest first: the changes that tend to
+/// explain a sudden spike of errors.
+pub const DEFAULT_QUERY: &str = "fields @timestamp, eventSource, eventName, userIdentity.arn
+| filter eventName not like /^(Get|List|Describe|Lookup|Head)/
+| sort @timestamp desc
+| limit 200";
+
+/// One CloudTrail event as listed in the pane.
+pub struct TrailEvent {
+    pub time: String,
+    /// The event's other fields, e.g. `iam  AttachRolePolicy  arn:aws:sts::...`.
+    pub summary: String,
+}
+
+/// CloudTrail events over the window of the query on screen, shown beside
+/// its results.
+pub struct CloudTrailPane {
+    /// Number of the query filling the pane; replies to earlier ones are dropped.
+    pub request: u64,
+    pub log_group: String,
+    /// `None` while the query runs.
+    pub events: Option<Result<Vec<TrailEvent>, String>>,
+}
+
+/// The companion query: `base`'s window against the trail's log group, in
+/// the trail's own account and region when they are configured.
+pub fn companion_params(base: &QueryParams, config: &CloudTrailConfig) -> Option<QueryParams> {
+    let log_group = config
+        .log_group
+        .as_deref()
+        .map(str::trim)
+        .filter(|log_group| !log_group.is_empty())?;
+    Some(QueryParams {
+        log_group: log_group.to_string(),
+        query: config.query.clone(),
+        region: config.region.clone().unwrap_or_else(|| base.region.clone()),
+        profile: config.profile.clone().or_else(|| base.profile.clone()),
+        ..base.clone()
+    })
+}
+
+pub fn events(records: &[LogRecord]) -> Vec<TrailEvent> {
+    records
+        .iter()
+        .map(|record| {
+            let mut time = String::new();
+            let mut parts = Vec::new();
+            for field in record {
+                match field.name.as_deref() {
+                    Some("@timestamp") => time = field.value.chars().take(19).collect(),
+                    Some(name) if name.starts_with('@') => {}
+                    _ if field.value.is_empty() => {}
+                    _ => parts.push(field.value.trim_end_matches(".amazonaws.com")),
+                }
+            }
+            TrailEvent {
+                time,
+                summary: parts.join("  "),
+            }
+        })
+        .collect()
+}
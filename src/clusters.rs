@@ -0,0 +1,89 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Stands in for the variable parts of a message.
+const MASK: &str = "<*>";
+/// Tokens compared per message; stack traces and long payloads differ mostly after this.
+const MAX_TOKENS: usize = 40;
+
+/// Messages sharing a fingerprint, with the first one seen as the example.
+pub struct Cluster {
+    pub fingerprint: String,
+    pub count: usize,
+    /// Index into the result rows of the example message.
+    pub example_row: usize,
+    pub example: String,
+}
+
+/// Clusters of one column over the filtered rows, largest first.
+pub struct ClusterView {
+    pub header: String,
+    pub clusters: Vec<Cluster>,
+    pub selected: usize,
+}
+
+/// The message with numbers, IDs, and other digit-bearing tokens masked, so
+/// `timeout after 3001 ms` and `timeout after 250 ms` fingerprint alike.
+pub fn fingerprint(message: &str) -> String {
+    message
+        .split_whitespace()
+        .take(MAX_TOKENS)
+        .map(mask_token)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `key=value` and `key:value` keep the key when only the value varies.
+fn mask_token(token: &str) -> String {
+    if !token.chars().any(|c| c.is_ascii_digit()) {
+        return token.to_string();
+    }
+    match token.rfind(['=', ':']) {
+        Some(at) if at > 0 && !token[..at].chars().any(|c| c.is_ascii_digit()) => {
+            format!("{}{MASK}", &token[..=at])
+        }
+        _ => MASK.to_string(),
+    }
+}
+
+/// Group `(row, message)` pairs by fingerprint; ties keep first-seen order.
+pub fn build<'a>(header: &str, messages: impl Iterator<Item = (usize, &'a str)>) -> ClusterView {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (row, message) in messages {
+        let fingerprint = fingerprint(message);
+        match positions.get(&fingerprint) {
+            Some(&idx) => clusters[idx].count += 1,
+            None => {
+                positions.insert(fingerprint.clone(), clusters.len());
+                clusters.push(Cluster {
+                    fingerprint,
+                    count: 1,
+                    example_row: row,
+                    example: message.to_string(),
+                });
+            }
+        }
+    }
+    // Stable sort, so equal counts stay in the order they first appeared.
+    clusters.sort_by_key(|cluster| Reverse(cluster.count));
+    ClusterView {
+        header: header.to_string(),
+        clusters,
+        selected: 0,
+    }
+}
+
+impl ClusterView {
+    pub fn move_selection(&mut self, delta: i32) {
+        let len = self.clusters.len();
+        if len == 0 {
+            return;
+        }
+        self.selected = (self.selected as i32 + delta).clamp(0, len as i32 - 1) as usize;
+    }
+
+    pub fn selected_cluster(&self) -> Option<&Cluster> {
+        self.clusters.get(self.selected)
+    }
+}
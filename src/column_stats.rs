@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+/// Distinct values listed for text columns.
+const TOP_VALUES: usize = 10;
+
+/// Summary of one column over the filtered rows.
+pub struct ColumnStats {
+    pub header: String,
+    /// Rows with a non-empty value.
+    pub count: usize,
+    pub empty: usize,
+    pub kind: StatsKind,
+}
+
+pub enum StatsKind {
+    Numeric {
+        min: f64,
+        max: f64,
+        mean: f64,
+        p95: f64,
+    },
+    Text {
+        distinct: usize,
+        /// Most frequent values, ties broken alphabetically.
+        top: Vec<(String, usize)>,
+    },
+}
+
+/// Numeric when every non-empty value parses as a number, otherwise a value count.
+pub fn compute<'a>(header: &str, values: impl Iterator<Item = &'a str>) -> ColumnStats {
+    let mut empty = 0;
+    let mut present = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() {
+            empty += 1;
+        } else {
+            present.push(value);
+        }
+    }
+    let numbers: Option<Vec<f64>> = present
+        .iter()
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+        })
+        .collect();
+    let kind = match numbers {
+        Some(mut numbers) if !numbers.is_empty() => {
+            numbers.sort_by(f64::total_cmp);
+            let sum: f64 = numbers.iter().sum();
+            // Nearest-rank percentile, so the result is always an observed value.
+            let rank = ((numbers.len() as f64) * 0.95).ceil() as usize;
+            StatsKind::Numeric {
+                min: numbers[0],
+                max: numbers[numbers.len() - 1],
+                mean: sum / numbers.len() as f64,
+                p95: numbers[rank.saturating_sub(1)],
+            }
+        }
+        _ => {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for value in &present {
+                *counts.entry(value).or_default() += 1;
+            }
+            let distinct = counts.len();
+            let mut top: Vec<(String, usize)> = counts
+                .into_iter()
+                .map(|(value, count)| (value.to_string(), count))
+                .collect();
+            top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            top.truncate(TOP_VALUES);
+            StatsKind::Text { distinct, top }
+        }
+    };
+    ColumnStats {
+        header: header.to_string(),
+        count: present.len(),
+        empty,
+        kind,
+    }
+}
+
+/// Whole numbers print without decimals; others keep up to three places.
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.0}")
+    } else {
+        let text = format!("{value:.3}");
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+impl ColumnStats {
+    /// Plain lines shared by the popover and linear mode.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{} values, {} empty", self.count, self.empty)];
+        match &self.kind {
+            StatsKind::Numeric {
+                min,
+                max,
+                mean,
+                p95,
+            } => {
+                lines.push(format!("min  {}", format_number(*min)));
+                lines.push(format!("max  {}", format_number(*max)));
+                lines.push(format!("avg  {}", format_number(*mean)));
+                lines.push(format!("p95  {}", format_number(*p95)));
+            }
+            StatsKind::Text { distinct, top } => {
+                lines.push(format!("{distinct} distinct; most common:"));
+                for (value, count) in top {
+                    let shown: String = value.chars().take(60).collect();
+                    lines.push(format!("{count:>6}  {shown}"));
+                }
+            }
+        }
+        lines
+    }
+}
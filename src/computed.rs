@@ -0,0 +1,329 @@
+use serde::Deserialize;
+
+use crate::column_stats::format_number;
+
+/// Column computed from each result row, e.g. `name = "Seconds"`,
+/// `expr = 'col("Elapsed") / 1000'`.
+#[derive(Clone, Deserialize)]
+pub struct ComputedColumnConfig {
+    pub name: String,
+    pub expr: String,
+}
+
+/// Parsed computed column, ready to evaluate against rows.
+#[derive(Clone)]
+pub struct ComputedColumn {
+    pub name: String,
+    expr: Expr,
+}
+
+#[derive(Clone)]
+enum Expr {
+    Number(f64),
+    Text(String),
+    Column(String),
+    Negate(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Clone, Copy)]
+enum Function {
+    Concat,
+    Lower,
+    Upper,
+    Len,
+    Round,
+    Abs,
+    Coalesce,
+}
+
+const FUNCTIONS: [(&str, Function); 7] = [
+    ("concat", Function::Concat),
+    ("lower", Function::Lower),
+    ("upper", Function::Upper),
+    ("len", Function::Len),
+    ("round", Function::Round),
+    ("abs", Function::Abs),
+    ("coalesce", Function::Coalesce),
+];
+
+/// Parse the configured columns, skipping (and reporting the first) bad expression.
+pub fn compile(columns: &[ComputedColumnConfig]) -> (Vec<ComputedColumn>, Option<String>) {
+    let mut compiled = Vec::with_capacity(columns.len());
+    let mut warning = None;
+    for column in columns {
+        match ComputedColumn::parse(&column.name, &column.expr) {
+            Ok(parsed) => compiled.push(parsed),
+            Err(err) => {
+                warning.get_or_insert_with(|| {
+                    format!("Ignoring computed column {}: {err}", column.name)
+                });
+            }
+        }
+    }
+    (compiled, warning)
+}
+
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+impl ComputedColumn {
+    /// Parse `expr`: numbers, "strings", `col("name")`, `+ - * /`, parentheses,
+    /// and concat, lower, upper, len, round, abs, coalesce.
+    pub fn parse(name: &str, expr: &str) -> Result<Self, String> {
+        let tokens = tokenize(expr)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let parsed = parser.expression()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(format!("Unexpected {} in '{expr}'", token.describe()));
+        }
+        Ok(Self {
+            name: name.trim().to_string(),
+            expr: parsed,
+        })
+    }
+
+    /// Parse a `Name = expression` line typed at runtime.
+    pub fn parse_definition(definition: &str) -> Result<Self, String> {
+        let (name, expr) = definition
+            .split_once('=')
+            .ok_or_else(|| "Write the column as Name = expression".to_string())?;
+        if name.trim().is_empty() {
+            return Err("The computed column needs a name".to_string());
+        }
+        Self::parse(name, expr)
+    }
+
+    /// Value for one row; empty when an input is missing or not a number.
+    pub fn evaluate(&self, headers: &[String], cells: &[String]) -> String {
+        match evaluate(&self.expr, headers, cells) {
+            Some(Value::Number(number)) if number.is_finite() => format_number(number),
+            Some(Value::Text(text)) => text,
+            _ => String::new(),
+        }
+    }
+}
+
+fn evaluate(expr: &Expr, headers: &[String], cells: &[String]) -> Option<Value> {
+    Some(match expr {
+        Expr::Number(number) => Value::Number(*number),
+        Expr::Text(text) => Value::Text(text.clone()),
+        Expr::Column(name) => {
+            let idx = headers.iter().position(|header| header == name)?;
+            Value::Text(cells.get(idx)?.clone())
+        }
+        Expr::Negate(inner) => Value::Number(-number(evaluate(inner, headers, cells)?)?),
+        Expr::Binary(op, left, right) => {
+            let left = number(evaluate(left, headers, cells)?)?;
+            let right = number(evaluate(right, headers, cells)?)?;
+            Value::Number(match op {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                _ => left / right,
+            })
+        }
+        Expr::Call(function, args) => {
+            let mut values = args.iter().map(|arg| evaluate(arg, headers, cells));
+            match function {
+                Function::Concat => Value::Text(
+                    values
+                        .map(|value| value.map(text).unwrap_or_default())
+                        .collect(),
+                ),
+                Function::Lower => Value::Text(text(values.next()??).to_lowercase()),
+                Function::Upper => Value::Text(text(values.next()??).to_uppercase()),
+                Function::Len => Value::Number(text(values.next()??).chars().count() as f64),
+                Function::Abs => Value::Number(number(values.next()??)?.abs()),
+                Function::Round => {
+                    let value = number(values.next()??)?;
+                    let digits = match values.next() {
+                        Some(digits) => number(digits?)?,
+                        None => 0.0,
+                    };
+                    let scale = 10f64.powi(digits as i32);
+                    Value::Number((value * scale).round() / scale)
+                }
+                Function::Coalesce => values
+                    .flatten()
+                    .find(|value| !matches!(value, Value::Text(text) if text.trim().is_empty()))?,
+            }
+        }
+    })
+}
+
+fn number(value: Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => Some(number),
+        Value::Text(text) => text.trim().parse().ok(),
+    }
+}
+
+fn text(value: Value) -> String {
+    match value {
+        Value::Number(number) => format_number(number),
+        Value::Text(text) => text,
+    }
+}
+
+enum Token {
+    Number(f64),
+    Text(String),
+    Name(String),
+    Symbol(char),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(number) => format_number(*number),
+            Token::Text(text) => format!("\"{text}\""),
+            Token::Name(name) => name.clone(),
+            Token::Symbol(symbol) => format!("'{symbol}'"),
+        }
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let ch = chars[idx];
+        if ch.is_whitespace() {
+            idx += 1;
+        } else if ch.is_ascii_digit() || ch == '.' {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_ascii_digit() || chars[idx] == '.') {
+                idx += 1;
+            }
+            let literal: String = chars[start..idx].iter().collect();
+            let number = literal
+                .parse()
+                .map_err(|_| format!("'{literal}' is not a number"))?;
+            tokens.push(Token::Number(number));
+        } else if ch == '"' || ch == '\'' {
+            let mut text = String::new();
+            idx += 1;
+            loop {
+                match chars.get(idx) {
+                    None => return Err(format!("Unclosed string in '{expr}'")),
+                    Some(&c) if c == ch => break,
+                    Some('\\') if idx + 1 < chars.len() => {
+                        text.push(chars[idx + 1]);
+                        idx += 2;
+                    }
+                    Some(&c) => {
+                        text.push(c);
+                        idx += 1;
+                    }
+                }
+            }
+            idx += 1;
+            tokens.push(Token::Text(text));
+        } else if ch.is_alphabetic() || ch == '_' {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_alphanumeric() || chars[idx] == '_') {
+                idx += 1;
+            }
+            tokens.push(Token::Name(chars[start..idx].iter().collect()));
+        } else if "+-*/(),".contains(ch) {
+            tokens.push(Token::Symbol(ch));
+            idx += 1;
+        } else {
+            return Err(format!("Unexpected '{ch}' in '{expr}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn expression(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        while let Some(op) = self.symbol_in("+-") {
+            let right = self.term()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.factor()?;
+        while let Some(op) = self.symbol_in("*/") {
+            let right = self.factor()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| "Expression ends too early".to_string())?;
+        self.pos += 1;
+        match token {
+            Token::Number(number) => Ok(Expr::Number(*number)),
+            Token::Text(text) => Ok(Expr::Text(text.clone())),
+            Token::Symbol('-') => Ok(Expr::Negate(Box::new(self.factor()?))),
+            Token::Symbol('(') => {
+                let inner = self.expression()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Token::Name(name) => {
+                let name = name.clone();
+                self.expect('(')?;
+                let mut args = Vec::new();
+                if self.symbol_in(")").is_none() {
+                    loop {
+                        args.push(self.expression()?);
+                        if self.symbol_in(")").is_some() {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                if name.eq_ignore_ascii_case("col") {
+                    return match args.as_slice() {
+                        [Expr::Text(column)] => Ok(Expr::Column(column.clone())),
+                        _ => Err("col() takes one quoted column name".to_string()),
+                    };
+                }
+                let function = FUNCTIONS
+                    .iter()
+                    .find(|(known, _)| known.eq_ignore_ascii_case(&name))
+                    .map(|(_, function)| *function)
+                    .ok_or_else(|| format!("Unknown function {name}()"))?;
+                Ok(Expr::Call(function, args))
+            }
+            other => Err(format!("Unexpected {}", other.describe())),
+        }
+    }
+
+    fn symbol_in(&mut self, symbols: &str) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Symbol(symbol)) if symbols.contains(*symbol) => {
+                self.pos += 1;
+                Some(*symbol)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        if self.symbol_in(&symbol.to_string()).is_some() {
+            Ok(())
+        } else {
+            Err(format!("Expected '{symbol}'"))
+        }
+    }
+}
@@ -0,0 +1,716 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::aws_profiles::home_dir;
+use crate::clipboard::ClipboardBackend;
+use crate::cloudtrail;
+use crate::computed::ComputedColumnConfig;
+use crate::durations::DurationUnit;
+use crate::focus::FocusField;
+use crate::highlight::HighlightRule;
+use crate::input_panes::InputPane;
+use crate::keymap::KeyChord;
+use crate::log_fetcher::QueryEngine;
+use crate::relative_ranges::relative_range_index;
+use crate::renderers::RendererConfig;
+use crate::snippets::Snippet;
+use crate::time_format::DateOrder;
+
+const CONFIG_ENV: &str = "AWSLOGS_CONFIG";
+const WEBHOOK_ENV: &str = "AWSLOGS_WEBHOOK_URL";
+const PUSHGATEWAY_ENV: &str = "AWSLOGS_PUSHGATEWAY_URL";
+const OPENSEARCH_PASSWORD_ENV: &str = "AWSLOGS_OPENSEARCH_PASSWORD";
+const OPENSEARCH_API_KEY_ENV: &str = "AWSLOGS_OPENSEARCH_API_KEY";
+
+/// User settings read from `~/.config/awslogs/config.toml` (or `$AWSLOGS_CONFIG`).
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub general: GeneralConfig,
+    pub startup: StartupConfig,
+    pub query: QueryConfig,
+    pub export: ExportConfig,
+    pub share: ShareConfig,
+    pub opensearch: OpenSearchConfig,
+    pub metrics: MetricsConfig,
+    pub hooks: HooksConfig,
+    pub clipboard: ClipboardConfig,
+    pub keys: KeysConfig,
+    pub time: TimeConfig,
+    pub network: NetworkConfig,
+    pub cache: CacheConfig,
+    pub links: LinksConfig,
+    pub timeline: TimelineConfig,
+    pub durations: DurationsConfig,
+    pub sampling: SamplingConfig,
+    pub guard: GuardConfig,
+    pub production: ProductionConfig,
+    pub cloudtrail: CloudTrailConfig,
+    pub enrich: EnrichConfig,
+    pub explain: ExplainConfig,
+    pub default_queries: Vec<DefaultQueryRule>,
+    pub presets: Vec<EnvironmentPreset>,
+    pub profile_groups: Vec<ProfileGroup>,
+    pub highlights: Vec<HighlightRule>,
+    pub computed: Vec<ComputedColumnConfig>,
+    pub renderers: Vec<RendererConfig>,
+    pub snippets: Vec<Snippet>,
+    /// Starting `{{name}}` values for every new workspace.
+    pub variables: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Query body of the first rule matching the log group (and profile, when the rule names one).
+    pub fn default_query_for(&self, profile: Option<&str>, log_group: &str) -> Option<&str> {
+        self.default_queries
+            .iter()
+            .find(|rule| {
+                wildcard_match(&rule.log_group, log_group)
+                    && rule
+                        .profile
+                        .as_deref()
+                        .is_none_or(|wanted| profile == Some(wanted))
+            })
+            .map(|rule| rule.query.as_str())
+    }
+}
+
+/// Query loaded automatically when the log group is selected.
+#[derive(Clone, Deserialize)]
+pub struct DefaultQueryRule {
+    /// Log group name; `*` matches any run of characters.
+    pub log_group: String,
+    pub profile: Option<String>,
+    pub query: String,
+}
+
+/// Named profile/region/log group bundle switched with Ctrl+P.
+#[derive(Clone, Deserialize)]
+pub struct EnvironmentPreset {
+    pub name: String,
+    pub profile: Option<String>,
+    pub region: Option<String>,
+    /// Log group, or the prefix to start typing from.
+    pub log_group: Option<String>,
+    /// Defaults to true when the name mentions "prod".
+    pub production: Option<bool>,
+}
+
+impl EnvironmentPreset {
+    pub fn is_production(&self) -> bool {
+        self.production
+            .unwrap_or_else(|| self.name.to_lowercase().contains("prod"))
+    }
+}
+
+/// Profiles queried together, picked after the single profiles in the
+/// profile picker; the query runs in each and the rows are merged.
+#[derive(Clone, Deserialize)]
+pub struct ProfileGroup {
+    pub name: String,
+    pub profiles: Vec<String>,
+}
+
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct GeneralConfig {
+    /// Ask before quitting while a query runs or the query has unsaved edits.
+    pub confirm_quit: bool,
+    /// Flash a background tab when its query finishes and say how to jump there.
+    pub focus_follows_activity: bool,
+    /// UI language, e.g. `de`; defaults to the `LANG` environment.
+    pub locale: Option<String>,
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Self {
+            confirm_quit: true,
+            focus_follows_activity: false,
+            locale: None,
+        }
+    }
+}
+
+/// The first screen of every workspace.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// Field focused first, e.g. `query` or `results`; the log group otherwise.
+    pub focus: Option<FocusField>,
+    /// Start with the input panes collapsed so the results fill the screen;
+    /// Ctrl+Down restores them.
+    pub inputs_collapsed: bool,
+    /// Open the filter box from the start, so Tab reaches it without `/` first.
+    pub show_filter: bool,
+    /// Relative window selected first, e.g. `15m`, `3h`, or `7 days`; 1 hour otherwise.
+    pub relative_range: Option<String>,
+    /// Input panes never shown, e.g. `["profile", "region"]`; "Show or hide
+    /// input panes" in the command palette changes them for the session.
+    pub hidden_inputs: Vec<InputPane>,
+}
+
+impl StartupConfig {
+    /// Drop a `relative_range` that names no relative window, saying why.
+    fn check_relative_range(&mut self) -> Option<String> {
+        let range = self.relative_range.as_deref()?;
+        if relative_range_index(range).is_some() {
+            return None;
+        }
+        let warning = format!(
+            "Unknown startup.relative_range \"{range}\"; use one of the relative windows, e.g. \"15m\" or \"1 day\""
+        );
+        self.relative_range = None;
+        Some(warning)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct QueryConfig {
+    /// Initial query engine; switchable at runtime from the command palette.
+    pub engine: QueryEngine,
+    /// Logs Insights queries allowed to run at once; later ones queue.
+    pub max_concurrent: usize,
+    /// Reuse results of an identical run over an absolute range this many
+    /// minutes old; 0 disables the cache.
+    pub cache_minutes: u64,
+    /// Memory results may use before later rows are spilled to a temporary
+    /// file and read back as they are shown; 0 keeps every row in memory.
+    /// A search index only gets what the rows leave over.
+    pub results_memory_mb: u64,
+    /// Index the filter text of result sets this large in the background so
+    /// repeated filtering looks rows up instead of scanning them; 0 disables.
+    pub search_index_rows: usize,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            engine: QueryEngine::default(),
+            max_concurrent: 4,
+            cache_minutes: 0,
+            results_memory_mb: 512,
+            search_index_rows: 10_000,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TimeConfig {
+    pub date_order: DateOrder,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct KeysConfig {
+    /// Extra chord that runs the query, for terminals that drop Ctrl+Enter.
+    pub run: Option<KeyChord>,
+    /// Let Esc quit when there is nothing left for it to close.
+    pub esc_quits: bool,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// How long the on-disk log group list stays fresh before it is listed again.
+    pub metadata_ttl_hours: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            metadata_ttl_hours: 24,
+        }
+    }
+}
+
+/// Profiles and accounts treated as production: a banner stays up while one is
+/// selected, and the first query of the session asks for confirmation.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ProductionConfig {
+    /// Profile names; `*` matches any run of characters.
+    pub profiles: Vec<String>,
+    /// Account IDs, matched through `sso_account_id` or `role_arn` in `~/.aws/config`.
+    pub accounts: Vec<String>,
+    pub confirm_first_query: bool,
+}
+
+impl Default for ProductionConfig {
+    fn default() -> Self {
+        Self {
+            profiles: Vec::new(),
+            accounts: Vec::new(),
+            confirm_first_query: true,
+        }
+    }
+}
+
+impl ProductionConfig {
+    pub fn matches(&self, profile: &str, account: Option<&str>) -> bool {
+        self.profiles
+            .iter()
+            .any(|pattern| wildcard_match(pattern, profile))
+            || account.is_some_and(|account| self.accounts.iter().any(|wanted| wanted == account))
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardAction {
+    /// Run anyway and say why the query looked expensive.
+    #[default]
+    Warn,
+    /// Stop before anything is sent to AWS.
+    Refuse,
+}
+
+/// Limits that catch accidental scans of shared accounts; 0 turns a check off.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct GuardConfig {
+    pub max_days: u64,
+    /// Most log groups a query without a `limit` clause may touch.
+    pub max_log_groups_without_limit: usize,
+    pub action: GuardAction,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            max_days: 30,
+            max_log_groups_without_limit: 20,
+            action: GuardAction::Warn,
+        }
+    }
+}
+
+/// Trail queried next to the results for recent changes in the account.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CloudTrailConfig {
+    /// Log group the trail delivers to; the companion query is off without one.
+    pub log_group: Option<String>,
+    pub query: String,
+    /// Profile and region of the account holding the trail, when it is not
+    /// the one being queried.
+    pub profile: Option<String>,
+    pub region: Option<String>,
+}
+
+impl Default for CloudTrailConfig {
+    fn default() -> Self {
+        Self {
+            log_group: None,
+            query: cloudtrail::DEFAULT_QUERY.to_string(),
+            profile: None,
+            region: None,
+        }
+    }
+}
+
+/// Extra columns derived from each row's `@logStream`.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct EnrichConfig {
+    /// Add ECS task and Kubernetes pod columns read from stream names.
+    pub log_streams: bool,
+    /// Resolver run with unseen stream names on stdin, one per line, printing
+    /// JSON lines like `{"stream": "...", "service": "checkout"}`.
+    pub command: Option<String>,
+    /// How long the command may take before it is killed and the rows are
+    /// shown without its columns.
+    pub timeout_secs: u64,
+}
+
+impl Default for EnrichConfig {
+    fn default() -> Self {
+        Self {
+            log_streams: false,
+            command: None,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// External service asked to explain a row's error; off unless a command or
+/// URL is set.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ExplainConfig {
+    /// Run with the redacted message on stdin; its stdout is the explanation.
+    pub command: Option<String>,
+    /// Endpoint POSTed `{"log_group": ..., "message": ...}` when no command is set.
+    pub url: Option<String>,
+    /// Values of keys containing one of these are masked before sending.
+    pub redact_keys: Vec<String>,
+    pub timeout_secs: u64,
+}
+
+impl Default for ExplainConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            url: None,
+            redact_keys: [
+                "password",
+                "passwd",
+                "secret",
+                "token",
+                "authorization",
+                "api_key",
+                "apikey",
+                "cookie",
+                "session",
+            ]
+            .map(String::from)
+            .to_vec(),
+            timeout_secs: 60,
+        }
+    }
+}
+
+impl ExplainConfig {
+    pub fn command(&self) -> Option<&str> {
+        self.command
+            .as_deref()
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.command().is_some() || self.url().is_some()
+    }
+}
+
+/// Client-side thinning that keeps huge result sets quick to browse.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SamplingConfig {
+    /// Show one row in this many while sampling.
+    pub every: usize,
+    /// Open results sampled when a query returns more rows than this; 0 never does.
+    pub auto_above: usize,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            every: 10,
+            auto_above: 0,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DurationsConfig {
+    /// Start with duration columns shown as `12.5 s`; toggled from the command palette.
+    pub humanize: bool,
+    /// Unit of numeric columns by name, e.g. `Elapsed = "ms"`. Columns ending in
+    /// `Ms`/`_ms` and ISO 8601 values like `PT2.5S` are recognised without a hint.
+    pub units: HashMap<String, DurationUnit>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TimelineConfig {
+    /// File of `<timestamp> <name>` lines, e.g. written by a deploy pipeline.
+    pub file: Option<PathBuf>,
+    pub markers: Vec<MarkerEntry>,
+}
+
+/// Named event drawn as a separator between result rows.
+#[derive(Clone, Deserialize)]
+pub struct MarkerEntry {
+    pub name: String,
+    /// RFC 3339, or `YYYY-MM-DD HH:MM:SS` in UTC like `@timestamp`.
+    pub at: String,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct LinksConfig {
+    /// Hosts the row modal may open in a browser; `*.example.com` covers subdomains
+    /// and `*` allows any host.
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: vec!["*.aws.amazon.com".to_string()],
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Proxy for AWS API calls, e.g. `http://proxy.corp:3128`; overrides `HTTPS_PROXY`.
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that bypass `proxy`; defaults to `NO_PROXY`.
+    pub no_proxy: Option<String>,
+    /// PEM file of extra trusted certificates, e.g. a TLS-inspecting proxy's root;
+    /// defaults to `AWS_CA_BUNDLE`.
+    pub ca_bundle: Option<PathBuf>,
+    /// Seconds an AWS call may go unanswered before the query offers to abort; 0 never does.
+    pub watchdog_seconds: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            no_proxy: None,
+            ca_bundle: None,
+            watchdog_seconds: 45,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Proxy in effect for AWS calls: the configured one, else the usual environment variables.
+    pub fn resolved_proxy(&self) -> Option<String> {
+        non_empty(self.proxy.clone()).or_else(|| {
+            ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+                .iter()
+                .find_map(|name| non_empty(env::var(name).ok()))
+        })
+    }
+
+    pub fn resolved_no_proxy(&self) -> Option<String> {
+        non_empty(self.no_proxy.clone()).or_else(|| {
+            ["NO_PROXY", "no_proxy"]
+                .iter()
+                .find_map(|name| non_empty(env::var(name).ok()))
+        })
+    }
+
+    pub fn resolved_ca_bundle(&self) -> Option<PathBuf> {
+        self.ca_bundle
+            .clone()
+            .filter(|path| !path.as_os_str().is_empty())
+            .or_else(|| non_empty(env::var("AWS_CA_BUNDLE").ok()).map(PathBuf::from))
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    pub backend: ClipboardBackend,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// Widest line the plain-text table export may produce.
+    pub text_max_width: usize,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            text_max_width: 120,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareFormat {
+    /// Slack incoming-webhook message with a `text` body.
+    #[default]
+    Slack,
+    /// Structured JSON for generic HTTP endpoints.
+    Json,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ShareConfig {
+    pub webhook_url: Option<String>,
+    pub format: ShareFormat,
+    /// Number of leading result rows included in the shared summary.
+    pub top_rows: usize,
+}
+
+impl Default for ShareConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            format: ShareFormat::default(),
+            top_rows: 5,
+        }
+    }
+}
+
+impl ShareConfig {
+    /// The environment variable wins so the URL can stay out of the config file.
+    pub fn resolved_webhook_url(&self) -> Option<String> {
+        env::var(WEBHOOK_ENV)
+            .ok()
+            .or_else(|| self.webhook_url.clone())
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+    }
+}
+
+/// OpenSearch/Elasticsearch cluster that results can be bulk-indexed into.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct OpenSearchConfig {
+    /// Cluster endpoint, e.g. `https://search.example.com:9200`.
+    pub url: Option<String>,
+    pub index: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sent as `Authorization: ApiKey ...` instead of basic auth.
+    pub api_key: Option<String>,
+    /// Documents per `_bulk` request.
+    pub batch_size: usize,
+}
+
+impl Default for OpenSearchConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            index: "cloudwatch-logs".to_string(),
+            username: None,
+            password: None,
+            api_key: None,
+            batch_size: 500,
+        }
+    }
+}
+
+impl OpenSearchConfig {
+    /// Secrets from the environment win so they can stay out of the config file.
+    pub fn resolved_password(&self) -> Option<String> {
+        env::var(OPENSEARCH_PASSWORD_ENV)
+            .ok()
+            .or_else(|| self.password.clone())
+    }
+
+    pub fn resolved_api_key(&self) -> Option<String> {
+        env::var(OPENSEARCH_API_KEY_ENV)
+            .ok()
+            .or_else(|| self.api_key.clone())
+            .filter(|key| !key.trim().is_empty())
+    }
+}
+
+/// Prometheus Pushgateway that stats series can be pushed to.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub pushgateway_url: Option<String>,
+    /// Pushgateway job the metrics are grouped under.
+    pub job: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            pushgateway_url: None,
+            job: "awslogs".to_string(),
+        }
+    }
+}
+
+impl MetricsConfig {
+    pub fn resolved_pushgateway_url(&self) -> Option<String> {
+        env::var(PUSHGATEWAY_ENV)
+            .ok()
+            .or_else(|| self.pushgateway_url.clone())
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+    }
+}
+
+/// Commands run in response to query activity.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Shell command run after every query finishes, successful or not.
+    pub on_query_complete: Option<String>,
+    /// How long a hook may run before it is killed and reported as failed.
+    pub timeout_secs: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_query_complete: None,
+            timeout_secs: 60,
+        }
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    if let Ok(custom) = env::var(CONFIG_ENV) {
+        if !custom.trim().is_empty() {
+            return Some(PathBuf::from(custom));
+        }
+    }
+    home_dir().map(|home| home.join(".config").join("awslogs").join("config.toml"))
+}
+
+/// Load the config file, falling back to defaults when it is missing or invalid.
+pub fn load_config() -> (Config, Option<String>) {
+    let Some(path) = config_path() else {
+        return (Config::default(), None);
+    };
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return (Config::default(), None),
+    };
+    match toml::from_str::<Config>(&contents) {
+        Ok(mut config) => {
+            let warning = config.startup.check_relative_range();
+            (config, warning)
+        }
+        Err(err) => (
+            Config::default(),
+            Some(format!("Ignoring invalid config {}: {err}", path.display())),
+        ),
+    }
+}
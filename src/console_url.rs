@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+/// Query details carried by a CloudWatch Logs Insights console URL.
+#[derive(Default)]
+pub struct ConsoleLink {
+    pub region: Option<String>,
+    pub log_groups: Vec<String>,
+    pub query: Option<String>,
+    pub range: Option<LinkRange>,
+}
+
+pub enum LinkRange {
+    /// Window ending now.
+    Relative { seconds: i64 },
+    Absolute {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+}
+
+/// Whether a command-line argument looks like a pasted console URL.
+pub fn is_console_url(arg: &str) -> bool {
+    arg.starts_with("https://") && arg.contains("logs-insights")
+}
+
+/// Parse a console URL such as
+/// `https://eu-west-1.console.aws.amazon.com/cloudwatch/home?region=eu-west-1#logsV2:logs-insights$3FqueryDetail$3D~(...)`.
+///
+/// The fragment is percent-encoded with `$` in place of `%`, and `queryDetail`
+/// is a JSURL value (`~(key~value)`, strings prefixed with `'`, `*XX` escapes).
+pub fn parse(url: &str) -> Result<ConsoleLink, String> {
+    let (base, fragment) = url
+        .split_once('#')
+        .ok_or("Console URL has no #logsV2 fragment")?;
+    // The console may encode the fragment more than once (`~` as `$257E`), and
+    // browsers sometimes turn the `$` escapes into `%24` when copying. Decoded
+    // JSURL never contains `$` or `%`, so decode until nothing changes.
+    let mut fragment = fragment.to_string();
+    for _ in 0..4 {
+        let decoded = percent_decode(&percent_decode(&fragment).replace('$', "%"));
+        if decoded == fragment {
+            break;
+        }
+        fragment = decoded;
+    }
+    let detail = fragment
+        .split_once("queryDetail=")
+        .map(|(_, rest)| rest)
+        .ok_or("Console URL has no queryDetail")?;
+
+    let mut parser = JsurlParser {
+        chars: detail.chars().collect(),
+        pos: 0,
+    };
+    let JsurlValue::Object(fields) = parser.value()? else {
+        return Err("Console queryDetail is not an object".into());
+    };
+
+    let mut link = ConsoleLink {
+        region: region_from_base(base),
+        ..ConsoleLink::default()
+    };
+    link.query = fields
+        .get("editorString")
+        .and_then(JsurlValue::as_str)
+        .map(str::to_string);
+    if let Some(JsurlValue::Array(sources)) = fields.get("source") {
+        link.log_groups = sources
+            .iter()
+            .filter_map(JsurlValue::as_str)
+            .map(str::to_string)
+            .collect();
+    }
+    link.range = link_range(&fields)?;
+    Ok(link)
+}
+
+/// Console deep link for the given query; the inverse of [`parse`].
+pub fn build(link: &ConsoleLink) -> String {
+    let region = link.region.as_deref().unwrap_or("us-east-1");
+    let mut detail = String::from("~(");
+    match &link.range {
+        Some(LinkRange::Absolute { start, end }) => {
+            let iso = |value: &DateTime<Utc>| value.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+            detail.push_str(&format!(
+                "end~'{}~start~'{}~timeType~'ABSOLUTE~tz~'UTC",
+                encode_text(&iso(end)),
+                encode_text(&iso(start))
+            ));
+        }
+        Some(LinkRange::Relative { seconds }) => {
+            detail.push_str(&format!(
+                "end~0~start~-{seconds}~timeType~'RELATIVE~unit~'seconds"
+            ));
+        }
+        None => detail.push_str("end~0~start~-3600~timeType~'RELATIVE~unit~'seconds"),
+    }
+    if let Some(query) = link.query.as_deref() {
+        detail.push_str(&format!("~editorString~'{}", encode_text(query)));
+    }
+    detail.push_str("~source~(");
+    for log_group in &link.log_groups {
+        detail.push_str(&format!("~'{}", encode_text(log_group)));
+    }
+    if link.log_groups.is_empty() {
+        detail.push('~');
+    }
+    detail.push_str("))");
+    format!(
+        "https://{region}.console.aws.amazon.com/cloudwatch/home?region={region}#logsV2:logs-insights$3FqueryDetail$3D{detail}"
+    )
+}
+
+/// JSURL string escaping: word characters, `-` and `.` pass through.
+fn encode_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.' => out.push(c),
+            '$' => out.push('!'),
+            c if (c as u32) < 0x100 => out.push_str(&format!("*{:02x}", c as u32)),
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("**{unit:04x}"));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn region_from_base(base: &str) -> Option<String> {
+    let query_region = base
+        .split_once('?')
+        .and_then(|(_, query)| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("region="))
+        })
+        .map(percent_decode);
+    query_region
+        .or_else(|| {
+            let host = base.strip_prefix("https://")?.split('/').next()?;
+            let (region, rest) = host.split_once('.')?;
+            rest.starts_with("console.").then(|| region.to_string())
+        })
+        .filter(|region| !region.is_empty())
+}
+
+fn link_range(fields: &HashMap<String, JsurlValue>) -> Result<Option<LinkRange>, String> {
+    let time_type = fields.get("timeType").and_then(JsurlValue::as_str);
+    if time_type == Some("ABSOLUTE") {
+        let bound = |key: &str| -> Result<DateTime<Utc>, String> {
+            let text = fields
+                .get(key)
+                .and_then(JsurlValue::as_str)
+                .ok_or(format!("Console URL is missing the {key} time"))?;
+            DateTime::parse_from_rfc3339(text)
+                .map(|value| value.with_timezone(&Utc))
+                .map_err(|err| format!("Invalid {key} time in console URL: {err}"))
+        };
+        return Ok(Some(LinkRange::Absolute {
+            start: bound("start")?,
+            end: bound("end")?,
+        }));
+    }
+    let Some(start) = fields.get("start").and_then(JsurlValue::as_number) else {
+        return Ok(None);
+    };
+    let end = fields
+        .get("end")
+        .and_then(JsurlValue::as_number)
+        .unwrap_or(0.0);
+    let unit_seconds = match fields.get("unit").and_then(JsurlValue::as_str) {
+        Some("minutes") => 60.0,
+        Some("hours") => 3600.0,
+        Some("days") => 86_400.0,
+        Some("weeks") => 604_800.0,
+        _ => 1.0,
+    };
+    let seconds = ((end - start).abs() * unit_seconds).round() as i64;
+    Ok((seconds > 0).then_some(LinkRange::Relative { seconds }))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            if let Some(byte) = text
+                .get(idx + 1..idx + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                idx += 3;
+                continue;
+            }
+        }
+        out.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+enum JsurlValue {
+    Object(HashMap<String, JsurlValue>),
+    Array(Vec<JsurlValue>),
+    Str(String),
+    Literal(String),
+}
+
+impl JsurlValue {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Literal(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+struct JsurlParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl JsurlParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, wanted: char) -> Result<(), String> {
+        if self.peek() == Some(wanted) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!(
+                "Malformed console queryDetail near position {}",
+                self.pos
+            ))
+        }
+    }
+
+    fn value(&mut self) -> Result<JsurlValue, String> {
+        self.expect('~')?;
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                self.container()
+            }
+            Some('\'') => {
+                self.pos += 1;
+                Ok(JsurlValue::Str(self.text()?))
+            }
+            _ => Ok(JsurlValue::Literal(self.text()?)),
+        }
+    }
+
+    fn container(&mut self) -> Result<JsurlValue, String> {
+        if self.peek() == Some('~') {
+            let mut items = Vec::new();
+            if self.chars.get(self.pos + 1) == Some(&')') {
+                self.pos += 2;
+                return Ok(JsurlValue::Array(items));
+            }
+            while self.peek() != Some(')') {
+                items.push(self.value()?);
+            }
+            self.pos += 1;
+            return Ok(JsurlValue::Array(items));
+        }
+        let mut fields = HashMap::new();
+        if self.peek() == Some(')') {
+            self.pos += 1;
+            return Ok(JsurlValue::Object(fields));
+        }
+        // Pairs are `key~value`, separated by further `~`.
+        loop {
+            let key = self.text()?;
+            let value = self.value()?;
+            fields.insert(key, value);
+            if self.peek() == Some(')') {
+                self.pos += 1;
+                return Ok(JsurlValue::Object(fields));
+            }
+            self.expect('~')?;
+        }
+    }
+
+    /// Read up to the next `~` or `)`, undoing `*XX`, `**XXXX` and `!` escapes.
+    fn text(&mut self) -> Result<String, String> {
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '~' | ')' => return Ok(out),
+                '*' => {
+                    let wide = self.chars.get(self.pos + 1) == Some(&'*');
+                    let (skip, digits) = if wide { (2, 4) } else { (1, 2) };
+                    let start = self.pos + skip;
+                    let hex: String = self
+                        .chars
+                        .get(start..start + digits)
+                        .ok_or("Truncated escape in console queryDetail")?
+                        .iter()
+                        .collect();
+                    let decoded = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or("Invalid escape in console queryDetail")?;
+                    out.push(decoded);
+                    self.pos = start + digits;
+                }
+                '!' => {
+                    out.push('$');
+                    self.pos += 1;
+                }
+                _ => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        Err("Unterminated console queryDetail".into())
+    }
+}
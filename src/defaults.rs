@@ -1,5 +1,7 @@
 use chrono::{Days, Local};
 
+use crate::time_format;
+
 pub struct AppDefaults {
     pub from: String,
     pub to: String,
@@ -7,7 +9,7 @@ pub struct AppDefaults {
     pub query: &'static str,
 }
 
-const DEFAULT_QUERY: &str = r#"fields @timestamp, @message, @@m
+pub const DEFAULT_QUERY: &str = r#"fields @timestamp, @message, @@m
       | filter @logStream like 'regreport'
       | sort @timestamp asc
       | limit 1000"#;
@@ -19,8 +21,8 @@ pub fn default_app_values() -> AppDefaults {
     let to = Local::now();
 
     AppDefaults {
-        from: from.format("%Y-%m-%d %H:%M:%S").to_string(),
-        to: to.format("%Y-%m-%d %H:%M:%S").to_string(),
+        from: time_format::format(from.naive_local(), false),
+        to: time_format::format(to.naive_local(), false),
         log_group: "devg",
         query: DEFAULT_QUERY,
     }
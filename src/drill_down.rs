@@ -0,0 +1,158 @@
+use crate::query_pairs::starts_regex;
+
+/// Commands dropped from the stages kept ahead of a drill-down; the raw query
+/// sorts by time itself.
+const DROPPED_COMMANDS: [&str; 3] = ["sort", "limit", "display"];
+
+/// Raw-events query behind one row of a `stats ... by X` result: the stages
+/// before `stats`, then a filter per grouping field matching the row's values.
+/// `None` when the query has no (single) grouped `stats`, or the row's group
+/// is only a `bin(...)` time bucket.
+pub fn raw_events_query(query: &str, headers: &[String], cells: &[String]) -> Option<String> {
+    let stages = split_top_level(query, '|');
+    let mut stats = stages
+        .iter()
+        .enumerate()
+        .filter(|(_, stage)| command(stage) == "stats");
+    let (stats_at, stats_stage) = stats.next()?;
+    if stats.next().is_some() {
+        return None;
+    }
+    let mut kept: Vec<String> = stages[..stats_at]
+        .iter()
+        .filter(|stage| !DROPPED_COMMANDS.contains(&command(stage).as_str()))
+        .map(|stage| stage.trim().to_string())
+        .collect();
+    let filters: Vec<String> = group_fields(stats_stage)?
+        .into_iter()
+        .filter(|(_, expr)| !expr.to_ascii_lowercase().starts_with("bin("))
+        .filter_map(|(header, expr)| {
+            let idx = headers.iter().position(|name| *name == header)?;
+            Some(condition(&expr, cells.get(idx).map_or("", String::as_str)))
+        })
+        .collect();
+    if filters.is_empty() {
+        return None;
+    }
+    kept.push(format!("filter {}", filters.join(" and ")));
+    kept.push("sort @timestamp desc".to_string());
+    Some(kept.join("\n| "))
+}
+
+/// Short `field = value` description of the row's group, for the tab title.
+pub fn describe(query: &str, headers: &[String], cells: &[String]) -> String {
+    let stages = split_top_level(query, '|');
+    let fields = stages
+        .iter()
+        .find(|stage| command(stage) == "stats")
+        .and_then(|stage| group_fields(stage))
+        .unwrap_or_default();
+    fields
+        .iter()
+        .filter(|(_, expr)| !expr.to_ascii_lowercase().starts_with("bin("))
+        .filter_map(|(header, _)| {
+            let idx = headers.iter().position(|name| name == header)?;
+            Some(format!("{header} = {}", cells.get(idx)?))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Result columns named by the `by` items of the query's `stats` stage;
+/// `None` without a grouped `stats`.
+pub fn group_columns(query: &str) -> Option<Vec<String>> {
+    let stages = split_top_level(query, '|');
+    let stage = stages.iter().find(|stage| command(stage) == "stats")?;
+    let fields = group_fields(stage)?;
+    Some(fields.into_iter().map(|(header, _)| header).collect())
+}
+
+/// `(result column, expression)` for each `by` item of a stats stage.
+fn group_fields(stage: &str) -> Option<Vec<(String, String)>> {
+    let (_, by_end) = find_keyword(stage, "by")?;
+    let fields = split_top_level(&stage[by_end..], ',')
+        .into_iter()
+        .map(|item| {
+            let item = item.trim();
+            match find_keyword(item, "as") {
+                Some((at, end)) => (
+                    item[end..].trim().to_string(),
+                    item[..at].trim().to_string(),
+                ),
+                None => (item.to_string(), item.to_string()),
+            }
+        })
+        .filter(|(header, _)| !header.is_empty())
+        .collect();
+    Some(fields)
+}
+
+fn condition(expr: &str, value: &str) -> String {
+    if value.is_empty() {
+        format!("not ispresent({expr})")
+    } else if value.parse::<f64>().is_ok() {
+        format!("{expr} = {value}")
+    } else {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("{expr} = \"{escaped}\"")
+    }
+}
+
+fn command(stage: &str) -> String {
+    stage
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Split on `separator` outside parentheses, quotes, and regex literals.
+fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (idx, ch) in top_level_chars(text) {
+        if ch == separator {
+            parts.push(text[start..idx].to_string());
+            start = idx + ch.len_utf8();
+        }
+    }
+    parts.push(text[start..].to_string());
+    parts
+}
+
+/// Byte range of a whitespace-delimited keyword outside brackets and literals.
+fn find_keyword(text: &str, keyword: &str) -> Option<(usize, usize)> {
+    top_level_chars(text)
+        .into_iter()
+        .filter(|(_, ch)| ch.is_whitespace())
+        .find_map(|(idx, ch)| {
+            let start = idx + ch.len_utf8();
+            let end = start + keyword.len();
+            let word = text.get(start..end)?;
+            let followed_by_space = text[end..].starts_with(char::is_whitespace);
+            (word.eq_ignore_ascii_case(keyword) && followed_by_space).then_some((start, end))
+        })
+}
+
+/// Byte offsets and characters that are not inside brackets or literals.
+fn top_level_chars(text: &str) -> Vec<(usize, char)> {
+    let mut chars = Vec::new();
+    let mut depth = 0usize;
+    let mut literal: Option<char> = None;
+    let mut escaped = false;
+    for (idx, ch) in text.char_indices() {
+        match literal {
+            Some(_) if escaped => escaped = false,
+            Some(_) if ch == '\\' => escaped = true,
+            Some(end) if ch == end => literal = None,
+            Some(_) => {}
+            None if matches!(ch, '"' | '\'' | '`') => literal = Some(ch),
+            None if ch == '/' && starts_regex(&text[..idx]) => literal = Some('/'),
+            None if ch == '(' || ch == '[' => depth += 1,
+            None if ch == ')' || ch == ']' => depth = depth.saturating_sub(1),
+            None if depth == 0 => chars.push((idx, ch)),
+            None => {}
+        }
+    }
+    chars
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Unit a numeric column is recorded in.
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DurationUnit {
+    #[serde(rename = "ns")]
+    Nanos,
+    #[serde(rename = "us")]
+    Micros,
+    #[serde(rename = "ms")]
+    Millis,
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "m")]
+    Minutes,
+    #[serde(rename = "h")]
+    Hours,
+}
+
+impl DurationUnit {
+    fn millis(self) -> f64 {
+        match self {
+            Self::Nanos => 1e-6,
+            Self::Micros => 1e-3,
+            Self::Millis => 1.0,
+            Self::Seconds => 1_000.0,
+            Self::Minutes => 60_000.0,
+            Self::Hours => 3_600_000.0,
+        }
+    }
+}
+
+/// Unit for a column: the configured hint, else one implied by a suffix such
+/// as `durationMs` or `latency_ms`.
+pub fn column_unit(header: &str, hints: &HashMap<String, DurationUnit>) -> Option<DurationUnit> {
+    if let Some(unit) = hints.get(header) {
+        return Some(*unit);
+    }
+    let lower = header.to_ascii_lowercase();
+    if lower.ends_with("millis") || header.ends_with("Ms") || lower.ends_with("_ms") {
+        Some(DurationUnit::Millis)
+    } else if header.ends_with("Us") || lower.ends_with("_us") || lower.ends_with("micros") {
+        Some(DurationUnit::Micros)
+    } else if header.ends_with("Ns") || lower.ends_with("_ns") || lower.ends_with("nanos") {
+        Some(DurationUnit::Nanos)
+    } else {
+        None
+    }
+}
+
+/// Milliseconds in an ISO 8601 duration (`PT2.5S`) or a plain number in `unit`.
+pub fn parse_millis(value: &str, unit: Option<DurationUnit>) -> Option<f64> {
+    let value = value.trim();
+    if let Some(millis) = parse_iso8601(value) {
+        return Some(millis);
+    }
+    let number: f64 = value
+        .parse()
+        .ok()
+        .filter(|number: &f64| number.is_finite())?;
+    Some(number * unit?.millis())
+}
+
+/// `PT1H2M3.5S` style durations; day and larger designators are not supported.
+fn parse_iso8601(value: &str) -> Option<f64> {
+    let rest = value.strip_prefix("PT")?;
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0.0;
+    let mut number = String::new();
+    for ch in rest.chars() {
+        match ch {
+            '0'..='9' | '.' => number.push(ch),
+            'H' | 'M' | 'S' => {
+                let amount: f64 = number.parse().ok()?;
+                number.clear();
+                total += amount
+                    * match ch {
+                        'H' => DurationUnit::Hours.millis(),
+                        'M' => DurationUnit::Minutes.millis(),
+                        _ => DurationUnit::Seconds.millis(),
+                    };
+            }
+            _ => return None,
+        }
+    }
+    number.is_empty().then_some(total)
+}
+
+/// `850 ms`, `12.5 s`, `3 m 20 s`, `2 h 5 m`.
+pub fn humanize(millis: f64) -> String {
+    let sign = if millis < 0.0 { "-" } else { "" };
+    let millis = millis.abs();
+    let text = if millis < 1.0 {
+        format!("{:.0} µs", millis * 1_000.0)
+    } else if millis < 1_000.0 {
+        format!("{} ms", trim_decimal(millis, 1))
+    } else if millis < 60_000.0 {
+        format!("{} s", trim_decimal(millis / 1_000.0, 1))
+    } else if millis < 3_600_000.0 {
+        let seconds = (millis / 1_000.0).round() as u64;
+        format!("{} m {} s", seconds / 60, seconds % 60)
+    } else {
+        let minutes = (millis / 60_000.0).round() as u64;
+        format!("{} h {} m", minutes / 60, minutes % 60)
+    };
+    format!("{sign}{text}")
+}
+
+fn trim_decimal(value: f64, places: usize) -> String {
+    let text = format!("{value:.places$}");
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::EnrichConfig;
+use crate::hooks;
+use crate::log_fetcher::{LogField, LogRecord};
+
+const STREAM_FIELD: &str = "@logStream";
+
+/// Extra columns for one log stream, in column order.
+pub type StreamColumns = Vec<(String, String)>;
+
+/// Turns log stream names into columns such as the ECS task or Kubernetes
+/// pod behind them.
+#[async_trait]
+pub trait StreamResolver: Send + Sync {
+    /// Columns for those of `streams` this resolver recognises.
+    async fn resolve(&self, streams: &[String]) -> Result<HashMap<String, StreamColumns>, String>;
+}
+
+/// Reads the stream names the ECS awslogs driver and Fluent Bit's
+/// Kubernetes tail input produce; no AWS calls.
+pub struct PatternResolver;
+
+/// Runs `enrich.command` once per batch of unseen streams: their names on
+/// stdin, one per line, and one JSON object per line back with a `stream`
+/// key plus one key per column, e.g. `{"stream": "...", "service": "checkout"}`.
+pub struct CommandResolver {
+    command: String,
+    timeout: Duration,
+}
+
+/// Adds resolver columns to query rows, remembering each stream's columns
+/// for the rest of the session.
+pub struct StreamEnricher {
+    resolvers: Vec<Box<dyn StreamResolver>>,
+    cache: Mutex<HashMap<String, StreamColumns>>,
+}
+
+impl StreamEnricher {
+    pub fn new(config: &EnrichConfig) -> Self {
+        let mut resolvers: Vec<Box<dyn StreamResolver>> = Vec::new();
+        if config.log_streams {
+            resolvers.push(Box::new(PatternResolver));
+        }
+        if let Some(command) = config.command.as_deref().map(str::trim) {
+            if !command.is_empty() {
+                resolvers.push(Box::new(CommandResolver {
+                    command: command.to_string(),
+                    timeout: Duration::from_secs(config.timeout_secs.max(1)),
+                }));
+            }
+        }
+        Self {
+            resolvers,
+            cache: Mutex::default(),
+        }
+    }
+
+    /// Append the resolved columns to every record, empty where a stream has
+    /// none. Returns why a resolver failed, if one did.
+    pub async fn enrich(&self, records: &mut [LogRecord]) -> Option<String> {
+        if self.resolvers.is_empty() {
+            return None;
+        }
+        let streams: Vec<Option<String>> = records
+            .iter()
+            .map(|record| {
+                record
+                    .iter()
+                    .find(|field| field.name.as_deref() == Some(STREAM_FIELD))
+                    .map(|field| field.value.clone())
+            })
+            .collect();
+        let mut unseen: Vec<String> = {
+            let cache = self.cache.lock().ok()?;
+            streams
+                .iter()
+                .flatten()
+                .filter(|stream| !cache.contains_key(*stream))
+                .cloned()
+                .collect()
+        };
+        if unseen.is_empty() && streams.iter().all(Option::is_none) {
+            return None;
+        }
+        unseen.sort_unstable();
+        unseen.dedup();
+
+        let mut failure = None;
+        let mut resolved: HashMap<String, StreamColumns> = HashMap::new();
+        if !unseen.is_empty() {
+            for resolver in &self.resolvers {
+                match resolver.resolve(&unseen).await {
+                    Ok(found) => {
+                        for (stream, columns) in found {
+                            resolved.entry(stream).or_default().extend(columns);
+                        }
+                    }
+                    Err(err) => {
+                        failure.get_or_insert(err);
+                    }
+                }
+            }
+        }
+        let mut cache = self.cache.lock().ok()?;
+        // After a failed resolver, a stream nothing resolved may just not have
+        // been looked up yet; only remember it as plain when every one ran.
+        for stream in unseen {
+            if let Some(columns) = resolved.remove(&stream) {
+                cache.insert(stream, columns);
+            } else if failure.is_none() {
+                cache.insert(stream, StreamColumns::new());
+            }
+        }
+
+        // A column named like a query field would be a second, different
+        // column of the same name; those are left out.
+        let fields: HashSet<&str> = records
+            .iter()
+            .flatten()
+            .filter_map(|field| field.name.as_deref())
+            .collect();
+        let mut names: Vec<&str> = Vec::new();
+        let mut clashes: Vec<&str> = Vec::new();
+        for columns in streams
+            .iter()
+            .flatten()
+            .filter_map(|stream| cache.get(stream))
+        {
+            for (name, _) in columns {
+                let seen = if fields.contains(name.as_str()) {
+                    &mut clashes
+                } else {
+                    &mut names
+                };
+                if !seen.contains(&name.as_str()) {
+                    seen.push(name);
+                }
+            }
+        }
+        if !clashes.is_empty() && failure.is_none() {
+            failure = Some(format!(
+                "Enrich columns named like query fields were left out: {}",
+                clashes.join(", ")
+            ));
+        }
+        let names: Vec<String> = names.into_iter().map(str::to_string).collect();
+        for (record, stream) in records.iter_mut().zip(&streams) {
+            let columns = stream.as_ref().and_then(|stream| cache.get(stream));
+            for name in &names {
+                let value = columns
+                    .and_then(|columns| columns.iter().find(|(column, _)| column == name))
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default();
+                record.push(LogField {
+                    name: Some(name.clone()),
+                    value,
+                });
+            }
+        }
+        failure
+    }
+}
+
+#[async_trait]
+impl StreamResolver for PatternResolver {
+    async fn resolve(&self, streams: &[String]) -> Result<HashMap<String, StreamColumns>, String> {
+        Ok(streams
+            .iter()
+            .filter_map(|stream| {
+                let columns = kubernetes_columns(stream).or_else(|| ecs_columns(stream))?;
+                Some((stream.clone(), columns))
+            })
+            .collect())
+    }
+}
+
+/// `prefix/container/task-id`, as written by the awslogs log driver.
+fn ecs_columns(stream: &str) -> Option<StreamColumns> {
+    let mut parts = stream.rsplitn(3, '/');
+    let task = parts.next()?;
+    let container = parts.next()?;
+    let prefix = parts.next()?;
+    let hex = |text: &str| text.chars().all(|c| c.is_ascii_hexdigit());
+    // Task ids are 32 hex digits, or a UUID on older ECS.
+    let is_task_id = (task.len() == 32 && hex(task))
+        || (task.len() == 36 && task.split('-').all(|part| !part.is_empty() && hex(part)));
+    if !is_task_id || container.is_empty() {
+        return None;
+    }
+    Some(vec![
+        ("ecs.prefix".to_string(), prefix.to_string()),
+        ("ecs.container".to_string(), container.to_string()),
+        ("ecs.task".to_string(), task.to_string()),
+    ])
+}
+
+/// `...containers.<pod>_<namespace>_<container>-<container id>.log`, as
+/// tagged by Fluent Bit and Container Insights.
+fn kubernetes_columns(stream: &str) -> Option<StreamColumns> {
+    let (_, file) = stream.rsplit_once("containers.")?;
+    let file = file.strip_suffix(".log").unwrap_or(file);
+    let mut parts = file.splitn(3, '_');
+    let pod = parts.next()?;
+    let namespace = parts.next()?;
+    let rest = parts.next()?;
+    let container = match rest.rsplit_once('-') {
+        Some((container, id)) if id.len() == 64 => container,
+        _ => rest,
+    };
+    if pod.is_empty() || namespace.is_empty() || container.is_empty() {
+        return None;
+    }
+    Some(vec![
+        ("k8s.namespace".to_string(), namespace.to_string()),
+        ("k8s.workload".to_string(), workload(pod).to_string()),
+        ("k8s.pod".to_string(), pod.to_string()),
+        ("k8s.container".to_string(), container.to_string()),
+    ])
+}
+
+/// The pod's owner, guessed from generated name suffixes: `web-7d9f8c6b5-x2x9z`
+/// (Deployment), `agent-x2x9z` (DaemonSet), `db-0` (StatefulSet).
+fn workload(pod: &str) -> &str {
+    let segments: Vec<&str> = pod.split('-').collect();
+    let generated = |segment: &str, lengths: std::ops::RangeInclusive<usize>| {
+        lengths.contains(&segment.len()) && segment.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+    let strip = match segments.as_slice() {
+        [_, .., hash, id] if generated(hash, 6..=10) && generated(id, 5..=5) => 2,
+        [_, .., id] if generated(id, 5..=5) => 1,
+        [_, .., ordinal] if ordinal.chars().all(|c| c.is_ascii_digit()) => 1,
+        _ => 0,
+    };
+    let kept: usize = segments[..segments.len() - strip]
+        .iter()
+        .map(|segment| segment.len() + 1)
+        .sum();
+    &pod[..kept.saturating_sub(1)]
+}
+
+#[async_trait]
+impl StreamResolver for CommandResolver {
+    async fn resolve(&self, streams: &[String]) -> Result<HashMap<String, StreamColumns>, String> {
+        let command = &self.command;
+        // Killed when the timeout drops it, so a hung resolver does not
+        // linger past the query it was enriching.
+        let mut child = hooks::shell(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("Unable to start enrich command `{command}`: {err}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let input = streams.join("\n") + "\n";
+            tokio::spawn(async move {
+                let _ = stdin.write_all(input.as_bytes()).await;
+            });
+        }
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| format!("Enrich command `{command}` timed out"))?
+            .map_err(|err| format!("Enrich command `{command}` did not finish: {err}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Enrich command `{command}` exited with {}",
+                output.status
+            ));
+        }
+        let mut found = HashMap::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            let Some(Value::String(stream)) = object.remove("stream") else {
+                continue;
+            };
+            found.insert(stream, columns(object));
+        }
+        Ok(found)
+    }
+}
+
+fn columns(object: Map<String, Value>) -> StreamColumns {
+    object
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(text) => text,
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            (name, value)
+        })
+        .collect()
+}
@@ -0,0 +1,236 @@
+use std::net::Ipv6Addr;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::ExplainConfig;
+use crate::hooks;
+
+/// What is sent for one row: the message after redaction, plus where it came from.
+pub struct ExplainRequest {
+    /// Numbers the request, so a reply meant for an earlier pane is dropped.
+    pub id: u64,
+    pub log_group: String,
+    pub message: String,
+}
+
+/// The explanation pane beside the results.
+pub struct ExplainPane {
+    /// The request this pane waits on.
+    pub request: u64,
+    /// First line of the redacted message, as a reminder of what was asked.
+    pub subject: String,
+    /// `None` while waiting for the reply.
+    pub reply: Option<Result<String, String>>,
+}
+
+const MASK: &str = "[redacted]";
+
+/// Mask what should not leave the machine: e-mail, IPv4 and IPv6 addresses,
+/// AWS access key and account ids, long hex or base64 tokens, and the values
+/// of `key=value` / `"key": "value"` pairs whose key contains one of `keys`;
+/// a quoted value is masked up to its closing quote.
+pub fn redact(text: &str, keys: &[String]) -> String {
+    let keys: Vec<String> = keys.iter().map(|key| key.to_ascii_lowercase()).collect();
+    let mut output = String::with_capacity(text.len());
+    // Set after a sensitive key: whether its `:` or `=` has been seen yet.
+    let mut after_key: Option<bool> = None;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let at_boundary = output
+            .chars()
+            .next_back()
+            .is_none_or(|before| !is_token_char(before) && before != ':');
+        if let Some(len) = ipv6_prefix(rest).filter(|_| at_boundary) {
+            output.push_str(MASK);
+            rest = &rest[len..];
+            after_key = None;
+            continue;
+        }
+        if after_key == Some(true) && matches!(c, '"' | '\'') {
+            output.push(c);
+            output.push_str(MASK);
+            rest = &rest[1..];
+            match closing_quote(rest, c) {
+                Some(end) => {
+                    output.push(c);
+                    rest = &rest[end + 1..];
+                }
+                None => rest = "",
+            }
+            after_key = None;
+            continue;
+        }
+        if !is_token_char(c) {
+            after_key = match (after_key, c) {
+                (Some(assigned), '"' | '\'' | ' ') => Some(assigned),
+                (Some(_), ':' | '=') => Some(true),
+                _ => None,
+            };
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let end = rest.find(|c: char| !is_token_char(c)).unwrap_or(rest.len());
+        let (token, tail) = rest.split_at(end);
+        rest = tail;
+        let lower = token.to_ascii_lowercase();
+        if after_key == Some(true) {
+            output.push_str(MASK);
+            // `Authorization: Bearer <token>` hides the token too.
+            if !matches!(lower.as_str(), "bearer" | "basic") {
+                after_key = None;
+            }
+            continue;
+        }
+        if is_sensitive(token) {
+            output.push_str(MASK);
+        } else {
+            output.push_str(token);
+        }
+        after_key = keys
+            .iter()
+            .any(|key| lower.contains(key.as_str()))
+            .then_some(false);
+    }
+    output
+}
+
+/// Byte length of the IPv6 address `text` starts with, if it starts with one.
+fn ipv6_prefix(text: &str) -> Option<usize> {
+    let end = text
+        .find(|c: char| !(c.is_ascii_hexdigit() || matches!(c, ':' | '.')))
+        .unwrap_or(text.len());
+    // A sentence may end right after the address.
+    let candidate = text[..end].trim_end_matches('.');
+    let followed_by_word = text[candidate.len()..].starts_with(char::is_alphanumeric);
+    (candidate.matches(':').count() >= 2
+        && !followed_by_word
+        && candidate.parse::<Ipv6Addr>().is_ok())
+    .then_some(candidate.len())
+}
+
+/// Index of the `quote` closing a quoted value, skipping escaped ones.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | '@' | '_' | '-' | '+' | '/')
+}
+
+fn is_sensitive(token: &str) -> bool {
+    let token = token.trim_matches('.');
+    let all = |test: fn(char) -> bool| token.chars().all(test);
+    let is_email = token
+        .split_once('@')
+        .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'));
+    let is_ipv4 =
+        token.split('.').count() == 4 && token.split('.').all(|part| part.parse::<u8>().is_ok());
+    let is_access_key = token.len() == 20
+        && (token.starts_with("AKIA") || token.starts_with("ASIA"))
+        && all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    let is_account = token.len() == 12 && all(|c| c.is_ascii_digit());
+    let is_secret = token.len() >= 32
+        && all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '_' | '-'))
+        && token.chars().any(|c| c.is_ascii_digit());
+    is_email || is_ipv4 || is_access_key || is_account || is_secret
+}
+
+/// Ask `explain.command` (message on stdin, explanation on stdout) or, when
+/// no command is set, POST `{"log_group": ..., "message": ...}` to `explain.url`
+/// and read `explanation`, `text` or the whole body from the reply.
+pub async fn explain(config: ExplainConfig, request: ExplainRequest) -> Result<String, String> {
+    let timeout = Duration::from_secs(config.timeout_secs.max(1));
+    let reply = if let Some(command) = config.command() {
+        tokio::time::timeout(timeout, run_command(command, &request))
+            .await
+            .map_err(|_| format!("Explain command `{command}` timed out"))??
+    } else if let Some(url) = config.url() {
+        post(url, timeout, &request).await?
+    } else {
+        return Err("No explain integration configured; set explain.command or explain.url".into());
+    };
+    let reply = reply.trim();
+    if reply.is_empty() {
+        Err("The explain integration returned nothing".into())
+    } else {
+        Ok(reply.to_string())
+    }
+}
+
+async fn run_command(command: &str, request: &ExplainRequest) -> Result<String, String> {
+    let mut child = hooks::shell(command)
+        .env("AWSLOGS_LOG_GROUP", &request.log_group)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("Unable to start explain command `{command}`: {err}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = request.message.clone();
+        tokio::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|err| format!("Explain command `{command}` did not finish: {err}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!(
+            "Explain command `{command}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ))
+    }
+}
+
+async fn post(url: &str, timeout: Duration, request: &ExplainRequest) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|err| format!("Unable to create HTTP client: {err}"))?;
+    let response = client
+        .post(url)
+        .json(&json!({
+            "log_group": request.log_group,
+            "message": request.message,
+        }))
+        .send()
+        .await
+        .map_err(|err| format!("Explain request failed: {err}"))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|err| format!("Unable to read the explanation: {err}"))?;
+    if !status.is_success() {
+        return Err(format!("Explain endpoint responded with {status}"));
+    }
+    let text = match serde_json::from_str::<Value>(&body) {
+        Ok(Value::Object(reply)) => ["explanation", "text"]
+            .iter()
+            .find_map(|key| reply.get(*key).and_then(Value::as_str))
+            .map(str::to_string)
+            .unwrap_or(body),
+        Ok(Value::String(text)) => text,
+        _ => body,
+    };
+    Ok(text)
+}
@@ -0,0 +1,477 @@
+use std::borrow::Cow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use chrono::NaiveDateTime;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::config::ExportConfig;
+use crate::metrics;
+
+pub const EXPORTS_DIR: &str = "exports";
+pub const SUPPORTED_EXTENSIONS: &str = ".csv, .json, .parquet, .xlsx, .txt, .prom";
+
+const XLSX_TIMESTAMP_FORMAT: &str = "yyyy-mm-dd hh:mm:ss.000";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Parquet,
+    Xlsx,
+    Text,
+    /// Time-bucketed stats series as OpenMetrics text.
+    OpenMetrics,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "parquet" => Some(Self::Parquet),
+            "xlsx" => Some(Self::Xlsx),
+            "txt" => Some(Self::Text),
+            "prom" | "om" => Some(Self::OpenMetrics),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Json => "JSON",
+            Self::Parquet => "Parquet",
+            Self::Xlsx => "Excel",
+            Self::Text => "plain-text table",
+            Self::OpenMetrics => "OpenMetrics",
+        }
+    }
+}
+
+/// Filtered rows restricted to the visible columns, ready to be written out.
+pub struct ExportTable {
+    pub headers: Vec<String>,
+    pub rows: ExportRows,
+}
+
+/// Rows to write out. Rows of large results are read one at a time from the
+/// results, and their spill file, instead of being copied up front.
+#[derive(Clone)]
+pub enum ExportRows {
+    Owned(Vec<Vec<String>>),
+    Lazy {
+        len: usize,
+        row: Arc<dyn Fn(usize) -> Option<Vec<String>> + Send + Sync>,
+    },
+}
+
+impl ExportRows {
+    pub fn lazy(
+        len: usize,
+        row: impl Fn(usize) -> Option<Vec<String>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Lazy {
+            len,
+            row: Arc::new(row),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Owned(rows) => rows.len(),
+            Self::Lazy { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Cow<'_, [String]>> {
+        match self {
+            Self::Owned(rows) => rows.get(idx).map(|row| Cow::Borrowed(row.as_slice())),
+            Self::Lazy { len, row } => (idx < *len).then(|| row(idx)).flatten().map(Cow::Owned),
+        }
+    }
+
+    /// Rows in order, skipping any that can no longer be read back.
+    pub fn iter(&self) -> impl Iterator<Item = Cow<'_, [String]>> + '_ {
+        (0..self.len()).filter_map(|idx| self.get(idx))
+    }
+
+    pub fn truncate(&mut self, keep: usize) {
+        match self {
+            Self::Owned(rows) => rows.truncate(keep),
+            Self::Lazy { len, .. } => *len = (*len).min(keep),
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<Vec<String>> {
+        match self {
+            Self::Owned(rows) => rows,
+            lazy => lazy.iter().map(Cow::into_owned).collect(),
+        }
+    }
+}
+
+impl From<Vec<Vec<String>>> for ExportRows {
+    fn from(rows: Vec<Vec<String>>) -> Self {
+        Self::Owned(rows)
+    }
+}
+
+impl Serialize for ExportRows {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for row in self.iter() {
+            seq.serialize_element(&*row)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ExportRows {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::Owned)
+    }
+}
+
+/// `query` is the one behind the table; OpenMetrics takes its labels from
+/// the query's `stats ... by` fields.
+pub fn write_export(
+    path: &Path,
+    format: ExportFormat,
+    table: &ExportTable,
+    options: &ExportConfig,
+    query: Option<&str>,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Unable to prepare export directory: {err}"))?;
+    }
+    let create = || File::create(path).map_err(|err| format!("Failed to create export: {err}"));
+    match format {
+        ExportFormat::Csv => write_csv(create()?, table),
+        ExportFormat::Json => write_json(create()?, table),
+        ExportFormat::Parquet => write_parquet(create()?, table),
+        ExportFormat::Text => write_text_table(create()?, table, options.text_max_width),
+        ExportFormat::OpenMetrics => fs::write(path, metrics::openmetrics(table, query)?)
+            .map_err(|err| format!("Failed to write OpenMetrics: {err}")),
+        ExportFormat::Xlsx => {
+            write_xlsx(path, table).map_err(|err| format!("Failed to write Excel workbook: {err}"))
+        }
+    }
+}
+
+/// Create a file only this user can read, failing if anything (a file or a
+/// symlink) already sits at `path`; for results written to the shared
+/// temporary directory.
+pub fn create_private(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+fn write_csv(file: File, table: &ExportTable) -> Result<(), String> {
+    let mut writer = BufWriter::new(file);
+    let mut write_line = |cells: &[String]| -> std::io::Result<()> {
+        let line = cells
+            .iter()
+            .map(|cell| csv_escape(cell))
+            .collect::<Vec<_>>()
+            .join(",");
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\r\n")
+    };
+    write_line(&table.headers).map_err(|err| format!("Failed to write CSV: {err}"))?;
+    for row in table.rows.iter() {
+        write_line(&row).map_err(|err| format!("Failed to write CSV: {err}"))?;
+    }
+    writer
+        .flush()
+        .map_err(|err| format!("Failed to write CSV: {err}"))
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_text_table(file: File, table: &ExportTable, max_width: usize) -> Result<(), String> {
+    let mut writer = BufWriter::new(file);
+    let mut result = Ok(());
+    text_table_lines(table, max_width, |line| {
+        if result.is_ok() {
+            result = writer.write_all(line.as_bytes());
+        }
+    });
+    result
+        .and_then(|_| writer.flush())
+        .map_err(|err| format!("Failed to write text table: {err}"))
+}
+
+/// Render a `psql`-style aligned table, truncating the widest columns until
+/// each line fits within `max_width` characters.
+pub fn render_text_table(table: &ExportTable, max_width: usize) -> String {
+    let mut output = String::new();
+    text_table_lines(table, max_width, |line| output.push_str(&line));
+    output
+}
+
+/// The lines of `render_text_table`, each ending in a newline. The rows are
+/// read twice, once to size the columns, rather than held in memory.
+fn text_table_lines(table: &ExportTable, max_width: usize, mut emit: impl FnMut(String)) {
+    const MIN_COLUMN_WIDTH: usize = 3;
+    let flatten = |value: &str| value.replace("\r\n", " ").replace(['\n', '\r', '\t'], " ");
+    let headers: Vec<String> = table.headers.iter().map(|h| flatten(h)).collect();
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    let mut row_count = 0;
+    for row in table.rows.iter() {
+        row_count += 1;
+        for (idx, cell) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(idx) {
+                *width = (*width).max(flatten(cell).chars().count());
+            }
+        }
+    }
+
+    // Each column is padded by one space on both sides and separated by `|`.
+    let line_width = |widths: &[usize]| -> usize {
+        widths.iter().map(|w| w + 2).sum::<usize>() + widths.len().saturating_sub(1)
+    };
+    while line_width(&widths) > max_width {
+        let Some((widest, width)) = widths
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by_key(|(_, width)| *width)
+        else {
+            break;
+        };
+        if width <= MIN_COLUMN_WIDTH {
+            break;
+        }
+        widths[widest] = width - 1;
+    }
+
+    let format_line = |cells: &[String]| -> String {
+        let parts: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(idx, &width)| {
+                let cell = cells.get(idx).map(|c| flatten(c)).unwrap_or_default();
+                format!(" {} ", fit_cell(&cell, width))
+            })
+            .collect();
+        format!("{}\n", parts.join("|").trim_end())
+    };
+
+    emit(format_line(&headers));
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+    emit(format!("{}\n", separator.join("+")));
+    for row in table.rows.iter() {
+        emit(format_line(&row));
+    }
+    let noun = if row_count == 1 { "row" } else { "rows" };
+    emit(format!("({row_count} {noun})\n"));
+}
+
+fn fit_cell(value: &str, width: usize) -> String {
+    let length = value.chars().count();
+    if length <= width {
+        format!("{value:<width$}")
+    } else {
+        let mut truncated: String = value.chars().take(width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Pretty-printed array of one object per row, written as the rows are read.
+pub fn write_json(file: File, table: &ExportTable) -> Result<(), String> {
+    let mut writer = BufWriter::new(file);
+    let write = |writer: &mut BufWriter<File>| -> io::Result<()> {
+        if table.rows.is_empty() {
+            return writer.write_all(b"[]");
+        }
+        writer.write_all(b"[")?;
+        for (idx, row) in table.rows.iter().enumerate() {
+            let object: Map<String, Value> = table
+                .headers
+                .iter()
+                .cloned()
+                .zip(row.iter().map(|cell| Value::String(cell.clone())))
+                .collect();
+            let text = serde_json::to_string_pretty(&Value::Object(object))?;
+            writer.write_all(if idx == 0 { b"\n  " } else { b",\n  " })?;
+            writer.write_all(text.replace('\n', "\n  ").as_bytes())?;
+        }
+        writer.write_all(b"\n]")
+    };
+    write(&mut writer)
+        .and_then(|_| writer.flush())
+        .map_err(|err| format!("Failed to write JSON: {err}"))
+}
+
+/// Rows per Parquet record batch, so large results are not all turned into
+/// Arrow arrays at once.
+const PARQUET_BATCH_ROWS: usize = 8192;
+
+fn write_parquet(file: File, table: &ExportTable) -> Result<(), String> {
+    let fields: Vec<Field> = table
+        .headers
+        .iter()
+        .map(|header| Field::new(header.as_str(), DataType::Utf8, true))
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, Arc::clone(&schema), Some(properties))
+        .map_err(|err| format!("Failed to create Parquet writer: {err}"))?;
+    let mut rows = table.rows.iter().peekable();
+    while rows.peek().is_some() {
+        let batch: Vec<_> = rows.by_ref().take(PARQUET_BATCH_ROWS).collect();
+        let columns: Vec<ArrayRef> = (0..table.headers.len())
+            .map(|idx| {
+                let values = batch
+                    .iter()
+                    .map(|row| row.get(idx).map(|cell| cell.as_str()));
+                Arc::new(StringArray::from_iter(values)) as ArrayRef
+            })
+            .collect();
+        let batch = RecordBatch::try_new(Arc::clone(&schema), columns)
+            .map_err(|err| format!("Failed to build Parquet batch: {err}"))?;
+        writer
+            .write(&batch)
+            .map_err(|err| format!("Failed to write Parquet: {err}"))?;
+    }
+    writer
+        .close()
+        .map_err(|err| format!("Failed to finish Parquet file: {err}"))?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Text,
+    Number,
+    Timestamp,
+}
+
+/// A column is typed only when every non-empty value parses as that type;
+/// all columns are checked in one pass over the rows.
+fn infer_column_kinds(table: &ExportTable) -> Vec<ColumnKind> {
+    let columns = table.headers.len();
+    // Per column: seen a value, all timestamps so far, all numbers so far.
+    let mut state = vec![(false, true, true); columns];
+    for row in table.rows.iter() {
+        for (column, value) in row.iter().take(columns).enumerate() {
+            let value = value.trim();
+            let (seen, timestamps, numbers) = &mut state[column];
+            if value.is_empty() || !(*timestamps || *numbers) {
+                continue;
+            }
+            *seen = true;
+            *timestamps = *timestamps && parse_timestamp(value).is_some();
+            *numbers = *numbers && parse_number(value).is_some();
+        }
+    }
+    state
+        .into_iter()
+        .map(
+            |(seen, timestamps, numbers)| match (seen, timestamps, numbers) {
+                (true, true, _) => ColumnKind::Timestamp,
+                (true, false, true) => ColumnKind::Number,
+                _ => ColumnKind::Text,
+            },
+        )
+        .collect()
+}
+
+/// Parse a Logs Insights timestamp such as `2024-05-01 12:00:00.000` (UTC).
+pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    let trimmed = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S%.f"))
+        .ok()
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+fn write_xlsx(path: &Path, table: &ExportTable) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let timestamp_format = Format::new().set_num_format(XLSX_TIMESTAMP_FORMAT);
+    let kinds = infer_column_kinds(table);
+
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("Results")?;
+    for (column, header) in table.headers.iter().enumerate() {
+        worksheet.write_string_with_format(0, column as u16, header, &header_format)?;
+    }
+    for (row_idx, row) in table.rows.iter().enumerate() {
+        let excel_row = row_idx as u32 + 1;
+        for (column, value) in row.iter().enumerate() {
+            let excel_col = column as u16;
+            let kind = kinds.get(column).copied().unwrap_or(ColumnKind::Text);
+            match kind {
+                ColumnKind::Timestamp => {
+                    if let Some(timestamp) = parse_timestamp(value.trim()) {
+                        worksheet.write_datetime_with_format(
+                            excel_row,
+                            excel_col,
+                            timestamp,
+                            &timestamp_format,
+                        )?;
+                        continue;
+                    }
+                }
+                ColumnKind::Number => {
+                    if let Some(number) = parse_number(value.trim()) {
+                        worksheet.write_number(excel_row, excel_col, number)?;
+                        continue;
+                    }
+                }
+                ColumnKind::Text => {}
+            }
+            if !value.is_empty() {
+                worksheet.write_string(excel_row, excel_col, value)?;
+            }
+        }
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+    worksheet.autofit();
+    for (column, kind) in kinds.iter().enumerate() {
+        if *kind == ColumnKind::Timestamp {
+            worksheet.set_column_width(column as u16, 24)?;
+        }
+    }
+    workbook.save(path)
+}
@@ -0,0 +1,87 @@
+/// Commands that change what later stages see; a pushed-down filter goes before the first.
+const REDUCING_COMMANDS: [&str; 5] = ["stats", "sort", "limit", "dedup", "display"];
+
+/// `filter` stages matching the client-side filter terms: any include term
+/// (like the table filter) and none of the exclude terms, case-insensitively.
+pub fn from_terms<'a>(terms: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut includes = Vec::new();
+    let mut excludes = Vec::new();
+    for term in terms {
+        match term.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => excludes.push(pattern(rest)),
+            Some(_) => {}
+            None => {
+                let rest = term.strip_prefix('+').unwrap_or(term);
+                if !rest.is_empty() {
+                    includes.push(pattern(rest));
+                }
+            }
+        }
+    }
+    let mut stages = Vec::new();
+    if !includes.is_empty() {
+        let alternatives: Vec<String> = includes
+            .iter()
+            .map(|pattern| format!("@message like {pattern}"))
+            .collect();
+        stages.push(format!("filter {}", alternatives.join(" or ")));
+    }
+    for pattern in excludes {
+        stages.push(format!("filter @message not like {pattern}"));
+    }
+    stages
+}
+
+/// Case-insensitive regex literal matching the term as plain text.
+fn pattern(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len() + 6);
+    escaped.push_str("/(?i)");
+    for ch in term.chars() {
+        if "\\.+*?()|[]{}^$/".contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped.push('/');
+    escaped
+}
+
+/// Insert stages before the first `stats`, `sort`, `limit`, `dedup`, or
+/// `display` line, or at the end when there is none.
+pub fn insert_stages(query: &str, stages: &[String]) -> String {
+    let mut lines: Vec<String> = query
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+    let at = lines
+        .iter()
+        .position(|line| {
+            let command = line
+                .trim_start()
+                .trim_start_matches('|')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            REDUCING_COMMANDS.contains(&command.as_str())
+        })
+        .unwrap_or(lines.len());
+    if at == 0 {
+        // The first command carries no pipe; the one it now follows needs one.
+        if let Some(first) = lines.first_mut() {
+            if !first.trim_start().starts_with('|') {
+                *first = format!("| {}", first.trim_start());
+            }
+        }
+    }
+    for (offset, stage) in stages.iter().enumerate() {
+        let line = if at + offset == 0 {
+            stage.clone()
+        } else {
+            format!("| {stage}")
+        };
+        lines.insert(at + offset, line);
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+
+use crate::i18n;
+
+/// The input or pane that receives key presses.
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusField {
+    AwsRegion,
+    AwsProfile,
+    TimeMode,
+    RelativeRange,
+    From,
+    To,
+    LogGroup,
+    Query,
+    Results,
+    Filter,
+    Scratchpad,
+}
+
+impl FocusField {
+    pub fn label(self) -> &'static str {
+        i18n::text(match self {
+            FocusField::AwsRegion => "field.aws_region",
+            FocusField::AwsProfile => "field.aws_profile",
+            FocusField::TimeMode => "field.time_mode",
+            FocusField::RelativeRange => "field.relative_range",
+            FocusField::From => "field.from",
+            FocusField::To => "field.to",
+            FocusField::LogGroup => "field.log_group",
+            FocusField::Query => "field.query",
+            FocusField::Results => "field.results",
+            FocusField::Filter => "field.filter",
+            FocusField::Scratchpad => "field.scratchpad",
+        })
+    }
+}
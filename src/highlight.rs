@@ -0,0 +1,128 @@
+use serde::Deserialize;
+
+/// Cell colour rule from the config, e.g. `column = "Elapsed"`, `when = "> 1000"`.
+#[derive(Clone, Deserialize)]
+pub struct HighlightRule {
+    pub column: String,
+    /// Comparison (`>`, `>=`, `<`, `<=`, `==`, `!=`) followed by a number or text.
+    pub when: String,
+    #[serde(default)]
+    pub color: HighlightColor,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightColor {
+    #[default]
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+enum Operand {
+    Number(f64),
+    Text(String),
+}
+
+/// Rule ready to test cell values against.
+pub struct CellRule {
+    column: String,
+    comparison: Comparison,
+    operand: Operand,
+    pub color: HighlightColor,
+}
+
+/// Parse every rule, keeping the valid ones and describing the first invalid one.
+pub fn compile(rules: &[HighlightRule]) -> (Vec<CellRule>, Option<String>) {
+    let mut compiled = Vec::with_capacity(rules.len());
+    let mut warning = None;
+    for rule in rules {
+        match parse_condition(&rule.when) {
+            Some((comparison, operand)) => compiled.push(CellRule {
+                column: rule.column.clone(),
+                comparison,
+                operand,
+                color: rule.color,
+            }),
+            None => {
+                warning.get_or_insert_with(|| {
+                    format!(
+                        "Ignoring highlight rule for {}: cannot read '{}'",
+                        rule.column, rule.when
+                    )
+                });
+            }
+        }
+    }
+    (compiled, warning)
+}
+
+fn parse_condition(when: &str) -> Option<(Comparison, Operand)> {
+    let when = when.trim();
+    // Two-character operators first so `>=` is not read as `>` followed by `=`.
+    let (comparison, rest) = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(symbol, comparison)| Some((comparison, when.strip_prefix(symbol)?)))?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    match rest.parse::<f64>() {
+        Ok(number) if number.is_finite() => Some((comparison, Operand::Number(number))),
+        _ if matches!(comparison, Comparison::Equal | Comparison::NotEqual) => Some((
+            comparison,
+            Operand::Text(rest.trim_matches('"').to_string()),
+        )),
+        _ => None,
+    }
+}
+
+impl CellRule {
+    pub fn applies_to(&self, header: &str) -> bool {
+        self.column == header
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        let value = value.trim();
+        match &self.operand {
+            Operand::Number(limit) => {
+                let Ok(number) = value.parse::<f64>() else {
+                    return false;
+                };
+                match self.comparison {
+                    Comparison::Greater => number > *limit,
+                    Comparison::GreaterOrEqual => number >= *limit,
+                    Comparison::Less => number < *limit,
+                    Comparison::LessOrEqual => number <= *limit,
+                    Comparison::Equal => number == *limit,
+                    Comparison::NotEqual => number != *limit,
+                }
+            }
+            Operand::Text(text) => match self.comparison {
+                Comparison::NotEqual => value != text,
+                _ => value == text,
+            },
+        }
+    }
+}
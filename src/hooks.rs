@@ -0,0 +1,193 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use chrono::Local;
+use serde_json::{json, Map, Value};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task;
+
+use crate::app::{Notice, QueryResults};
+use crate::export::{self, ExportTable};
+
+/// A finished query, as handed to the `hooks.on_query_complete` command.
+pub struct QueryCompletion {
+    pub log_group: String,
+    pub region: String,
+    pub query: String,
+    /// Query window in epoch milliseconds, when the query got as far as being submitted.
+    pub window: Option<(i64, i64)>,
+    pub elapsed: Option<Duration>,
+    /// The results, or the error the query failed with. Their rows are
+    /// copied out on a worker thread rather than the UI loop.
+    pub outcome: Result<QueryResults, String>,
+}
+
+/// Run the hook through the shell in the background. Results are written to
+/// a JSON file that is removed once the hook exits; metadata arrives both as
+/// `AWSLOGS_*` variables and as the first NDJSON line on stdin, followed by
+/// one line per row.
+pub fn spawn_on_query_complete(
+    command: String,
+    timeout: Duration,
+    completion: QueryCompletion,
+    notices: &UnboundedSender<Notice>,
+) {
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        if let Err(err) = run(&command, timeout, completion).await {
+            let _ = notices.send(Notice::error(format!("Query hook failed: {err}")));
+        }
+    });
+}
+
+async fn run(command: &str, timeout: Duration, completion: QueryCompletion) -> Result<(), String> {
+    let outcome = match &completion.outcome {
+        Ok(results) => {
+            let results = results.clone();
+            let prepared = task::spawn_blocking(move || prepare_results(&results.export_table()))
+                .await
+                .map_err(|err| format!("preparing the results was interrupted: {err}"))?;
+            Ok(prepared?)
+        }
+        Err(err) => Err(err.clone()),
+    };
+    let results_file = outcome.as_ref().ok().map(|(path, _, _)| path.clone());
+    let metadata = metadata(&completion, &outcome);
+    let mut input = format!("{}\n", Value::Object(metadata.clone()));
+    if let Ok((_, _, rows)) = &outcome {
+        input.push_str(rows);
+    }
+
+    // Killed when the timeout drops it, so a hung hook does not outlive
+    // its results file.
+    let mut child = shell(command)
+        .envs(environment(&metadata))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("unable to start `{command}`: {err}"))?;
+    // Feed stdin alongside waiting so a chatty hook cannot fill its stderr
+    // pipe while we are still writing. A hook that never reads stdin closes
+    // it early, which is fine.
+    if let Some(mut stdin) = child.stdin.take() {
+        tokio::spawn(async move {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+    }
+    let output = tokio::time::timeout(timeout, child.wait_with_output()).await;
+    if let Some(path) = results_file {
+        let _ = fs::remove_file(path);
+    }
+    let output = output
+        .map_err(|_| format!("`{command}` timed out after {} s", timeout.as_secs()))?
+        .map_err(|err| format!("`{command}` did not finish: {err}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => Err(format!(
+            "`{command}` exited with {}: {}",
+            output.status,
+            line.trim()
+        )),
+        None => Err(format!("`{command}` exited with {}", output.status)),
+    }
+}
+
+/// The results file the hook gets, its row count, and one NDJSON line per row.
+type PreparedResults = (PathBuf, usize, String);
+
+/// Write the rows to a new private file and render them for stdin.
+fn prepare_results(table: &ExportTable) -> Result<PreparedResults, String> {
+    let path = results_path();
+    let file = export::create_private(&path)
+        .map_err(|err| format!("unable to create {}: {err}", path.display()))?;
+    if let Err(err) = export::write_json(file, table) {
+        let _ = fs::remove_file(&path);
+        return Err(err);
+    }
+    let mut rows = String::new();
+    for row in table.rows.iter() {
+        let record: Map<String, Value> = table
+            .headers
+            .iter()
+            .cloned()
+            .zip(row.iter().map(|cell| Value::String(cell.clone())))
+            .collect();
+        rows.push_str(&Value::Object(record).to_string());
+        rows.push('\n');
+    }
+    Ok((path, table.rows.len(), rows))
+}
+
+fn metadata(
+    completion: &QueryCompletion,
+    outcome: &Result<PreparedResults, String>,
+) -> Map<String, Value> {
+    let (start_millis, end_millis) = completion.window.unzip();
+    let mut metadata = json!({
+        "status": if outcome.is_ok() { "success" } else { "error" },
+        "log_group": completion.log_group,
+        "region": completion.region,
+        "query": completion.query,
+        "start_millis": start_millis,
+        "end_millis": end_millis,
+        "elapsed_ms": completion.elapsed.map(|elapsed| elapsed.as_millis() as u64),
+    });
+    match outcome {
+        Ok((path, row_count, _)) => {
+            metadata["row_count"] = json!(row_count);
+            metadata["results_file"] = json!(path.display().to_string());
+        }
+        Err(err) => metadata["error"] = json!(err),
+    }
+    match metadata {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// `log_group` → `AWSLOGS_LOG_GROUP`, skipping values that are not set.
+fn environment(metadata: &Map<String, Value>) -> Vec<(String, String)> {
+    metadata
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                Value::Null => return None,
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            Some((format!("AWSLOGS_{}", key.to_ascii_uppercase()), value))
+        })
+        .collect()
+}
+
+fn results_path() -> PathBuf {
+    env::temp_dir().join(format!(
+        "awslogs-results-{}-{}.json",
+        std::process::id(),
+        Local::now().format("%Y%m%d%H%M%S%3f")
+    ))
+}
+
+pub fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::config::config_path;
+use crate::help::HELP_TEXT;
+
+const ENGLISH: &str = include_str!("../assets/locales/en.toml");
+const HELP_KEY: &str = "help.text";
+
+static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Load the catalog for `locale`, or for the language in `LC_ALL`/`LC_MESSAGES`/`LANG`
+/// when none is configured. Translations live in `locales/<locale>.toml` next to the
+/// config file; a configured locale without one is reported, a guessed one is not.
+pub fn init(locale: Option<&str>) -> Option<String> {
+    let mut messages = english();
+    let configured = locale.map(str::trim).filter(|locale| !locale.is_empty());
+    let warning = match configured.map(str::to_string).or_else(environment_locale) {
+        Some(locale) if !is_english(&locale) => match load_translation(&locale, &mut messages) {
+            Ok(()) => None,
+            Err(err) => configured.map(|_| err),
+        },
+        _ => None,
+    };
+    let _ = CATALOG.set(messages);
+    warning
+}
+
+/// The message for `key`; the key itself when no catalog has it.
+pub fn text(key: &'static str) -> &'static str {
+    catalog().get(key).map_or(key, String::as_str)
+}
+
+/// The message for `key` with each `{name}` replaced by its value.
+pub fn fill(key: &str, args: &[(&str, &str)]) -> String {
+    let message = catalog().get(key).map_or(key, String::as_str);
+    args.iter()
+        .fold(message.to_string(), |message, (name, value)| {
+            message.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Help screen, translated when the catalog has `[help] text`.
+pub fn help_text() -> &'static str {
+    catalog().get(HELP_KEY).map_or(HELP_TEXT, String::as_str)
+}
+
+fn catalog() -> &'static HashMap<String, String> {
+    CATALOG.get_or_init(english)
+}
+
+fn english() -> HashMap<String, String> {
+    let mut messages = HashMap::new();
+    if let Ok(table) = toml::from_str::<toml::Table>(ENGLISH) {
+        flatten("", &table, &mut messages);
+    }
+    messages
+}
+
+/// Overlay `locales/pt_br.toml`, falling back to `locales/pt.toml`.
+fn load_translation(locale: &str, messages: &mut HashMap<String, String>) -> Result<(), String> {
+    let dir = config_path()
+        .and_then(|path| path.parent().map(|dir| dir.join("locales")))
+        .ok_or("No config directory to read translations from")?;
+    let normalized = locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('-', "_")
+        .to_lowercase();
+    let language = normalized.split('_').next().unwrap_or_default().to_string();
+    let path = [normalized, language]
+        .into_iter()
+        .map(|name| dir.join(format!("{name}.toml")))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            format!(
+                "No translation for locale '{locale}' in {}; using English",
+                dir.display()
+            )
+        })?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|err| format!("Unable to read {}: {err}", path.display()))?;
+    let table: toml::Table = toml::from_str(&contents)
+        .map_err(|err| format!("Ignoring invalid translation {}: {err}", path.display()))?;
+    flatten("", &table, messages);
+    Ok(())
+}
+
+/// `[title] status = "..."` becomes `title.status`.
+fn flatten(prefix: &str, table: &toml::Table, messages: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::String(message) => {
+                messages.insert(key, message.clone());
+            }
+            toml::Value::Table(nested) => flatten(&key, nested, messages),
+            _ => {}
+        }
+    }
+}
+
+fn environment_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+fn is_english(locale: &str) -> bool {
+    let locale = locale.to_lowercase();
+    locale == "c" || locale.starts_with("c.") || locale == "posix" || locale.starts_with("en")
+}
@@ -3,31 +3,77 @@ use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use arboard::Clipboard;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, ModifierKeyCode};
+use futures::future::join_all;
 use tokio::sync::mpsc;
 use tokio::task;
 use tui_input::backend::crossterm::EventHandler;
+use tui_input::{Input as SingleLineInput, InputRequest};
 use tui_textarea::Input as TextAreaInput;
 
-use crate::app::{App, FocusField, QueryFileEntry, SaveDialogMode};
-use crate::log_fetcher::{LogFetcher, QueryOutcome};
+use crate::app::{
+    format_local_timestamp, App, Notice, OpenDialogMode, PendingRun, QueryFileEntry,
+    SaveDialogMode, WindowNudge, WorkspaceCommand,
+};
+use crate::aws_profiles;
+use crate::block_edit::BlockCaret;
+use crate::clipboard;
+use crate::config::{GuardAction, GuardConfig};
+use crate::export::{self, ExportFormat, EXPORTS_DIR, SUPPORTED_EXTENSIONS};
+use crate::focus::FocusField;
+use crate::i18n;
+use crate::log_fetcher::{
+    simple_filter, CallWatch, ExportTaskState, LogFetcher, LogField, LogRecord, QueryEngine,
+    QueryLimiter, QueryOutcome, QueryParams, QueryReport, QueueTicket, S3ExportRequest,
+    SimpleFilter, MAX_QUERY_LOG_GROUPS,
+};
+use crate::metrics;
+use crate::opensearch;
+use crate::palette::PaletteAction;
+use crate::query_pairs;
+use crate::share;
+use crate::snapshot::{self, SNAPSHOTS_DIR};
+use crate::table_image;
+use crate::widgets::date_picker::PickerPart;
 
 const QUERIES_DIR: &str = "queries";
+const S3_EXPORT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const MODAL_HSCROLL_STEP: i32 = 8;
+/// Column naming the account each row came from when a profile group runs.
+const ACCOUNT_FIELD: &str = "@account";
 
-fn queries_directory() -> Result<PathBuf, String> {
+pub(crate) fn queries_directory() -> Result<PathBuf, String> {
+    working_subdirectory(QUERIES_DIR)
+}
+
+fn snapshots_directory() -> Result<PathBuf, String> {
+    working_subdirectory(SNAPSHOTS_DIR)
+}
+
+fn exports_directory() -> Result<PathBuf, String> {
+    working_subdirectory(EXPORTS_DIR)
+}
+
+fn working_subdirectory(name: &str) -> Result<PathBuf, String> {
     let cwd =
         env::current_dir().map_err(|err| format!("Unable to resolve working directory: {err}"))?;
-    Ok(cwd.join(QUERIES_DIR))
+    Ok(cwd.join(name))
 }
 
 pub async fn handle_key_event(
     key: KeyEvent,
     app: &mut App,
     fetcher: &Arc<dyn LogFetcher>,
-    tx: &mpsc::UnboundedSender<QueryOutcome>,
+    tx: &mpsc::UnboundedSender<QueryReport>,
+    notices: &mpsc::UnboundedSender<Notice>,
 ) -> Result<bool, Box<dyn Error>> {
+    if let KeyCode::Modifier(ModifierKeyCode::LeftAlt | ModifierKeyCode::RightAlt) = key.code {
+        // Only terminals that report bare modifier keys send these.
+        app.show_mnemonics = key.kind != KeyEventKind::Release;
+        return Ok(false);
+    }
     if key.kind != KeyEventKind::Press {
         return Ok(false);
     }
@@ -36,6 +82,109 @@ pub async fn handle_key_event(
     let code = key.code;
     let ctrl = modifiers.contains(KeyModifiers::CONTROL);
     let super_mod = modifiers.contains(KeyModifiers::SUPER);
+    let shift = modifiers.contains(KeyModifiers::SHIFT);
+    let alt = modifiers.contains(KeyModifiers::ALT);
+    // Mnemonics stay visible while Alt chords keep arriving.
+    app.show_mnemonics = alt;
+
+    if app.quit_prompt.is_some() {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => return Ok(true),
+            KeyCode::Char('c') if ctrl => return Ok(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_quit(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if app.run_prompt.is_some() {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                app.confirm_production_run();
+                match app.pending_run {
+                    PendingRun::Query => start_query_submission(app, fetcher, tx),
+                    PendingRun::CloudTrail => app.toggle_cloudtrail(),
+                    PendingRun::ValueSample => app.start_value_sample(),
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.run_prompt = None;
+                if app.pending_run == PendingRun::ValueSample {
+                    app.value_field_input = None;
+                }
+                app.set_status(i18n::text("status.query_not_run"));
+            }
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if let Some((_, input)) = app.note_input.as_mut() {
+        match code {
+            KeyCode::Esc => app.note_input = None,
+            KeyCode::Enter => app.apply_note(),
+            _ => {
+                let _ = input.handle_event(&Event::Key(key));
+            }
+        }
+        return Ok(false);
+    }
+
+    if let Some(input) = app.value_field_input.as_mut() {
+        match code {
+            KeyCode::Esc => app.value_field_input = None,
+            KeyCode::Enter => app.start_value_sample(),
+            _ => {
+                let _ = input.handle_event(&Event::Key(key));
+            }
+        }
+        return Ok(false);
+    }
+
+    if let Some(input) = app.variables_input.as_mut() {
+        match code {
+            KeyCode::Esc => app.variables_input = None,
+            KeyCode::Enter => app.submit_variables(),
+            _ => {
+                let _ = input.handle_event(&Event::Key(key));
+            }
+        }
+        return Ok(false);
+    }
+
+    if let Some(input) = app.computed_input.as_mut() {
+        match code {
+            KeyCode::Esc => app.computed_input = None,
+            KeyCode::Enter => app.submit_computed_column(),
+            _ => {
+                let _ = input.handle_event(&Event::Key(key));
+            }
+        }
+        return Ok(false);
+    }
+
+    if let Some(picker) = app.value_picker.as_mut() {
+        match code {
+            KeyCode::Esc => app.value_picker = None,
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            KeyCode::Char(' ') => picker.toggle_selected(),
+            KeyCode::Enter => app.insert_value_filter(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if let Some(input) = app.rename_input.as_mut() {
+        match code {
+            KeyCode::Esc => app.rename_input = None,
+            KeyCode::Enter => app.apply_rename(),
+            _ => {
+                let _ = input.handle_event(&Event::Key(key));
+            }
+        }
+        return Ok(false);
+    }
 
     if app.help_open {
         if (ctrl && matches!(code, KeyCode::Char('h') | KeyCode::Char('H')))
@@ -46,34 +195,192 @@ pub async fn handle_key_event(
         return Ok(false);
     }
 
+    if app.palette_active() {
+        match code {
+            KeyCode::Esc => app.close_palette(),
+            KeyCode::Enter => {
+                let action = app
+                    .palette
+                    .as_ref()
+                    .and_then(|state| state.selected_action());
+                app.close_palette();
+                if let Some(action) = action {
+                    return Ok(run_palette_action(action, app, fetcher, tx, notices).await);
+                }
+            }
+            KeyCode::Up => {
+                if let Some(state) = app.palette.as_mut() {
+                    state.move_selection(-1);
+                }
+            }
+            KeyCode::Down => {
+                if let Some(state) = app.palette.as_mut() {
+                    state.move_selection(1);
+                }
+            }
+            _ => {
+                if let Some(state) = app.palette.as_mut() {
+                    let previous = state.filter_input.value().to_string();
+                    let _ = state.filter_input.handle_event(&Event::Key(key));
+                    if state.filter_input.value() != previous {
+                        state.apply_filter();
+                    }
+                }
+            }
+        }
+        return Ok(false);
+    }
+
+    if let Some((_, state)) = app.date_picker.as_mut() {
+        let day_part = state.part() == PickerPart::Day;
+        match code {
+            KeyCode::Esc => app.close_date_picker(),
+            KeyCode::Enter => app.apply_date_picker(),
+            KeyCode::Tab => state.cycle_part(true),
+            KeyCode::BackTab => state.cycle_part(false),
+            KeyCode::PageUp => state.move_months(-1),
+            KeyCode::PageDown => state.move_months(1),
+            KeyCode::Left if day_part => state.move_days(-1),
+            KeyCode::Right if day_part => state.move_days(1),
+            KeyCode::Up if day_part => state.move_days(-7),
+            KeyCode::Down if day_part => state.move_days(7),
+            KeyCode::Left => state.cycle_part(false),
+            KeyCode::Right => state.cycle_part(true),
+            KeyCode::Up => state.adjust_time(1),
+            KeyCode::Down => state.adjust_time(-1),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if (ctrl || super_mod) && matches!(code, KeyCode::Char('k') | KeyCode::Char('K')) {
+        app.open_palette();
+        return Ok(false);
+    }
+
     if app.modal_open
         && (modifiers.is_empty() || modifiers == KeyModifiers::SHIFT)
         && matches!(code, KeyCode::Char('c') | KeyCode::Char('C'))
     {
         if let Some(text) = app.selected_row_detail_text() {
-            match Clipboard::new() {
-                Ok(mut clipboard) => {
-                    if let Err(err) = clipboard.set_text(text) {
-                        app.set_error(format!("Unable to copy row details: {err}"));
-                    } else {
-                        app.set_status("Copied row details to clipboard.");
-                    }
-                }
-                Err(err) => {
-                    app.set_error(format!("Unable to access clipboard: {err}"));
+            match clipboard::copy_text(text, app.config.clipboard.backend) {
+                Ok(target) => {
+                    app.set_status(i18n::fill("status.copied_row", &[("target", target)]))
                 }
+                Err(err) => app.set_error(err),
             }
         } else {
-            app.set_status("No row details to copy.");
+            app.set_status(i18n::text("status.no_row_to_copy"));
+        }
+        return Ok(false);
+    }
+
+    if let Some(view) = app.clusters.as_mut() {
+        match code {
+            KeyCode::Esc | KeyCode::Char('g') => app.clusters = None,
+            KeyCode::Up => view.move_selection(-1),
+            KeyCode::Down => view.move_selection(1),
+            KeyCode::PageUp => view.move_selection(-10),
+            KeyCode::PageDown => view.move_selection(10),
+            KeyCode::Enter => app.jump_to_cluster_example(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if app.query_diff.is_some() {
+        match code {
+            KeyCode::Esc | KeyCode::Enter => app.query_diff = None,
+            KeyCode::Char('r') => app.revert_query_to_saved(),
+            KeyCode::Up => app.scroll_query_diff(-1),
+            KeyCode::Down => app.scroll_query_diff(1),
+            KeyCode::PageUp => app.scroll_query_diff(-10),
+            KeyCode::PageDown => app.scroll_query_diff(10),
+            _ => {}
         }
         return Ok(false);
     }
 
+    if app.trace_scroll.is_some() {
+        match code {
+            KeyCode::Esc | KeyCode::Enter => app.trace_scroll = None,
+            KeyCode::Up => app.scroll_query_trace(-1),
+            KeyCode::Down => app.scroll_query_trace(1),
+            KeyCode::PageUp => app.scroll_query_trace(-10),
+            KeyCode::PageDown => app.scroll_query_trace(10),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if app.column_stats.is_some() {
+        match code {
+            KeyCode::Esc | KeyCode::Char('s') => app.column_stats = None,
+            KeyCode::Left => app.cycle_column_stats(-1),
+            KeyCode::Right => app.cycle_column_stats(1),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    if app.modal_open && modifiers.is_empty() {
+        match code {
+            KeyCode::Up => {
+                app.move_modal_field(-1);
+                return Ok(false);
+            }
+            KeyCode::Down => {
+                app.move_modal_field(1);
+                return Ok(false);
+            }
+            KeyCode::Char('v') => {
+                app.toggle_modal_raw();
+                return Ok(false);
+            }
+            KeyCode::Char('w') => {
+                app.toggle_modal_wrap();
+                return Ok(false);
+            }
+            KeyCode::Left if !app.modal_wrap => {
+                app.scroll_modal_horizontally(-MODAL_HSCROLL_STEP);
+                return Ok(false);
+            }
+            KeyCode::Right if !app.modal_wrap => {
+                app.scroll_modal_horizontally(MODAL_HSCROLL_STEP);
+                return Ok(false);
+            }
+            KeyCode::Home if !app.modal_wrap => {
+                app.modal_hscroll = 0;
+                return Ok(false);
+            }
+            KeyCode::Char('o') => {
+                app.open_modal_url();
+                return Ok(false);
+            }
+            KeyCode::Char('y') => {
+                match app.selected_modal_field() {
+                    Some((header, value)) => {
+                        match clipboard::copy_text(value, app.config.clipboard.backend) {
+                            Ok(target) => app.set_status(i18n::fill(
+                                "status.copied_field",
+                                &[("header", &header), ("target", target)],
+                            )),
+                            Err(err) => app.set_error(err),
+                        }
+                    }
+                    None => app.set_status(i18n::text("status.no_field_to_copy")),
+                }
+                return Ok(false);
+            }
+            _ => {}
+        }
+    }
+
     if app.save_dialog_active() {
         match code {
             KeyCode::Esc => {
                 app.close_save_dialog();
-                app.set_status("Save canceled");
+                app.set_status(i18n::text("status.save_canceled"));
             }
             KeyCode::Up => {
                 if let Some(state) = app.save_dialog_state_mut() {
@@ -86,7 +393,7 @@ pub async fn handle_key_event(
                 }
             }
             KeyCode::Enter => {
-                if let Err(err) = confirm_save_dialog(app).await {
+                if let Err(err) = confirm_save_dialog(app, fetcher, notices).await {
                     app.set_error(err);
                 }
             }
@@ -104,7 +411,7 @@ pub async fn handle_key_event(
         match code {
             KeyCode::Esc => {
                 app.close_open_dialog();
-                app.set_status("Open canceled");
+                app.set_status(i18n::text("status.open_canceled"));
             }
             KeyCode::Enter => {
                 if let Err(err) = confirm_open_dialog(app).await {
@@ -135,6 +442,18 @@ pub async fn handle_key_event(
         return Ok(false);
     }
 
+    if let Some(state) = app.input_pane_picker.as_mut() {
+        match code {
+            KeyCode::Esc => app.input_pane_picker = None,
+            KeyCode::Enter => app.apply_input_pane_picker(),
+            KeyCode::Up => state.move_selection(-1),
+            KeyCode::Down => state.move_selection(1),
+            KeyCode::Char(' ') => state.toggle_selected(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
     if app.column_modal_active() {
         match code {
             KeyCode::Esc => {
@@ -157,13 +476,117 @@ pub async fn handle_key_event(
         return Ok(false);
     }
 
+    if alt && !ctrl && !super_mod {
+        if let KeyCode::Char(digit @ '1'..='9') = code {
+            let slot = digit as usize - '1' as usize;
+            if app.select_quick_range(slot).is_some() {
+                start_query_submission(app, fetcher, tx);
+            }
+            return Ok(false);
+        }
+        if matches!(code, KeyCode::Char('n') | KeyCode::Char('N')) {
+            app.workspace_command = Some(WorkspaceCommand::NextUnseen);
+            return Ok(false);
+        }
+        if let Some(field) = jump_target(app, code) {
+            app.close_modal();
+            if field == FocusField::Filter {
+                app.expand_inputs();
+                focus_results_filter(app);
+            } else {
+                focus_input(app, field);
+            }
+            return Ok(false);
+        }
+    }
+
+    if app.focus == FocusField::Query
+        && !ctrl
+        && !alt
+        && matches!(code, KeyCode::Tab | KeyCode::BackTab)
+        && app.query_tab(code == KeyCode::Tab)
+    {
+        return Ok(false);
+    }
+    if app.focus == FocusField::Query && code == KeyCode::Esc && app.snippet_session.is_some() {
+        app.snippet_session = None;
+        app.query_area.cancel_selection();
+        return Ok(false);
+    }
+
+    if app.focus == FocusField::Query
+        && alt
+        && shift
+        && !ctrl
+        && matches!(code, KeyCode::Up | KeyCode::Down)
+    {
+        let delta = if code == KeyCode::Up { -1 } else { 1 };
+        let caret = app
+            .query_block
+            .get_or_insert_with(|| BlockCaret::at_cursor(&app.query_area));
+        caret.extend(&mut app.query_area, delta);
+        return Ok(false);
+    }
+
+    if let Some(caret) = app
+        .query_block
+        .as_mut()
+        .filter(|_| app.focus == FocusField::Query)
+    {
+        let mut handled = true;
+        match code {
+            KeyCode::Char(ch) if !ctrl && !alt && !super_mod => {
+                caret.insert(&mut app.query_area, ch.encode_utf8(&mut [0; 4]));
+            }
+            KeyCode::Backspace => caret.backspace(&mut app.query_area),
+            KeyCode::Delete => caret.delete(&mut app.query_area),
+            KeyCode::Left => caret.move_col(&mut app.query_area, -1),
+            KeyCode::Right => caret.move_col(&mut app.query_area, 1),
+            KeyCode::Esc => app.query_block = None,
+            _ => {
+                // Anything else ends block editing and acts as usual.
+                app.query_block = None;
+                handled = false;
+            }
+        }
+        if handled {
+            return Ok(false);
+        }
+    }
+
+    if app.chip_cursor.is_some() && app.focus == FocusField::Results && !app.modal_open {
+        let mut handled = true;
+        match code {
+            KeyCode::Left => app.move_chip_cursor(-1),
+            KeyCode::Right => app.move_chip_cursor(1),
+            KeyCode::Char(' ') => app.toggle_chip(),
+            KeyCode::Char('-') => app.flip_chip(),
+            KeyCode::Delete | KeyCode::Backspace => app.remove_chip(),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('f') => app.chip_cursor = None,
+            _ => {
+                // Anything else leaves the chip row and acts as usual.
+                app.chip_cursor = None;
+                handled = false;
+            }
+        }
+        if handled {
+            return Ok(false);
+        }
+    }
+
+    // Esc closes the popup rather than the zoom underneath it.
+    if (code == KeyCode::F(11) || (code == KeyCode::Esc && app.query_zoomed)) && !app.popup_open() {
+        app.toggle_query_zoom();
+        return Ok(false);
+    }
+
     if code == KeyCode::Esc {
         if app.modal_open {
             app.close_modal();
             return Ok(false);
         }
         match app.focus {
-            FocusField::Filter => {
+            FocusField::Filter | FocusField::Scratchpad => {
                 app.focus = FocusField::Results;
                 return Ok(false);
             }
@@ -174,6 +597,10 @@ pub async fn handle_key_event(
             }
             _ => {}
         }
+        if app.config.keys.esc_quits {
+            return Ok(app.request_quit());
+        }
+        return Ok(false);
     }
 
     if modifiers.is_empty()
@@ -181,33 +608,83 @@ pub async fn handle_key_event(
         && app.focus == FocusField::Results
         && !app.inputs_collapsed
     {
-        app.activate_filter();
-        app.focus = FocusField::Filter;
+        focus_results_filter(app);
         return Ok(false);
     }
 
-    if (ctrl || super_mod) && matches!(code, KeyCode::Char('s') | KeyCode::Char('S')) {
-        match gather_query_file_entries().await {
-            Ok(entries) => {
-                let prefill = app.saved_query_file_name();
-                app.open_save_dialog_with_entries(SaveDialogMode::Save, prefill, entries);
-            }
-            Err(err) => app.set_error(err),
+    if (ctrl || super_mod)
+        && (shift || code == KeyCode::Char('S'))
+        && matches!(code, KeyCode::Char('s') | KeyCode::Char('S'))
+    {
+        open_snapshot_save_dialog(app).await;
+        return Ok(false);
+    }
+
+    if (ctrl || super_mod)
+        && (shift || code == KeyCode::Char('O'))
+        && matches!(code, KeyCode::Char('o') | KeyCode::Char('O'))
+    {
+        open_snapshot_open_dialog(app).await;
+        return Ok(false);
+    }
+
+    if (ctrl || super_mod) && matches!(code, KeyCode::Char('f') | KeyCode::Char('F')) {
+        if app.push_filter_to_query() {
+            start_query_submission(app, fetcher, tx);
         }
         return Ok(false);
     }
 
+    if (ctrl || super_mod) && matches!(code, KeyCode::Char('w') | KeyCode::Char('W')) {
+        share_results(app, notices);
+        return Ok(false);
+    }
+
+    if (ctrl || super_mod)
+        && (shift || code == KeyCode::Char('E'))
+        && matches!(code, KeyCode::Char('e') | KeyCode::Char('E'))
+    {
+        open_s3_export_dialog(app);
+        return Ok(false);
+    }
+
+    if (ctrl || super_mod) && matches!(code, KeyCode::Char('e') | KeyCode::Char('E')) {
+        open_export_dialog(app).await;
+        return Ok(false);
+    }
+
+    if (ctrl || super_mod) && matches!(code, KeyCode::Char('s') | KeyCode::Char('S')) {
+        open_query_save_dialog(app).await;
+        return Ok(false);
+    }
+
     if (ctrl || super_mod) && matches!(code, KeyCode::Char('o') | KeyCode::Char('O')) {
-        match gather_query_file_entries().await {
-            Ok(entries) => {
-                if entries.is_empty() {
-                    app.set_status("No saved queries available");
-                } else {
-                    app.open_open_dialog(entries);
-                }
+        open_query_open_dialog(app).await;
+        return Ok(false);
+    }
+
+    if app.focus == FocusField::Results && !ctrl && !alt && !super_mod {
+        let nudge = match code {
+            KeyCode::Char('[') => Some(WindowNudge::Earlier),
+            KeyCode::Char(']') => Some(WindowNudge::Later),
+            KeyCode::Char('{') => Some(WindowNudge::Narrower),
+            KeyCode::Char('}') => Some(WindowNudge::Wider),
+            _ => None,
+        };
+        if let Some(nudge) = nudge {
+            match app.nudge_time_window(nudge) {
+                Ok(()) => start_query_submission(app, fetcher, tx),
+                Err(err) => app.set_error(err),
             }
-            Err(err) => app.set_error(err),
+            return Ok(false);
         }
+    }
+
+    if app.focus == FocusField::Results
+        && code == KeyCode::Char('@')
+        && (modifiers.is_empty() || modifiers == KeyModifiers::SHIFT)
+    {
+        app.replay_macro();
         return Ok(false);
     }
 
@@ -217,7 +694,9 @@ pub async fn handle_key_event(
                 if app.modal_open {
                     app.close_modal();
                 } else if app.results_navigation {
-                    app.toggle_modal();
+                    if !app.request_drill_down() {
+                        app.toggle_modal();
+                    }
                 } else {
                     app.enter_results_navigation();
                 }
@@ -243,31 +722,81 @@ pub async fn handle_key_event(
                 app.open_column_modal();
                 return Ok(false);
             }
-            KeyCode::Char('x') => {
-                if app.results_navigation || app.modal_open {
-                    app.exit_results_navigation();
+            KeyCode::Char('m') if app.results_navigation => {
+                if let Some(count) = app.toggle_mark_selected_row() {
+                    app.set_status(i18n::fill(
+                        "status.rows_marked",
+                        &[("count", &count.to_string())],
+                    ));
                 }
                 return Ok(false);
             }
-            _ => {}
-        }
-    }
-
-    if app.focus == FocusField::AwsProfile && modifiers.is_empty() {
-        match code {
-            KeyCode::Left | KeyCode::Up => {
-                app.move_profile_selection(-1);
+            KeyCode::Char('z') => {
+                zoom_to_marked_rows(app, fetcher, tx);
                 return Ok(false);
             }
-            KeyCode::Right | KeyCode::Down => {
-                app.move_profile_selection(1);
+            KeyCode::Char('q') => {
+                app.toggle_macro_recording();
                 return Ok(false);
             }
-            _ => {}
-        }
-    }
-
-    if app.focus == FocusField::TimeMode && modifiers.is_empty() {
+            KeyCode::Char('n') if app.results_navigation => {
+                app.open_note_dialog();
+                return Ok(false);
+            }
+            KeyCode::Char('s') => {
+                app.open_column_stats();
+                return Ok(false);
+            }
+            KeyCode::Char('i') => {
+                if let Err(err) = save_table_image(app).await {
+                    app.set_error(err);
+                }
+                return Ok(false);
+            }
+            KeyCode::Char('g') => {
+                app.open_clusters();
+                return Ok(false);
+            }
+            KeyCode::Char('f') => {
+                app.focus_chips();
+                return Ok(false);
+            }
+            KeyCode::Char('d') => {
+                app.toggle_sampling();
+                return Ok(false);
+            }
+            KeyCode::Char('x') => {
+                // The watchdog's escape hatch, once no dialog is left to take the key.
+                if app.query_stalled().is_some() && !app.modal_open {
+                    app.abort_query();
+                } else if app.results_navigation || app.modal_open {
+                    app.exit_results_navigation();
+                }
+                return Ok(false);
+            }
+            _ => {}
+        }
+    }
+
+    if app.focus == FocusField::AwsProfile && modifiers.is_empty() {
+        match code {
+            KeyCode::Left | KeyCode::Up => {
+                app.move_profile_selection(-1);
+                return Ok(false);
+            }
+            KeyCode::Right | KeyCode::Down => {
+                app.move_profile_selection(1);
+                return Ok(false);
+            }
+            KeyCode::Enter => {
+                apply_profile(app, fetcher, notices);
+                return Ok(false);
+            }
+            _ => {}
+        }
+    }
+
+    if app.focus == FocusField::TimeMode && modifiers.is_empty() {
         match code {
             KeyCode::Enter
             | KeyCode::Char(' ')
@@ -328,7 +857,7 @@ pub async fn handle_key_event(
         }
     }
 
-    if is_ctrl_enter(&key) {
+    if is_run_key(&key, app) {
         start_query_submission(app, fetcher, tx);
         return Ok(false);
     }
@@ -350,8 +879,15 @@ pub async fn handle_key_event(
             _ => {}
         }
         match code {
-            KeyCode::Char('c') => return Ok(true),
+            KeyCode::Char('q') | KeyCode::Char('c') => return Ok(app.request_quit()),
             KeyCode::Char('r') => start_query_submission(app, fetcher, tx),
+            KeyCode::Char('p') => app.cycle_preset(),
+            KeyCode::Char('l') => copy_console_link(app),
+            KeyCode::Char(' ') if app.focus == FocusField::Query => app.complete_query_field(),
+            KeyCode::Char('t') => app.workspace_command = Some(WorkspaceCommand::Open),
+            KeyCode::PageDown => app.workspace_command = Some(WorkspaceCommand::Next),
+            KeyCode::PageUp => app.workspace_command = Some(WorkspaceCommand::Previous),
+            KeyCode::F(4) => app.workspace_command = Some(WorkspaceCommand::Close),
             _ => {}
         }
         return Ok(false);
@@ -394,16 +930,29 @@ pub async fn handle_key_event(
             }
             return Ok(false);
         }
+        KeyCode::F(2) => {
+            app.open_rename_dialog();
+            return Ok(false);
+        }
+        KeyCode::F(3) => {
+            app.toggle_scratchpad();
+            return Ok(false);
+        }
+        KeyCode::F(5) if modifiers.contains(KeyModifiers::SHIFT) => {
+            refresh_query_submission(app, fetcher, tx);
+            return Ok(false);
+        }
         KeyCode::F(5) => {
             start_query_submission(app, fetcher, tx);
             return Ok(false);
         }
         KeyCode::Enter
-            if matches!(
-                app.focus,
-                FocusField::AwsRegion | FocusField::From | FocusField::To | FocusField::LogGroup
-            ) =>
+            if matches!(app.focus, FocusField::From | FocusField::To) && modifiers.is_empty() =>
         {
+            app.open_date_picker(app.focus);
+            return Ok(false);
+        }
+        KeyCode::Enter if matches!(app.focus, FocusField::AwsRegion | FocusField::LogGroup) => {
             start_query_submission(app, fetcher, tx);
             return Ok(false);
         }
@@ -420,14 +969,35 @@ pub async fn handle_key_event(
             let _ = app.to_input.handle_event(&event);
         }
         FocusField::LogGroup => {
+            if matches!(code, KeyCode::Right | KeyCode::End) && app.accept_log_group_completion() {
+                return Ok(false);
+            }
+            let previous = app.log_group_input.value().to_string();
             let _ = app.log_group_input.handle_event(&event);
+            if app.log_group_input.value() != previous {
+                app.apply_default_query();
+            }
         }
         FocusField::AwsRegion => {
             let _ = app.aws_region_input.handle_event(&event);
         }
         FocusField::Query => {
-            let input = TextAreaInput::from(event.clone());
-            app.query_area.input(input);
+            let paired = match code {
+                KeyCode::Char(ch) if !ctrl && !alt && !super_mod => {
+                    query_pairs::type_char(&mut app.query_area, ch)
+                }
+                KeyCode::Backspace if modifiers.is_empty() => {
+                    query_pairs::backspace(&mut app.query_area)
+                }
+                _ => false,
+            };
+            if !paired {
+                let input = TextAreaInput::from(event.clone());
+                app.query_area.input(input);
+            }
+        }
+        FocusField::Scratchpad => {
+            app.scratchpad.input(TextAreaInput::from(event.clone()));
         }
         FocusField::Results => {}
         FocusField::Filter => {
@@ -448,10 +1018,98 @@ pub async fn handle_key_event(
     Ok(false)
 }
 
+/// Bracketed paste goes into whichever input is active. Several names pasted into
+/// the Log group field become its comma-separated list.
+pub fn handle_paste(app: &mut App, text: &str) {
+    // Single-line inputs take the pasted lines joined by spaces.
+    let line = text.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+    if let Some((_, input)) = app.note_input.as_mut() {
+        insert_text(input, &line);
+        return;
+    }
+    if let Some(input) = app.rename_input.as_mut() {
+        insert_text(input, &line);
+        return;
+    }
+    if let Some(input) = app.value_field_input.as_mut() {
+        insert_text(input, &line);
+        return;
+    }
+    if let Some(input) = app.computed_input.as_mut() {
+        insert_text(input, &line);
+        return;
+    }
+    if let Some(input) = app.variables_input.as_mut() {
+        insert_text(input, &line);
+        return;
+    }
+    if let Some(state) = app.palette.as_mut() {
+        insert_text(&mut state.filter_input, &line);
+        state.apply_filter();
+        return;
+    }
+    if let Some(state) = app.save_dialog_state_mut() {
+        insert_text(&mut state.input, &line);
+        return;
+    }
+    if let Some(state) = app.open_dialog_state_mut() {
+        insert_text(&mut state.filter_input, &line);
+        state.apply_filter();
+        return;
+    }
+    if app.quit_prompt.is_some()
+        || app.run_prompt.is_some()
+        || app.help_open
+        || app.modal_open
+        || app.column_stats.is_some()
+        || app.clusters.is_some()
+        || app.value_picker.is_some()
+        || app.date_picker_active()
+        || app.column_modal_active()
+        || app.input_pane_picker.is_some()
+    {
+        return;
+    }
+    match app.focus {
+        FocusField::LogGroup if text.trim().contains(['\n', ',', ';', '\t']) => {
+            app.paste_log_groups(text);
+            app.apply_default_query();
+        }
+        FocusField::LogGroup => {
+            insert_text(&mut app.log_group_input, text.trim());
+            app.apply_default_query();
+        }
+        FocusField::Query => {
+            app.query_area.insert_str(text);
+        }
+        FocusField::Scratchpad => {
+            app.scratchpad.insert_str(text);
+        }
+        FocusField::From => insert_text(&mut app.from_input, &line),
+        FocusField::To => insert_text(&mut app.to_input, &line),
+        FocusField::AwsRegion => insert_text(&mut app.aws_region_input, &line),
+        FocusField::Filter => {
+            insert_text(&mut app.filter_input, &line);
+            app.schedule_filter_update();
+        }
+        FocusField::AwsProfile
+        | FocusField::TimeMode
+        | FocusField::RelativeRange
+        | FocusField::Results => {}
+    }
+}
+
+fn insert_text(input: &mut SingleLineInput, text: &str) {
+    for c in text.chars().filter(|c| !c.is_control()) {
+        input.handle(InputRequest::InsertChar(c));
+    }
+}
+
 fn focus_accepts_text_input(focus: FocusField) -> bool {
     matches!(
         focus,
         FocusField::Query
+            | FocusField::Scratchpad
             | FocusField::From
             | FocusField::To
             | FocusField::LogGroup
@@ -460,36 +1118,400 @@ fn focus_accepts_text_input(focus: FocusField) -> bool {
     )
 }
 
-async fn confirm_save_dialog(app: &mut App) -> Result<(), String> {
+async fn open_snapshot_save_dialog(app: &mut App) {
+    if app.results.rows.is_empty() {
+        app.set_status(i18n::text("status.no_results_to_snapshot"));
+        return;
+    }
+    match gather_snapshot_file_entries().await {
+        Ok(entries) => {
+            let prefill = format!("results-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            app.open_save_dialog_with_entries(SaveDialogMode::Snapshot, Some(prefill), entries);
+        }
+        Err(err) => app.set_error(err),
+    }
+}
+
+async fn open_snapshot_open_dialog(app: &mut App) {
+    match gather_snapshot_file_entries().await {
+        Ok(entries) => {
+            if entries.is_empty() {
+                app.set_status(i18n::text("status.no_snapshots"));
+            } else {
+                app.open_open_dialog(OpenDialogMode::Snapshot, entries);
+            }
+        }
+        Err(err) => app.set_error(err),
+    }
+}
+
+fn open_s3_export_dialog(app: &mut App) {
+    let prefill = app.last_s3_destination.clone();
+    app.open_save_dialog_with_entries(SaveDialogMode::S3Export, prefill, Vec::new());
+}
+
+async fn open_export_dialog(app: &mut App) {
+    if app.filtered_indices.is_empty() {
+        app.set_status(i18n::text("status.no_results_to_export"));
+        return;
+    }
+    match gather_export_file_entries().await {
+        Ok(entries) => {
+            let prefill = format!(
+                "results-{}.csv",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            );
+            app.open_save_dialog_with_entries(SaveDialogMode::Export, Some(prefill), entries);
+        }
+        Err(err) => app.set_error(err),
+    }
+}
+
+async fn open_query_save_dialog(app: &mut App) {
+    match gather_query_file_entries().await {
+        Ok(entries) => {
+            let prefill = app.saved_query_file_name();
+            app.open_save_dialog_with_entries(SaveDialogMode::Save, prefill, entries);
+        }
+        Err(err) => app.set_error(err),
+    }
+}
+
+async fn open_query_open_dialog(app: &mut App) {
+    match gather_query_file_entries().await {
+        Ok(entries) => {
+            if entries.is_empty() {
+                app.set_status(i18n::text("status.no_saved_queries"));
+            } else {
+                app.open_open_dialog(OpenDialogMode::Query, entries);
+            }
+        }
+        Err(err) => app.set_error(err),
+    }
+}
+
+fn focus_results_filter(app: &mut App) {
+    if app.is_hidden_input(FocusField::Filter) {
+        app.set_status(i18n::text("status.filter_hidden"));
+        return;
+    }
+    app.activate_filter();
+    app.focus = FocusField::Filter;
+}
+
+/// Carry out a command palette choice; returns true when the app should quit.
+async fn run_palette_action(
+    action: PaletteAction,
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    tx: &mpsc::UnboundedSender<QueryReport>,
+    notices: &mpsc::UnboundedSender<Notice>,
+) -> bool {
+    match action {
+        PaletteAction::RunQuery => start_query_submission(app, fetcher, tx),
+        PaletteAction::RefreshQuery => refresh_query_submission(app, fetcher, tx),
+        PaletteAction::NewWorkspace => app.workspace_command = Some(WorkspaceCommand::Open),
+        PaletteAction::NextWorkspace => app.workspace_command = Some(WorkspaceCommand::Next),
+        PaletteAction::RenameWorkspace => app.open_rename_dialog(),
+        PaletteAction::CloseWorkspace => app.workspace_command = Some(WorkspaceCommand::Close),
+        PaletteAction::OpenQuery => open_query_open_dialog(app).await,
+        PaletteAction::SaveQuery => open_query_save_dialog(app).await,
+        PaletteAction::ToggleColumns => {
+            if app.results.headers.is_empty() {
+                app.set_status(i18n::text("status.no_columns"));
+            } else {
+                app.open_column_modal();
+            }
+        }
+        PaletteAction::FilterResults => {
+            app.expand_inputs();
+            focus_results_filter(app);
+        }
+        PaletteAction::ExportResults => open_export_dialog(app).await,
+        PaletteAction::ExportToS3 => open_s3_export_dialog(app),
+        PaletteAction::ShareResults => share_results(app, notices),
+        PaletteAction::IndexToOpenSearch => index_results(app, notices),
+        PaletteAction::PushMetrics => push_metrics(app, notices),
+        PaletteAction::SaveSnapshot => open_snapshot_save_dialog(app).await,
+        PaletteAction::OpenSnapshot => open_snapshot_open_dialog(app).await,
+        PaletteAction::FocusRegion => focus_input(app, FocusField::AwsRegion),
+        PaletteAction::FocusProfile => {
+            if app.show_profile_picker() {
+                focus_input(app, FocusField::AwsProfile);
+            } else {
+                app.set_status(i18n::text("status.no_profiles"));
+            }
+        }
+        PaletteAction::ApplyProfile => apply_profile(app, fetcher, notices),
+        PaletteAction::FocusTimeRange => {
+            let field = if app.relative_mode {
+                FocusField::RelativeRange
+            } else {
+                FocusField::From
+            };
+            focus_input(app, field);
+        }
+        PaletteAction::SwitchEnvironment => app.cycle_preset(),
+        PaletteAction::CopyConsoleLink => copy_console_link(app),
+        PaletteAction::CycleQueryEngine => app.cycle_query_engine(),
+        PaletteAction::FocusLogGroup => focus_input(app, FocusField::LogGroup),
+        PaletteAction::FocusQuery => focus_input(app, FocusField::Query),
+        PaletteAction::FocusResults => app.focus = FocusField::Results,
+        PaletteAction::ZoomToMarked => zoom_to_marked_rows(app, fetcher, tx),
+        PaletteAction::EditRowNote => app.open_note_dialog(),
+        PaletteAction::ToggleDurations => app.toggle_humanize_durations(),
+        PaletteAction::ColumnStats => app.open_column_stats(),
+        PaletteAction::MessageClusters => app.open_clusters(),
+        PaletteAction::PushFilterToQuery => {
+            if app.push_filter_to_query() {
+                start_query_submission(app, fetcher, tx);
+            }
+        }
+        PaletteAction::SetBaseline => app.set_baseline(),
+        PaletteAction::ClearBaseline => app.clear_baseline(),
+        PaletteAction::AddComputedColumn => app.open_computed_dialog(),
+        PaletteAction::EditVariables => app.open_variables_dialog(),
+        PaletteAction::PickFieldValues => app.open_value_field_dialog(),
+        PaletteAction::ShowCloudTrail => app.toggle_cloudtrail(),
+        PaletteAction::ExplainRow => app.toggle_explain(),
+        PaletteAction::ToggleScratchpad => app.toggle_scratchpad(),
+        PaletteAction::ToggleSampling => app.toggle_sampling(),
+        PaletteAction::TableImage => {
+            if let Err(err) = save_table_image(app).await {
+                app.set_error(err);
+            }
+        }
+        PaletteAction::ToggleTimeMode => app.toggle_relative_mode(),
+        PaletteAction::ChooseInputPanes => app.open_input_pane_picker(),
+        PaletteAction::ZoomQueryEditor => app.toggle_query_zoom(),
+        PaletteAction::CollapseInputs => app.collapse_inputs(),
+        PaletteAction::ExpandInputs => app.expand_inputs(),
+        PaletteAction::ShowQueryDiff => {
+            if let Err(err) = show_query_diff(app).await {
+                app.set_error(err);
+            }
+        }
+        PaletteAction::ShowQueryTrace => app.open_query_trace(),
+        PaletteAction::ShowHelp => app.toggle_help(),
+        PaletteAction::Quit => return app.request_quit(),
+    }
+    false
+}
+
+/// Alt+letter shortcuts that jump straight to a field.
+fn jump_target(app: &App, code: KeyCode) -> Option<FocusField> {
+    let KeyCode::Char(c) = code else {
+        return None;
+    };
+    match c.to_ascii_lowercase() {
+        'g' => Some(FocusField::LogGroup),
+        'e' => Some(FocusField::AwsRegion),
+        'f' => Some(FocusField::Filter),
+        't' if app.relative_mode => Some(FocusField::RelativeRange),
+        't' => Some(FocusField::From),
+        _ => None,
+    }
+}
+
+fn copy_console_link(app: &mut App) {
+    let url = match app.console_link_url() {
+        Ok(url) => url,
+        Err(err) => {
+            app.set_error(err);
+            return;
+        }
+    };
+    match clipboard::copy_text(url, app.config.clipboard.backend) {
+        Ok(target) => app.set_status(i18n::fill(
+            "status.copied_console_link",
+            &[("target", target)],
+        )),
+        Err(err) => app.set_error(err),
+    }
+}
+
+fn zoom_to_marked_rows(
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    tx: &mpsc::UnboundedSender<QueryReport>,
+) {
+    match app.zoom_to_marked_rows() {
+        Ok(()) => start_query_submission(app, fetcher, tx),
+        Err(err) => app.set_error(err),
+    }
+}
+
+/// Focus an input pane, restoring the panes first if they were collapsed.
+fn focus_input(app: &mut App, field: FocusField) {
+    app.expand_inputs();
+    app.focus = field;
+}
+
+async fn confirm_save_dialog(
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    notices: &mpsc::UnboundedSender<Notice>,
+) -> Result<(), String> {
     let filename = if let Some(state) = app.save_dialog_state_mut() {
         state.input.value().to_string()
     } else {
         return Ok(());
     };
     if filename.is_empty() {
-        app.set_status("Please enter a file name");
+        app.set_status(i18n::text("status.file_name_required"));
         return Ok(());
     }
-    let destination = queries_directory()?.join(filename);
-    save_query_to_path(app, destination).await?;
+    match app.save_dialog_mode() {
+        Some(SaveDialogMode::S3Export) => {
+            let request = app.s3_export_request(&filename)?;
+            app.last_s3_destination = Some(filename);
+            start_s3_export(app, request, fetcher, notices);
+        }
+        Some(SaveDialogMode::Export) => {
+            let destination = exports_directory()?.join(filename);
+            export_results_to_path(app, destination).await?;
+        }
+        Some(SaveDialogMode::Snapshot) => {
+            let destination = snapshots_directory()?.join(snapshot::snapshot_file_name(&filename));
+            save_snapshot_to_path(app, destination).await?;
+        }
+        _ => {
+            let destination = queries_directory()?.join(filename);
+            save_query_to_path(app, destination).await?;
+        }
+    }
     app.close_save_dialog();
     Ok(())
 }
 
 async fn confirm_open_dialog(app: &mut App) -> Result<(), String> {
     let Some(path) = app.open_dialog_selected_path() else {
-        app.set_status("No matching queries to open");
+        app.set_status(i18n::text("status.no_matching_files"));
         return Ok(());
     };
-    load_query_from_path(app, path).await?;
+    match app.open_dialog_mode() {
+        Some(OpenDialogMode::Snapshot) => load_snapshot_from_path(app, path).await?,
+        _ => load_query_from_path(app, path).await?,
+    }
     app.close_open_dialog();
     Ok(())
 }
 
+async fn save_snapshot_to_path(app: &mut App, destination: PathBuf) -> Result<(), String> {
+    let Some(payload) = app.results_snapshot() else {
+        app.set_status(i18n::text("status.no_results_to_snapshot"));
+        return Ok(());
+    };
+    let snapshots_dir = snapshots_directory()?;
+    let path = destination.clone();
+    task::spawn_blocking(move || snapshot::write_snapshot(&path, &payload))
+        .await
+        .map_err(|err| format!("Snapshot save interrupted: {err}"))??;
+    let display = format_file_display(&destination, &snapshots_dir, SNAPSHOTS_DIR);
+    app.set_status(i18n::fill(
+        "status.snapshot_saved",
+        &[("display", &display)],
+    ));
+    Ok(())
+}
+
+async fn export_results_to_path(app: &mut App, destination: PathBuf) -> Result<(), String> {
+    let Some(format) = ExportFormat::from_path(&destination) else {
+        return Err(format!(
+            "Unsupported export type; use one of {SUPPORTED_EXTENSIONS}"
+        ));
+    };
+    let Some(table) = app.export_table() else {
+        app.set_status(i18n::text("status.no_results_to_export"));
+        return Ok(());
+    };
+    let row_count = table.rows.len();
+    let exports_dir = exports_directory()?;
+    let path = destination.clone();
+    let options = app.config.export.clone();
+    let query = app.last_query.as_ref().map(|params| params.query.clone());
+    task::spawn_blocking(move || {
+        export::write_export(&path, format, &table, &options, query.as_deref())
+    })
+    .await
+    .map_err(|err| format!("Export interrupted: {err}"))??;
+    let display = format_file_display(&destination, &exports_dir, EXPORTS_DIR);
+    app.set_status(i18n::fill(
+        "status.exported",
+        &[
+            ("row_count", &row_count.to_string()),
+            ("format", format.label()),
+            ("display", &display),
+        ],
+    ));
+    Ok(())
+}
+
+/// Picture the rows on screen as a PNG under ./exports and put it on the clipboard.
+async fn save_table_image(app: &mut App) -> Result<(), String> {
+    let Some(table) = app.visible_table() else {
+        app.set_status(i18n::text("status.no_results_to_picture"));
+        return Ok(());
+    };
+    let exports_dir = exports_directory()?;
+    let path = exports_dir.join(format!(
+        "table-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let target = path.clone();
+    let directory = exports_dir.clone();
+    let picture = task::spawn_blocking(move || -> Result<table_image::Picture, String> {
+        let text = export::render_text_table(&table, table_image::MAX_LINE_CHARS);
+        // The header line and the rule under it.
+        let picture = table_image::draw(&text, 2);
+        let png = picture.to_png()?;
+        fs::create_dir_all(&directory)
+            .map_err(|err| format!("Unable to prepare export directory: {err}"))?;
+        fs::write(&target, png).map_err(|err| format!("Failed to write image: {err}"))?;
+        Ok(picture)
+    })
+    .await
+    .map_err(|err| format!("Image export interrupted: {err}"))??;
+    let display = format_file_display(&path, &exports_dir, EXPORTS_DIR);
+    let copied = clipboard::copy_image(
+        picture.width,
+        picture.height,
+        picture.to_rgba(),
+        app.config.clipboard.backend,
+    );
+    match copied {
+        Ok(target) => app.set_status(i18n::fill(
+            "status.picture_copied",
+            &[("display", &display), ("target", target)],
+        )),
+        Err(err) => app.set_status(i18n::fill(
+            "status.picture_saved",
+            &[("display", &display), ("err", &err)],
+        )),
+    }
+    Ok(())
+}
+
+async fn load_snapshot_from_path(app: &mut App, path: PathBuf) -> Result<(), String> {
+    let snapshots_dir = snapshots_directory()?;
+    let target = path.clone();
+    let loaded = task::spawn_blocking(move || snapshot::read_snapshot(&target))
+        .await
+        .map_err(|err| format!("Snapshot load interrupted: {err}"))??;
+    let saved_at = loaded.saved_at.clone();
+    app.load_snapshot(loaded);
+    let display = format_file_display(&path, &snapshots_dir, SNAPSHOTS_DIR);
+    app.set_status(i18n::fill(
+        "status.snapshot_loaded",
+        &[("display", &display), ("saved_at", &saved_at)],
+    ));
+    Ok(())
+}
+
 async fn save_query_to_path(app: &mut App, destination: PathBuf) -> Result<(), String> {
     let contents = app.query_text();
     if contents.trim().is_empty() {
-        app.set_status("Current query is empty; nothing to save");
+        app.set_status(i18n::text("status.query_empty"));
         return Ok(());
     }
     let queries_dir = queries_directory()?;
@@ -506,7 +1528,7 @@ async fn save_query_to_path(app: &mut App, destination: PathBuf) -> Result<(), S
     .map_err(|err| format!("Save operation interrupted: {err}"))??;
     let display = format_query_display(&destination, &queries_dir);
     app.set_saved_query_path(destination);
-    app.set_status(format!("Saved query to {display}"));
+    app.set_status(i18n::fill("status.query_saved", &[("display", &display)]));
     Ok(())
 }
 
@@ -525,20 +1547,47 @@ async fn load_query_from_path(app: &mut App, path: PathBuf) -> Result<(), String
     app.focus = FocusField::Query;
     app.set_saved_query_path(path.clone());
     let display = format_query_display(&path, &queries_dir);
-    app.set_status(format!("Loaded query from {display}"));
+    app.set_status(i18n::fill("status.query_loaded", &[("display", &display)]));
+    Ok(())
+}
+
+async fn show_query_diff(app: &mut App) -> Result<(), String> {
+    let Some(path) = app.saved_query_path.clone() else {
+        app.set_status(i18n::text("status.query_not_from_file"));
+        return Ok(());
+    };
+    let saved = task::spawn_blocking(move || -> Result<String, String> {
+        fs::read_to_string(&path).map_err(|err| format!("Failed to read file: {err}"))
+    })
+    .await
+    .map_err(|err| format!("Load operation interrupted: {err}"))??;
+    app.open_query_diff(saved);
     Ok(())
 }
 
 async fn gather_query_file_entries() -> Result<Vec<QueryFileEntry>, String> {
-    let queries_dir = queries_directory()?;
+    gather_file_entries(queries_directory()?, QUERIES_DIR).await
+}
+
+async fn gather_snapshot_file_entries() -> Result<Vec<QueryFileEntry>, String> {
+    gather_file_entries(snapshots_directory()?, SNAPSHOTS_DIR).await
+}
+
+async fn gather_export_file_entries() -> Result<Vec<QueryFileEntry>, String> {
+    gather_file_entries(exports_directory()?, EXPORTS_DIR).await
+}
+
+async fn gather_file_entries(
+    directory: PathBuf,
+    label: &'static str,
+) -> Result<Vec<QueryFileEntry>, String> {
     let entries = {
-        let queries_dir = queries_dir.clone();
         task::spawn_blocking(move || -> Result<Vec<QueryFileEntry>, String> {
-            fs::create_dir_all(&queries_dir)
-                .map_err(|err| format!("Unable to prepare {QUERIES_DIR} directory: {err}"))?;
+            fs::create_dir_all(&directory)
+                .map_err(|err| format!("Unable to prepare {label} directory: {err}"))?;
             let mut list = Vec::new();
-            for entry in fs::read_dir(&queries_dir)
-                .map_err(|err| format!("Unable to read {QUERIES_DIR}: {err}"))?
+            for entry in
+                fs::read_dir(&directory).map_err(|err| format!("Unable to read {label}: {err}"))?
             {
                 let entry = entry.map_err(|err| format!("Failed to read entry: {err}"))?;
                 let path = entry.path();
@@ -562,39 +1611,524 @@ async fn gather_query_file_entries() -> Result<Vec<QueryFileEntry>, String> {
         })
     }
     .await
-    .map_err(|err| format!("Listing queries interrupted: {err}"))??;
+    .map_err(|err| format!("Listing {label} interrupted: {err}"))??;
     Ok(entries)
 }
 
 fn format_query_display(path: &Path, base: &Path) -> String {
+    format_file_display(path, base, QUERIES_DIR)
+}
+
+fn format_file_display(path: &Path, base: &Path, label: &str) -> String {
     if let Ok(relative) = path.strip_prefix(base) {
-        format!("{QUERIES_DIR}/{}", relative.display())
+        format!("{label}/{}", relative.display())
     } else {
         path.display().to_string()
     }
 }
 
+fn start_s3_export(
+    app: &mut App,
+    request: S3ExportRequest,
+    fetcher: &Arc<dyn LogFetcher>,
+    notices: &mpsc::UnboundedSender<Notice>,
+) {
+    let destination = match request.prefix.as_deref() {
+        Some(prefix) => format!("s3://{}/{prefix}", request.bucket),
+        None => format!("s3://{}", request.bucket),
+    };
+    app.set_status(i18n::fill(
+        "status.s3_export_starting",
+        &[("destination", &destination)],
+    ));
+    let fetcher = Arc::clone(fetcher);
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        let task_id = match fetcher.start_s3_export(&request).await {
+            Ok(task_id) => task_id,
+            Err(err) => {
+                let _ = notices.send(Notice::error(err));
+                return;
+            }
+        };
+        let _ = notices.send(Notice::info(format!(
+            "S3 export task {task_id} started for {destination}"
+        )));
+        let mut last_state = ExportTaskState::Pending;
+        loop {
+            tokio::time::sleep(S3_EXPORT_POLL_INTERVAL).await;
+            let state = match fetcher.s3_export_state(&request, &task_id).await {
+                Ok(state) => state,
+                Err(err) => {
+                    let _ = notices.send(Notice::error(err));
+                    return;
+                }
+            };
+            if state == last_state {
+                continue;
+            }
+            let notice = match &state {
+                ExportTaskState::Pending => Notice::info(format!("S3 export {task_id} pending")),
+                ExportTaskState::Running => Notice::info(format!("S3 export {task_id} running")),
+                ExportTaskState::Completed => {
+                    Notice::info(format!("S3 export {task_id} completed: {destination}"))
+                }
+                ExportTaskState::Failed(message) => {
+                    Notice::error(format!("S3 export {task_id} failed: {message}"))
+                }
+                ExportTaskState::Cancelled => {
+                    Notice::error(format!("S3 export {task_id} was cancelled"))
+                }
+            };
+            let _ = notices.send(notice);
+            if state.is_finished() {
+                return;
+            }
+            last_state = state;
+        }
+    });
+}
+
+fn share_results(app: &mut App, notices: &mpsc::UnboundedSender<Notice>) {
+    let config = app.config.share.clone();
+    if config.resolved_webhook_url().is_none() {
+        app.set_error(i18n::text("error.no_webhook"));
+        return;
+    }
+    let Some(summary) = app.share_summary(config.top_rows) else {
+        app.set_status(i18n::text("status.no_results_to_share"));
+        return;
+    };
+    let text_max_width = app.config.export.text_max_width;
+    app.set_status(i18n::text("status.sharing"));
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        let notice = match share::post_summary(config, text_max_width, summary).await {
+            Ok(()) => Notice::info("Shared results summary to webhook"),
+            Err(err) => Notice::error(err),
+        };
+        let _ = notices.send(notice);
+    });
+}
+
+/// One profile's share of a submitted query.
+struct QueryRun {
+    fetcher: Arc<dyn LogFetcher>,
+    limiter: Arc<QueryLimiter>,
+    simple: Option<SimpleFilter>,
+    ticket: Option<QueueTicket>,
+    guard: GuardConfig,
+}
+
+impl QueryRun {
+    /// Resolve the log groups and run the query, with any warnings about how
+    /// it ran.
+    async fn execute(self, params: QueryParams) -> (QueryOutcome, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut log_groups = match self.fetcher.resolve_log_groups(&params).await {
+            Ok(groups) if groups.is_empty() => {
+                let err = format!("No log groups match {}", params.log_group);
+                return (QueryOutcome::Error(err), warnings);
+            }
+            Ok(groups) => groups,
+            Err(err) => return (QueryOutcome::Error(err), warnings),
+        };
+        if let Some(issue) = log_group_guard_issue(&params, log_groups.len(), &self.guard) {
+            if self.guard.action == GuardAction::Refuse {
+                return (QueryOutcome::Error(format!("Refused: {issue}")), warnings);
+            }
+            warnings.push(format!("Warning: {issue}"));
+        }
+        if log_groups.len() > MAX_QUERY_LOG_GROUPS {
+            log_groups.truncate(MAX_QUERY_LOG_GROUPS);
+            warnings.push(format!(
+                "Warning: {} matches more than {MAX_QUERY_LOG_GROUPS} log groups; only the first {MAX_QUERY_LOG_GROUPS} were queried.",
+                params.log_group
+            ));
+        }
+        let run = async {
+            match self.simple {
+                Some(filter) => {
+                    self.fetcher
+                        .filter_events(params.clone(), log_groups, filter)
+                        .await
+                }
+                None => {
+                    let _permit = match self.ticket {
+                        Some(ticket) => Some(self.limiter.admit(ticket).await),
+                        None => None,
+                    };
+                    self.fetcher.run_query(params.clone(), log_groups).await
+                }
+            }
+        };
+        let (outcome, retention) =
+            tokio::join!(run, self.fetcher.log_group_retention_days(&params));
+        // A failed lookup only costs the warning, never the results.
+        warnings.extend(
+            retention
+                .ok()
+                .flatten()
+                .and_then(|days| retention_warning(&params, days)),
+        );
+        (outcome, warnings)
+    }
+}
+
+/// A run's account, when it ran for a profile group, and its outcome and warnings.
+type AccountRun = (Option<String>, (QueryOutcome, Vec<String>));
+
+/// Rows of every account's run with an `@account` column, in timestamp order
+/// when the query sorts by it. Failed accounts become warnings unless they
+/// all failed.
+fn merge_account_runs(
+    results: Vec<AccountRun>,
+    query: &str,
+    warnings: &mut Vec<String>,
+) -> QueryOutcome {
+    let runs = results.len();
+    let mut records = Vec::new();
+    let mut failures = Vec::new();
+    for (account, (outcome, run_warnings)) in results {
+        let account = account.unwrap_or_default();
+        warnings.extend(
+            run_warnings
+                .iter()
+                .map(|warning| format!("{account}: {warning}")),
+        );
+        match outcome {
+            QueryOutcome::Success(rows) => {
+                records.extend(rows.into_iter().map(|mut row| {
+                    row.push(LogField {
+                        name: Some(ACCOUNT_FIELD.to_string()),
+                        value: account.clone(),
+                    });
+                    row
+                }));
+            }
+            QueryOutcome::Error(err) => failures.push(format!("{account}: {err}")),
+        }
+    }
+    if failures.len() == runs {
+        return QueryOutcome::Error(failures.join("; "));
+    }
+    if !failures.is_empty() {
+        warnings.push(format!("Warning: failed in {}", failures.join("; ")));
+    }
+    if let Some(descending) = timestamp_sort(query) {
+        let timestamp = |record: &LogRecord| {
+            record
+                .iter()
+                .find(|field| field.name.as_deref() == Some("@timestamp"))
+                .map(|field| field.value.clone())
+        };
+        records.sort_by_cached_key(timestamp);
+        if descending {
+            records.reverse();
+        }
+    }
+    QueryOutcome::Success(records)
+}
+
+/// Whether the query ends up sorted by `@timestamp`, and if so descending.
+fn timestamp_sort(query: &str) -> Option<bool> {
+    let order = query
+        .split('|')
+        .filter_map(|stage| stage.trim().strip_prefix("sort "))
+        .next_back()?
+        .trim();
+    let rest = order.strip_prefix("@timestamp")?;
+    Some(rest.trim_start().starts_with("desc"))
+}
+
+/// Switch to the selected profile's region and check its credentials now
+/// instead of on the next query.
+fn apply_profile(
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    notices: &mpsc::UnboundedSender<Notice>,
+) {
+    // Applying re-reads the profile, e.g. after `aws sso login` or an edit
+    // to ~/.aws/config.
+    if let Some(group) = app.selected_profile_group() {
+        let (name, profiles) = (group.name.clone(), group.profiles.clone());
+        for profile in &profiles {
+            fetcher.forget_profile(profile);
+        }
+        check_group_credentials(app, fetcher, notices, name, profiles);
+        return;
+    }
+    let Some(profile) = app.selected_profile_name().map(str::to_string) else {
+        app.set_status(i18n::text("status.no_profile_selected"));
+        return;
+    };
+    fetcher.forget_profile(&profile);
+    let settings = aws_profiles::profile_settings(&profile);
+    let Some(region) = app.apply_profile_region(&profile, settings.region) else {
+        return;
+    };
+    let sign_in = settings
+        .sso
+        .as_deref()
+        .map(|sso| format!(" through SSO {sso}"))
+        .unwrap_or_default();
+    app.set_status(i18n::fill(
+        "status.checking_credentials",
+        &[
+            ("profile", &profile),
+            ("region", &region),
+            ("sign_in", &sign_in),
+        ],
+    ));
+    let fetcher = Arc::clone(fetcher);
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        let notice = match fetcher.check_credentials(&region, Some(&profile)).await {
+            Ok(()) => Notice::info(format!("Profile {profile} is ready in {region}")),
+            Err(err) if settings.sso.is_some() => Notice::error(format!(
+                "Profile {profile} has no usable credentials: {err}; try `aws sso login --profile {profile}`"
+            )),
+            Err(err) => Notice::error(format!(
+                "Profile {profile} has no usable credentials: {err}"
+            )),
+        };
+        let _ = notices.send(notice);
+    });
+}
+
+/// A group's profiles each keep their own region setting, but the group runs
+/// in the Region field, so that is where they are checked.
+fn check_group_credentials(
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    notices: &mpsc::UnboundedSender<Notice>,
+    name: String,
+    profiles: Vec<String>,
+) {
+    let region = app.aws_region_input.value().trim().to_string();
+    if region.is_empty() {
+        app.set_error(i18n::fill(
+            "error.group_region_required",
+            &[("name", &name)],
+        ));
+        return;
+    }
+    app.set_status(i18n::fill(
+        "status.checking_group_credentials",
+        &[
+            ("count", &profiles.len().to_string()),
+            ("name", &name),
+            ("region", &region),
+        ],
+    ));
+    let fetcher = Arc::clone(fetcher);
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        let checks = profiles
+            .iter()
+            .map(|profile| fetcher.check_credentials(&region, Some(profile)));
+        let failures: Vec<String> = join_all(checks)
+            .await
+            .into_iter()
+            .zip(&profiles)
+            .filter_map(|(check, profile)| check.err().map(|err| format!("{profile}: {err}")))
+            .collect();
+        let notice = if failures.is_empty() {
+            Notice::info(format!(
+                "All {} profiles of {name} are ready in {region}",
+                profiles.len()
+            ))
+        } else {
+            Notice::error(format!(
+                "{name} has profiles without usable credentials: {}",
+                failures.join("; ")
+            ))
+        };
+        let _ = notices.send(notice);
+    });
+}
+
+fn push_metrics(app: &mut App, notices: &mpsc::UnboundedSender<Notice>) {
+    let config = app.config.metrics.clone();
+    if config.resolved_pushgateway_url().is_none() {
+        app.set_error(i18n::text("error.no_pushgateway"));
+        return;
+    }
+    let Some(table) = app.export_table() else {
+        app.set_status(i18n::text("status.no_results_to_push"));
+        return;
+    };
+    app.set_status(i18n::text("status.pushing_metrics"));
+    let query = app.last_query.as_ref().map(|params| params.query.clone());
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        let notice = match metrics::push(config, table, query).await {
+            Ok(count) => Notice::info(format!("Pushed {count} series to the Pushgateway")),
+            Err(err) => Notice::error(err),
+        };
+        let _ = notices.send(notice);
+    });
+}
+
+fn index_results(app: &mut App, notices: &mpsc::UnboundedSender<Notice>) {
+    let config = app.config.opensearch.clone();
+    if config
+        .url
+        .as_deref()
+        .is_none_or(|url| url.trim().is_empty())
+    {
+        app.set_error(i18n::text("error.no_opensearch"));
+        return;
+    }
+    let Some(table) = app.export_table() else {
+        app.set_status(i18n::text("status.no_results_to_index"));
+        return;
+    };
+    let source = app.index_source();
+    let index = config.index.clone();
+    app.set_status(i18n::fill(
+        "status.indexing",
+        &[("count", &table.rows.len().to_string()), ("index", &index)],
+    ));
+    let notices = notices.clone();
+    tokio::spawn(async move {
+        let notice = match opensearch::bulk_index(config, table, source).await {
+            Ok(count) => Notice::info(format!("Indexed {count} rows into {index}")),
+            Err(err) => Notice::error(err),
+        };
+        let _ = notices.send(notice);
+    });
+}
+
 pub(crate) fn start_query_submission(
     app: &mut App,
     fetcher: &Arc<dyn LogFetcher>,
-    tx: &mpsc::UnboundedSender<QueryOutcome>,
+    tx: &mpsc::UnboundedSender<QueryReport>,
+) {
+    submit_query(app, fetcher, tx, true);
+}
+
+/// Run the query even when the result cache holds a recent identical run.
+pub(crate) fn refresh_query_submission(
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    tx: &mpsc::UnboundedSender<QueryReport>,
+) {
+    submit_query(app, fetcher, tx, false);
+}
+
+fn submit_query(
+    app: &mut App,
+    fetcher: &Arc<dyn LogFetcher>,
+    tx: &mpsc::UnboundedSender<QueryReport>,
+    use_cache: bool,
 ) {
     if app.submitting {
-        app.set_status("Query already in progress");
+        app.set_status(i18n::text("status.query_in_progress"));
         return;
     }
 
     match app.prepare_submission() {
         Ok(params) => {
-            app.submitting = true;
-            app.set_status("Running query...");
+            if !app.check_production_run(PendingRun::Query) {
+                return;
+            }
+            let simple = match app.query_engine {
+                QueryEngine::Insights => None,
+                QueryEngine::Auto | QueryEngine::FilterEvents => simple_filter(&params.query),
+            };
+            if app.query_engine == QueryEngine::FilterEvents && simple.is_none() {
+                app.set_error(i18n::text("error.filter_events_unsupported"));
+                return;
+            }
+            let guard = app.config.guard.clone();
+            let mut warnings = Vec::new();
+            if let Some(issue) = range_guard_issue(&params, &guard) {
+                if guard.action == GuardAction::Refuse {
+                    app.set_error(i18n::fill("error.refused", &[("issue", &issue)]));
+                    return;
+                }
+                warnings.push(format!("Warning: {issue}"));
+            }
+            // Results need the normal layout.
+            app.query_zoomed = false;
+            if use_cache && app.apply_cached_results(&params) {
+                return;
+            }
+            app.last_query = Some(params.clone());
+            app.begin_submission();
+            if simple.is_some() {
+                app.set_status(i18n::text("status.running_filter_events"));
+            } else {
+                app.set_status(i18n::text("status.running"));
+            }
             app.clear_results();
+            // One run per profile of a selected group, each tagged with its account.
+            let runs: Vec<(QueryParams, Option<String>)> = match app.selected_profile_group() {
+                Some(group) => group
+                    .profiles
+                    .iter()
+                    .map(|profile| {
+                        let params = QueryParams {
+                            profile: Some(profile.clone()),
+                            ..params.clone()
+                        };
+                        (params, Some(app.profile_account(profile)))
+                    })
+                    .collect(),
+                None => vec![(params.clone(), None)],
+            };
+            // FilterLogEvents does not count against the Insights concurrency limit.
+            let tickets: Vec<Option<QueueTicket>> = runs
+                .iter()
+                .map(|_| simple.is_none().then(|| app.query_limiter.ticket()))
+                .collect();
+            app.query_tickets = tickets.iter().flatten().map(QueueTicket::place).collect();
+            let limiter = Arc::clone(&app.query_limiter);
+            let enricher = Arc::clone(&app.stream_enricher);
             let fetcher = Arc::clone(fetcher);
             let tx = tx.clone();
-            tokio::spawn(async move {
-                let outcome = fetcher.run_query(params).await;
-                let _ = tx.send(outcome);
-            });
+            let watch = CallWatch::default();
+            app.query_watch = watch.clone();
+            let task = tokio::spawn(watch.scope(async move {
+                if let Err(err) = fetcher.check_connectivity(&params).await {
+                    let _ = tx.send(QueryReport {
+                        outcome: QueryOutcome::Error(err),
+                        warning: None,
+                    });
+                    return;
+                }
+                let runs = runs
+                    .into_iter()
+                    .zip(tickets)
+                    .map(|((params, account), ticket)| {
+                        let run = QueryRun {
+                            fetcher: Arc::clone(&fetcher),
+                            limiter: Arc::clone(&limiter),
+                            simple: simple.clone(),
+                            ticket,
+                            guard: guard.clone(),
+                        };
+                        async move { (account, run.execute(params).await) }
+                    });
+                let mut results = join_all(runs).await;
+                let mut outcome = match results.pop() {
+                    Some((None, (outcome, run_warnings))) if results.is_empty() => {
+                        warnings.extend(run_warnings);
+                        outcome
+                    }
+                    last => {
+                        results.extend(last);
+                        merge_account_runs(results, &params.query, &mut warnings)
+                    }
+                };
+                if let QueryOutcome::Success(records) = &mut outcome {
+                    warnings.extend(enricher.enrich(records).await);
+                }
+                let warning = (!warnings.is_empty()).then(|| warnings.join(" "));
+                let _ = tx.send(QueryReport { outcome, warning });
+            }));
+            app.query_task = Some(task.abort_handle());
         }
         Err(err) => {
             app.set_error(err);
@@ -602,6 +2136,67 @@ pub(crate) fn start_query_submission(
     }
 }
 
+fn range_guard_issue(params: &QueryParams, guard: &GuardConfig) -> Option<String> {
+    let days = (params.end_millis - params.start_millis) as f64 / 86_400_000.0;
+    (guard.max_days > 0 && days > guard.max_days as f64).then(|| {
+        format!(
+            "the time range spans {days:.1} days, more than the guard's {} (see [guard] in the config).",
+            guard.max_days
+        )
+    })
+}
+
+fn log_group_guard_issue(
+    params: &QueryParams,
+    group_count: usize,
+    guard: &GuardConfig,
+) -> Option<String> {
+    let limit = guard.max_log_groups_without_limit;
+    (limit > 0 && group_count > limit && !has_limit_clause(&params.query)).then(|| {
+        format!(
+            "the query has no `limit` and touches {group_count} log groups, more than the guard's {limit} (see [guard] in the config)."
+        )
+    })
+}
+
+/// Whether any pipeline stage is a `limit` command.
+fn has_limit_clause(query: &str) -> bool {
+    query.split(['|', '\n']).any(|stage| {
+        let mut words = stage.split_whitespace();
+        words
+            .next()
+            .is_some_and(|command| command.eq_ignore_ascii_case("limit"))
+    })
+}
+
+/// Explain when the window starts before the oldest event the log group still keeps.
+fn retention_warning(params: &QueryParams, retention_days: i32) -> Option<String> {
+    let now = chrono::Utc::now();
+    let oldest = now - chrono::Duration::days(i64::from(retention_days));
+    if params.start_millis >= oldest.timestamp_millis() {
+        return None;
+    }
+    let plural = if retention_days == 1 { "day" } else { "days" };
+    Some(format!(
+        "Warning: {} keeps {retention_days} {plural} of data, so nothing before {} can match.",
+        params.log_group,
+        format_local_timestamp(oldest)
+    ))
+}
+
+pub(crate) fn is_suspend_key(key: &KeyEvent) -> bool {
+    key.kind == KeyEventKind::Press
+        && key.modifiers == KeyModifiers::CONTROL
+        && matches!(key.code, KeyCode::Char('z') | KeyCode::Char('Z'))
+}
+
+pub(crate) fn is_run_key(key: &KeyEvent, app: &App) -> bool {
+    if key.kind != KeyEventKind::Press {
+        return false;
+    }
+    is_ctrl_enter(key) || app.config.keys.run.is_some_and(|chord| chord.matches(key))
+}
+
 pub(crate) fn is_ctrl_enter(key: &KeyEvent) -> bool {
     if key.kind != KeyEventKind::Press {
         return false;
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+
+use crate::focus::FocusField;
+
+/// An input block that can be hidden to give its space to the results.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputPane {
+    Region,
+    Profile,
+    TimeRange,
+    LogGroup,
+    Query,
+    Filter,
+}
+
+impl InputPane {
+    pub const ALL: [InputPane; 6] = [
+        Self::Region,
+        Self::Profile,
+        Self::TimeRange,
+        Self::LogGroup,
+        Self::Query,
+        Self::Filter,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Region => "AWS region",
+            Self::Profile => "AWS profile",
+            Self::TimeRange => "Time range (mode, relative window, From/To)",
+            Self::LogGroup => "Log group",
+            Self::Query => "Logs Insights query",
+            Self::Filter => "Filter",
+        }
+    }
+
+    /// The pane a focus field is drawn in, if it can be hidden.
+    pub fn of(field: FocusField) -> Option<Self> {
+        match field {
+            FocusField::AwsRegion => Some(Self::Region),
+            FocusField::AwsProfile => Some(Self::Profile),
+            FocusField::TimeMode
+            | FocusField::RelativeRange
+            | FocusField::From
+            | FocusField::To => Some(Self::TimeRange),
+            FocusField::LogGroup => Some(Self::LogGroup),
+            FocusField::Query => Some(Self::Query),
+            FocusField::Filter => Some(Self::Filter),
+            FocusField::Results | FocusField::Scratchpad => None,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+use crossterm::event::KeyEvent;
+
+/// Keys recorded with `q` in the results and replayed with `@`, vim style.
+#[derive(Default)]
+pub struct KeyMacro {
+    /// Keys pressed so far while recording.
+    recording: Option<Vec<KeyEvent>>,
+    /// The last finished recording.
+    recorded: Vec<KeyEvent>,
+    /// Keys still to replay; the event loop feeds them in between queries.
+    pending: VecDeque<KeyEvent>,
+    /// Set from `replay` until the loop finds nothing left, so an `@` inside
+    /// the macro cannot restart it.
+    replaying: bool,
+}
+
+impl KeyMacro {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replaying
+    }
+
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some(keys) = self.recording.as_mut() {
+            keys.push(key);
+        }
+    }
+
+    /// Drop the last recorded key, one that only tried to replay the macro.
+    pub fn unrecord(&mut self) {
+        if let Some(keys) = self.recording.as_mut() {
+            keys.pop();
+        }
+    }
+
+    pub fn start(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// Keep what was recorded, minus the key that stopped it; returns its length.
+    pub fn finish(&mut self) -> usize {
+        let mut keys = self.recording.take().unwrap_or_default();
+        keys.pop();
+        self.recorded = keys;
+        self.recorded.len()
+    }
+
+    /// Queue the recording for replay; returns how many keys it holds.
+    pub fn replay(&mut self) -> usize {
+        self.pending = self.recorded.iter().copied().collect();
+        self.replaying = !self.pending.is_empty();
+        self.pending.len()
+    }
+
+    pub fn peek(&mut self) -> Option<KeyEvent> {
+        let key = self.pending.front().copied();
+        self.replaying &= key.is_some();
+        key
+    }
+
+    pub fn advance(&mut self) {
+        self.pending.pop_front();
+    }
+
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+        self.replaying = false;
+    }
+}
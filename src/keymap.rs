@@ -0,0 +1,114 @@
+use std::env;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// A single key plus modifiers, written in config as e.g. `"ctrl+enter"` or `"alt+r"`.
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut code = None;
+        for part in spec.split('+').map(str::trim) {
+            let lower = part.to_ascii_lowercase();
+            match lower.as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "option" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                "super" | "cmd" | "command" => modifiers |= KeyModifiers::SUPER,
+                _ if code.is_some() => return Err(format!("Key chord `{spec}` names two keys")),
+                _ => {
+                    code = Some(
+                        parse_key(&lower)
+                            .ok_or_else(|| format!("Unknown key `{part}` in `{spec}`"))?,
+                    )
+                }
+            }
+        }
+        let code = code.ok_or_else(|| format!("Key chord `{spec}` has no key"))?;
+        Ok(Self { code, modifiers })
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let code = match key.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            other => other,
+        };
+        // Terminals disagree on whether Shift is reported alongside an uppercase letter.
+        let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER;
+        let shift_matches = !self.modifiers.contains(KeyModifiers::SHIFT)
+            || key.modifiers.contains(KeyModifiers::SHIFT)
+            || matches!(key.code, KeyCode::Char(c) if c.is_ascii_uppercase());
+        code == self.code && key.modifiers & relevant == self.modifiers & relevant && shift_matches
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, label) in [
+            (KeyModifiers::CONTROL, "Ctrl"),
+            (KeyModifiers::SUPER, "Cmd"),
+            (KeyModifiers::ALT, "Alt"),
+            (KeyModifiers::SHIFT, "Shift"),
+        ] {
+            if self.modifiers.contains(flag) {
+                write!(f, "{label}+")?;
+            }
+        }
+        match self.code {
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    match name {
+        "enter" | "return" => Some(KeyCode::Enter),
+        "space" => Some(KeyCode::Char(' ')),
+        _ => {
+            if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                return (1..=12).contains(&number).then_some(KeyCode::F(number));
+            }
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_graphic() => Some(KeyCode::Char(c)),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The chord advertised as "Run" in hints: the configured one, otherwise Ctrl+Enter
+/// unless tmux is likely to swallow it without the kitty keyboard protocol.
+pub fn primary_run_chord(configured: Option<KeyChord>, keyboard_enhanced: bool) -> KeyChord {
+    configured.unwrap_or_else(|| {
+        let code = if keyboard_enhanced || env::var_os("TMUX").is_none() {
+            KeyCode::Enter
+        } else {
+            KeyCode::Char('r')
+        };
+        KeyChord {
+            code,
+            modifiers: KeyModifiers::CONTROL,
+        }
+    })
+}
@@ -0,0 +1,519 @@
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::app::{App, SaveDialogMode, StatusKind, REFRESH_KEY_HINT};
+use crate::clusters::ClusterView;
+use crate::focus::FocusField;
+use crate::i18n;
+use crate::value_picker::ValuePicker;
+use crate::variables;
+use crate::widgets::date_picker::PickerPart;
+use crate::widgets::tab_bar::TabItem;
+
+/// Screen-reader friendly rendering: plain lines, no borders or side-by-side panes,
+/// with the cursor parked on the line describing the focused control.
+pub fn draw_linear_ui(frame: &mut Frame, app: &App, tabs: &[TabItem], active_tab: usize) {
+    let area = frame.size();
+    let (mut lines, mut cursor_line) = if let Some(prompt) = app.quit_prompt.as_deref() {
+        (
+            vec![
+                Line::from(prompt.to_string()),
+                Line::from("Press y or Enter to quit, n or Esc to stay."),
+            ],
+            0,
+        )
+    } else if let Some(prompt) = app.run_prompt.as_deref() {
+        (
+            vec![
+                Line::from(prompt.to_string()),
+                Line::from("Press y or Enter to run, n or Esc to cancel."),
+            ],
+            0,
+        )
+    } else if let Some(input) = app.rename_input.as_ref() {
+        (
+            vec![
+                Line::from(format!("Rename workspace: {}", input.value())),
+                Line::from(
+                    "Press Enter to save or Esc to cancel; leave empty for an automatic title.",
+                ),
+            ],
+            0,
+        )
+    } else if let Some(input) = app.value_field_input.as_ref() {
+        (
+            vec![
+                Line::from(format!("Field to pick values from: {}", input.value())),
+                Line::from("Press Enter to sample its values or Esc to cancel."),
+            ],
+            0,
+        )
+    } else if let Some(input) = app.variables_input.as_ref() {
+        (
+            vec![
+                Line::from(format!("Workspace variables: {}", input.value())),
+                Line::from("Write name = value pairs separated by semicolons; the query uses them as {{name}}."),
+                Line::from("Press Enter to save or Esc to cancel."),
+            ],
+            0,
+        )
+    } else if let Some(input) = app.computed_input.as_ref() {
+        (
+            vec![
+                Line::from(format!("Computed column: {}", input.value())),
+                Line::from(
+                    "Write Name = expression, e.g. Seconds = col(\"Elapsed\") / 1000; Name = alone removes it.",
+                ),
+                Line::from("Press Enter to add it or Esc to cancel."),
+            ],
+            0,
+        )
+    } else if let Some(picker) = app.value_picker.as_ref() {
+        value_picker_lines(picker)
+    } else if let Some(view) = app.clusters.as_ref() {
+        cluster_lines(view)
+    } else if let Some((_, input)) = app.note_input.as_ref() {
+        (
+            vec![
+                Line::from(format!("Row note: {}", input.value())),
+                Line::from("Press Enter to save or Esc to cancel; leave empty to remove the note."),
+            ],
+            0,
+        )
+    } else if app.help_open {
+        (help_lines(), 0)
+    } else if let Some(lines) = overlay_lines(app) {
+        (lines, 0)
+    } else {
+        main_lines(app)
+    };
+    if let Some(tab) = tabs.get(active_tab).filter(|_| tabs.len() > 1) {
+        lines.insert(
+            0,
+            Line::from(format!(
+                "Workspace {} of {}: {} ({}). Ctrl+PgDn/Ctrl+PgUp to switch.",
+                active_tab + 1,
+                tabs.len(),
+                tab.title,
+                tab.state.label()
+            )),
+        );
+        cursor_line += 1;
+    }
+
+    let cursor_text_len = lines
+        .get(cursor_line)
+        .map(|line| line.width())
+        .unwrap_or_default();
+    let widget = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(widget, area);
+    place_cursor(frame, area, cursor_line, cursor_text_len);
+}
+
+fn place_cursor(frame: &mut Frame, area: Rect, line: usize, text_len: usize) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    let y = area.y + (line as u16).min(area.height - 1);
+    let x = area.x + (text_len as u16).min(area.width - 1);
+    frame.set_cursor(x, y);
+}
+
+fn help_lines() -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(i18n::text("hint.help_close"))];
+    lines.extend(
+        i18n::help_text()
+            .lines()
+            .map(|line| line.trim_start_matches('#').trim_start())
+            .map(Line::from),
+    );
+    lines
+}
+
+fn overlay_lines(app: &App) -> Option<Vec<Line<'static>>> {
+    if let Some(state) = app.column_modal.as_ref() {
+        let selected = state.selected();
+        let header = app
+            .results
+            .headers
+            .get(selected)
+            .cloned()
+            .unwrap_or_default();
+        let shown = if state.selections().get(selected).copied().unwrap_or(false) {
+            "shown"
+        } else {
+            "hidden"
+        };
+        return Some(vec![
+            Line::from(format!(
+                "Select columns. Column {} of {}: {header}, {shown}.",
+                selected + 1,
+                state.selections().len()
+            )),
+            Line::from("Up/Down move, Space toggles, Enter applies, Esc cancels."),
+        ]);
+    }
+    if let Some((field, state)) = app.date_picker.as_ref() {
+        let editing = match state.part() {
+            PickerPart::Day => "day; arrows move by day or week, PageUp/PageDown by month",
+            PickerPart::Hour => "hour; Up/Down change it",
+            PickerPart::Minute => "minute; Up/Down change it",
+            PickerPart::Second => "second; Up/Down change it",
+        };
+        return Some(vec![
+            Line::from(format!(
+                "Pick {}: {}",
+                field.label(),
+                state.value().format("%A %Y-%m-%d %H:%M:%S")
+            )),
+            Line::from(format!("Editing {editing}.")),
+            Line::from("Tab switches part, Enter sets the value, Esc cancels."),
+        ]);
+    }
+    if let Some(state) = app.palette.as_ref() {
+        let selected = state
+            .selected_action()
+            .map(|action| match action.shortcut() {
+                Some(shortcut) => format!("{}, shortcut {shortcut}", action.label()),
+                None => action.label().to_string(),
+            })
+            .unwrap_or_else(|| "no match".to_string());
+        return Some(vec![
+            Line::from(format!(
+                "Command palette. Search: {}",
+                state.filter_input.value()
+            )),
+            Line::from(format!(
+                "Selected: {selected} ({} matches)",
+                state.matches.len()
+            )),
+            Line::from("Up/Down select, Enter runs, Esc cancels."),
+        ]);
+    }
+    if let Some(state) = app.open_dialog.as_ref() {
+        let selected = state
+            .selected_entry()
+            .map(|entry| entry.display.clone())
+            .unwrap_or_else(|| "no match".to_string());
+        return Some(vec![
+            Line::from(format!("Open file. Filter: {}", state.filter_input.value())),
+            Line::from(format!(
+                "Selected: {selected} ({} matches)",
+                state.filtered_indices.len()
+            )),
+            Line::from("Up/Down select, Enter opens, Esc cancels."),
+        ]);
+    }
+    if let Some(state) = app.save_dialog.as_ref() {
+        let title = match state.mode {
+            SaveDialogMode::Save => "Save query",
+            SaveDialogMode::Snapshot => "Save results snapshot",
+            SaveDialogMode::Export => "Export filtered results",
+            SaveDialogMode::S3Export => "Export log group to S3",
+        };
+        return Some(vec![
+            Line::from(format!("{title}. Name: {}", state.input.value())),
+            Line::from("Enter confirms, Esc cancels."),
+        ]);
+    }
+    if let Some((_, stats)) = app.column_stats.as_ref() {
+        let mut lines = vec![Line::from(format!(
+            "Stats for {}. Left/Right change column, Esc closes.",
+            stats.header
+        ))];
+        lines.extend(stats.lines().into_iter().map(Line::from));
+        return Some(lines);
+    }
+    if app.modal_open {
+        let details = app.selected_row_data()?;
+        let mut lines = vec![Line::from(
+            "Row detail. Up/Down select a field, y copies it, o opens its link, C copies all, v toggles raw values, Esc closes.",
+        )];
+        if let Some(note) = app.selected_row_note() {
+            lines.push(Line::from(format!("Note: {note}")));
+        }
+        for (field_idx, (header, value)) in details.into_iter().enumerate() {
+            let marker = if field_idx == app.modal_field {
+                "> "
+            } else {
+                ""
+            };
+            let rendered = app.modal_value_lines(&header, &value);
+            if rendered.is_empty() {
+                lines.push(Line::from(format!("{marker}{header}: empty")));
+            } else {
+                lines.push(Line::from(format!(
+                    "{marker}{header}: {}",
+                    rendered.join(" ")
+                )));
+            }
+        }
+        return Some(lines);
+    }
+    None
+}
+
+fn cluster_lines(view: &ClusterView) -> (Vec<Line<'static>>, usize) {
+    let mut lines = vec![Line::from(format!(
+        "{} clusters of {}. Up/Down move, Enter goes to the example row, Esc closes.",
+        view.clusters.len(),
+        view.header
+    ))];
+    for cluster in &view.clusters {
+        lines.push(Line::from(format!(
+            "{} events: {}. Example: {}",
+            cluster.count, cluster.fingerprint, cluster.example
+        )));
+    }
+    (lines, view.selected + 1)
+}
+
+fn value_picker_lines(picker: &ValuePicker) -> (Vec<Line<'static>>, usize) {
+    let mut lines = vec![Line::from(format!(
+        "Values of {}. Up/Down move, Space ticks, Enter inserts a filter, Esc cancels.",
+        picker.field
+    ))];
+    let Some(values) = picker.values.as_ref() else {
+        lines.push(Line::from("Sampling values..."));
+        return (lines, 0);
+    };
+    for (idx, (value, count)) in values.iter().enumerate() {
+        let state = if picker.is_chosen(idx) {
+            "ticked"
+        } else {
+            "not ticked"
+        };
+        lines.push(Line::from(format!("{value}: {count} events, {state}")));
+    }
+    (lines, picker.selected + 1)
+}
+
+fn main_lines(app: &App) -> (Vec<Line<'static>>, usize) {
+    let mut lines = Vec::new();
+    if let Some(banner) = app.production_banner() {
+        lines.push(Line::from(format!("Warning: {banner}")));
+    }
+    let status_prefix = match app.status_kind {
+        StatusKind::Error => "Error",
+        StatusKind::Info => "Status",
+    };
+    lines.push(Line::from(format!("{status_prefix}: {}", app.status)));
+
+    let cursor_line = lines.len();
+    lines.push(Line::from(format!(
+        "Focus: {}: {}",
+        app.focus.label(),
+        focus_value(app)
+    )));
+
+    let range = if app.relative_mode {
+        format!("last {}", app.current_relative_option().label)
+    } else {
+        format!("{} to {}", app.from_input.value(), app.to_input.value())
+    };
+    lines.push(Line::from(format!(
+        "Region {}, profile {}, range {range}, log group {}.",
+        app.aws_region_input.value(),
+        app.profile_label(),
+        app.log_group_input.value()
+    )));
+    if let Some(preset) = app.current_preset() {
+        let kind = if preset.is_production() {
+            ", production"
+        } else {
+            ""
+        };
+        lines.push(Line::from(format!("Environment: {}{kind}", preset.name)));
+    }
+    lines.push(Line::from(format!(
+        "Query: {}",
+        app.query_area.lines().join(" ")
+    )));
+    if let Some(hint) = app
+        .query_function_hint()
+        .filter(|_| app.focus == FocusField::Query)
+    {
+        lines.push(Line::from(format!("Reference: {hint}.")));
+    }
+    if variables::has_placeholders(&app.query_text()) {
+        lines.push(Line::from(format!(
+            "Variables: {}.",
+            app.variables_summary()
+        )));
+    }
+    for hint in app.query_lint_hints() {
+        lines.push(Line::from(format!("Query hint: {hint}.")));
+    }
+    if app.filter_active {
+        lines.push(Line::from(format!("Filter: {}", app.filter_input.value())));
+        if let Some(cursor) = app.chip_cursor {
+            let chips = app.filter_chips();
+            if let Some((token, enabled)) = chips.get(cursor) {
+                lines.push(Line::from(format!(
+                    "Filter term {} of {}: {token}, {}. Left/Right move, Space switches it on or off, - flips include/exclude, Delete removes, Esc returns.",
+                    cursor + 1,
+                    chips.len(),
+                    if *enabled { "on" } else { "off" }
+                )));
+            }
+        }
+        if let Some((matches, total)) = app.filter_preview {
+            lines.push(Line::from(format!(
+                "Filter would match {matches} of {total} rows."
+            )));
+        }
+        if app.filter_job.is_some() {
+            lines.push(Line::from("Filtering…"));
+        }
+    }
+    if let Some(pane) = &app.cloudtrail {
+        match &pane.events {
+            None => lines.push(Line::from(format!(
+                "CloudTrail: querying {}…",
+                pane.log_group
+            ))),
+            Some(Err(err)) => lines.push(Line::from(format!("CloudTrail failed: {err}"))),
+            Some(Ok(events)) => {
+                lines.push(Line::from(format!(
+                    "CloudTrail: {} changes in {}.",
+                    events.len(),
+                    pane.log_group
+                )));
+                for event in events {
+                    lines.push(Line::from(format!("{} {}", event.time, event.summary)));
+                }
+            }
+        }
+    }
+    if let Some(pane) = &app.explain {
+        match &pane.reply {
+            None => lines.push(Line::from("Explanation: waiting for a reply…")),
+            Some(Err(err)) => lines.push(Line::from(format!("Explanation failed: {err}"))),
+            Some(Ok(reply)) => {
+                lines.push(Line::from("Explanation:"));
+                lines.extend(reply.lines().map(|line| Line::from(line.to_string())));
+            }
+        }
+    }
+    if app.scratchpad_open {
+        lines.push(Line::from(format!(
+            "Scratchpad: {}",
+            app.scratchpad.lines().join(" ")
+        )));
+    }
+
+    let total = app.results.rows.len();
+    if let Some(progress) = app.submission_progress() {
+        lines.push(Line::from(format!("Results: query running, {progress}.")));
+    } else if total == 0 {
+        let message = if app.results_initialized {
+            "Results: query returned no rows."
+        } else {
+            "Results: none yet."
+        };
+        lines.push(Line::from(message));
+    } else {
+        lines.push(Line::from(format!(
+            "Results: {} of {total} rows match the filter.",
+            app.filtered_indices.len()
+        )));
+        if app.baseline.is_some() {
+            lines.push(Line::from(format!(
+                "{} rows have message patterns the baseline run did not.",
+                app.new_pattern_rows.len()
+            )));
+        }
+        if let Some(every) = app.sample_every {
+            lines.push(Line::from(format!(
+                "Sampling: showing 1 in {every} matching rows; press d for the full view."
+            )));
+        }
+    }
+
+    if let Some(last_run) = app.last_run.as_ref() {
+        lines.push(Line::from(last_run.summary()));
+    }
+    if let Some(badge) = app.cached_results_badge() {
+        lines.push(Line::from(format!(
+            "Results {badge} ({REFRESH_KEY_HINT} to refresh)."
+        )));
+    }
+    if app.results_stale() {
+        lines.push(Line::from(format!(
+            "Inputs changed since this run; results are stale ({} to re-run).",
+            app.run_key_hint()
+        )));
+    }
+
+    if app.results_navigation {
+        if let (Some(position), Some(details)) =
+            (app.selected_filtered_index, app.selected_row_data())
+        {
+            lines.push(Line::from(format!(
+                "Row {} of {}:",
+                position + 1,
+                app.filtered_indices.len()
+            )));
+            for marker in app.markers_before(position) {
+                lines.push(Line::from(format!(
+                    "Marker just before this row: {}",
+                    marker.name
+                )));
+            }
+            if let Some(note) = app.selected_row_note() {
+                lines.push(Line::from(format!("Note: {note}")));
+            }
+            if app.selected_row_is_new_pattern() {
+                lines.push(Line::from("New message pattern since the baseline."));
+            }
+            for (header, value) in details {
+                let first_line = value.lines().next().unwrap_or_default().to_string();
+                lines.push(Line::from(format!("{header}: {first_line}")));
+            }
+        }
+    }
+
+    lines.push(Line::from(format!(
+        "Tab next field, {} run, Enter opens row detail, Ctrl+H help, {} quit.",
+        app.run_key_hint(),
+        app.quit_key_hint()
+    )));
+    (lines, cursor_line)
+}
+
+fn focus_value(app: &App) -> String {
+    match app.focus {
+        FocusField::AwsRegion => app.aws_region_input.value().to_string(),
+        FocusField::AwsProfile => app.profile_label().to_string(),
+        FocusField::TimeMode => {
+            if app.relative_mode {
+                "relative".to_string()
+            } else {
+                "absolute".to_string()
+            }
+        }
+        FocusField::RelativeRange => app.current_relative_option().label.to_string(),
+        FocusField::From => app.from_input.value().to_string(),
+        FocusField::To => app.to_input.value().to_string(),
+        FocusField::LogGroup => app.log_group_input.value().to_string(),
+        FocusField::Query => {
+            let (row, _) = app.query_area.cursor();
+            let line = app.query_area.lines().get(row).cloned().unwrap_or_default();
+            format!("line {}: {line}", row + 1)
+        }
+        FocusField::Results => match app.selected_filtered_index {
+            Some(position) if app.results_navigation => {
+                format!("row {} of {}", position + 1, app.filtered_indices.len())
+            }
+            _ => format!("{} rows", app.filtered_indices.len()),
+        },
+        FocusField::Filter => app.filter_input.value().to_string(),
+        FocusField::Scratchpad => {
+            let (row, _) = app.scratchpad.cursor();
+            let line = app.scratchpad.lines().get(row).cloned().unwrap_or_default();
+            format!("line {}: {line}", row + 1)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::process::{Command, Stdio};
+
+/// First `http(s)://` URL in the text, trimmed of trailing punctuation and JSON quoting.
+pub fn find_url(text: &str) -> Option<String> {
+    let start = ["https://", "http://"]
+        .iter()
+        .filter_map(|scheme| text.find(scheme))
+        .min()?;
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`'))
+        .unwrap_or(rest.len());
+    let url = rest[..end].trim_end_matches(['.', ',', ';', ':', ')', ']', '}', '\\']);
+    (url.len() > "https://".len()).then(|| url.to_string())
+}
+
+/// Host part of a URL, without userinfo or port.
+fn host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Whether the URL's host matches an allowlist entry. `*.example.com` also
+/// matches `example.com`; a bare `*` allows any host.
+pub fn is_allowed(url: &str, allowed_hosts: &[String]) -> bool {
+    let Some(host) = host(url) else {
+        return false;
+    };
+    let host = host.to_ascii_lowercase();
+    allowed_hosts.iter().any(|pattern| {
+        let pattern = pattern.to_ascii_lowercase();
+        if pattern == "*" {
+            return true;
+        }
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host == domain || host.ends_with(&format!(".{domain}")),
+            None => host == pattern,
+        }
+    })
+}
+
+/// Hand the URL to the platform's default browser without waiting for it.
+pub fn open_in_browser(url: &str) -> Result<(), String> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        // Not `cmd /C start`: cmd would treat `&`, `|`, or `^` in a URL taken
+        // from log data as its own syntax.
+        Command::new("explorer.exe")
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|err| format!("Unable to open browser: {err}"))
+}
@@ -1,51 +1,312 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
+use aws_config::timeout::TimeoutConfig;
 use aws_config::BehaviorVersion;
-use aws_sdk_cloudwatchlogs::types::QueryStatus;
+use aws_sdk_cloudwatchlogs::config::SharedHttpClient;
+use aws_sdk_cloudwatchlogs::error::DisplayErrorContext;
+use aws_sdk_cloudwatchlogs::types::{ExportTaskStatusCode, QueryStatus};
 use aws_sdk_cloudwatchlogs::Client;
+use aws_smithy_http_client::proxy::ProxyConfig;
+use aws_smithy_http_client::tls::{self, rustls_provider::CryptoMode, TlsContext, TrustStore};
+use aws_smithy_http_client::{Builder, Connector};
 use aws_types::region::Region;
-use tokio::time::sleep;
+use aws_types::SdkConfig;
+use chrono::DateTime;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
 
-use super::{LogFetcher, LogField, LogRecord, QueryOutcome, QueryParams};
+use crate::config::NetworkConfig;
+use crate::resource_groups::Resource;
+
+use super::{
+    trace_response, watched, ExportTaskState, LogFetcher, LogField, LogRecord, QueryOutcome,
+    QueryParams, S3ExportRequest, SimpleFilter, MAX_QUERY_LOG_GROUPS,
+};
+
+/// Without these the SDK waits on an unreachable endpoint for minutes.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Suggestions don't need every group in very large accounts.
+const MAX_LISTED_LOG_GROUPS: usize = 5000;
+
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Poll quickly while the query statistics keep growing, and back off once
+/// scanning stalls, so long queries cost fewer GetQueryResults calls.
+struct PollBackoff {
+    interval: Duration,
+    last_progress: (f64, f64),
+}
+
+impl PollBackoff {
+    fn new() -> Self {
+        Self {
+            interval: MIN_POLL_INTERVAL,
+            last_progress: (0.0, 0.0),
+        }
+    }
+
+    /// Next wait, given the records matched and scanned so far.
+    fn next_delay(&mut self, matched: f64, scanned: f64) -> Duration {
+        let (last_matched, last_scanned) = self.last_progress;
+        self.interval = if matched > last_matched {
+            MIN_POLL_INTERVAL
+        } else if scanned > last_scanned {
+            // Still scanning without new matches: ease off gently.
+            (self.interval + self.interval / 4).min(MAX_POLL_INTERVAL)
+        } else {
+            (self.interval * 2).min(MAX_POLL_INTERVAL)
+        };
+        self.last_progress = (matched, scanned);
+        self.interval
+    }
+}
 
 #[derive(Clone)]
 pub struct AwsLogFetcher {
     behavior: BehaviorVersion,
+    http_client: Option<SharedHttpClient>,
+    proxied: bool,
+    /// One configuration per region and profile, shared by the Logs, Lambda,
+    /// and ECS clients, so credentials are resolved once and then refreshed
+    /// by the SDK instead of on every call.
+    configs: Arc<Mutex<HashMap<(String, Option<String>), SdkConfig>>>,
 }
 
 impl AwsLogFetcher {
     pub fn new(behavior: BehaviorVersion) -> Self {
-        Self { behavior }
+        Self {
+            behavior,
+            http_client: None,
+            proxied: false,
+            configs: Arc::default(),
+        }
     }
-}
 
-#[async_trait]
-impl LogFetcher for AwsLogFetcher {
-    async fn run_query(&self, params: QueryParams) -> QueryOutcome {
+    /// Route AWS calls through the configured proxy, falling back to
+    /// `HTTPS_PROXY`/`NO_PROXY`, and trust the configured CA bundle on top of
+    /// the platform roots.
+    pub fn with_network(mut self, network: &NetworkConfig) -> Result<Self, String> {
+        let proxy = match network.proxy.as_deref().map(str::trim) {
+            Some(url) if !url.is_empty() => {
+                let config =
+                    ProxyConfig::all(url).map_err(|err| format!("Invalid proxy {url}: {err}"))?;
+                match network.resolved_no_proxy() {
+                    Some(rules) => config.no_proxy(&rules),
+                    None => config,
+                }
+            }
+            _ => ProxyConfig::from_env(),
+        };
+        let mut trust_store = TrustStore::default();
+        if let Some(path) = network.resolved_ca_bundle() {
+            let pem = fs::read(&path)
+                .map_err(|err| format!("Failed to read CA bundle {}: {err}", path.display()))?;
+            trust_store = trust_store.with_pem_certificate(pem);
+        }
+        let tls_context = TlsContext::builder()
+            .with_trust_store(trust_store)
+            .build()
+            .map_err(|err| format!("Invalid CA bundle: {err}"))?;
+        self.proxied = network.resolved_proxy().is_some();
+        self.http_client = Some(Builder::new().build_with_connector_fn(
+            move |settings, components| {
+                let mut builder = Connector::builder()
+                    .tls_provider(tls::Provider::Rustls(CryptoMode::AwsLc))
+                    .tls_context(tls_context.clone());
+                builder.set_connector_settings(settings.cloned());
+                if let Some(components) = components {
+                    builder.set_sleep_impl(components.sleep_impl());
+                }
+                builder.set_proxy_config(Some(proxy.clone()));
+                builder.build()
+            },
+        ));
+        Ok(self)
+    }
+
+    async fn sdk_config(&self, region: &str, profile: Option<&str>) -> SdkConfig {
+        let key = (region.to_string(), profile.map(str::to_string));
+        if let Some(config) = self
+            .configs
+            .lock()
+            .ok()
+            .and_then(|configs| configs.get(&key).cloned())
+        {
+            return config;
+        }
         let mut loader = aws_config::defaults(self.behavior);
-        if let Some(profile) = params.profile.as_deref() {
+        if let Some(profile) = profile {
             loader = loader.profile_name(profile);
         }
-        loader = loader.region(Region::new(params.region.clone()));
-        let config = loader.load().await;
-        let client = Client::new(&config);
+        loader = loader.region(Region::new(region.to_string()));
+        loader = loader.timeout_config(
+            TimeoutConfig::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .read_timeout(READ_TIMEOUT)
+                .build(),
+        );
+        if let Some(http_client) = &self.http_client {
+            loader = loader.http_client(http_client.clone());
+        }
+        let request = format!("profile={} region={region}", profile.unwrap_or("default"));
+        let config = watched("credentials", request, loader.load()).await;
+        if let Ok(mut configs) = self.configs.lock() {
+            configs.insert(key, config.clone());
+        }
+        config
+    }
 
-        let log_groups = vec![params.log_group.clone()];
-        let joined = log_groups.join(",");
+    async fn client(&self, region: &str, profile: Option<&str>) -> Client {
+        Client::new(&self.sdk_config(region, profile).await)
+    }
 
-        let start_result = client
-            .start_query()
-            .log_group_names(joined)
-            .query_string(params.query.clone())
-            .start_time(params.start_epoch)
-            .end_time(params.end_epoch)
-            .send()
-            .await;
+    /// The function's own log group: `LoggingConfig.LogGroup` when it has
+    /// one, otherwise the default `/aws/lambda/<name>`.
+    async fn function_log_group(
+        &self,
+        name: &str,
+        region: &str,
+        profile: Option<&str>,
+    ) -> Result<String, String> {
+        let client = aws_sdk_lambda::Client::new(&self.sdk_config(region, profile).await);
+        let response = watched(
+            "GetFunctionConfiguration",
+            format!("functionName={name}"),
+            client
+                .get_function_configuration()
+                .function_name(name)
+                .send(),
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to look up Lambda function {name}: {}",
+                DisplayErrorContext(err)
+            )
+        })?;
+        let custom = response
+            .logging_config()
+            .and_then(|logging| logging.log_group())
+            .filter(|group| !group.is_empty());
+        trace_response(custom.unwrap_or("default log group").to_string());
+        // The function name may have been given as an ARN.
+        let name = response.function_name().unwrap_or(name);
+        Ok(custom
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("/aws/lambda/{name}")))
+    }
+
+    /// The `awslogs-group` of every container in the service's current task
+    /// definition.
+    async fn service_log_groups(
+        &self,
+        cluster: Option<&str>,
+        service: &str,
+        region: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let client = aws_sdk_ecs::Client::new(&self.sdk_config(region, profile).await);
+        let cluster = cluster.unwrap_or("default");
+        let response = watched(
+            "DescribeServices",
+            format!("cluster={cluster} services={service}"),
+            client
+                .describe_services()
+                .cluster(cluster)
+                .services(service)
+                .send(),
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to look up ECS service {service}: {}",
+                DisplayErrorContext(err)
+            )
+        })?;
+        let task_definition = response
+            .services()
+            .iter()
+            .find_map(|found| found.task_definition())
+            .ok_or_else(|| format!("ECS service {service} not found in cluster {cluster}"))?
+            .to_string();
+        trace_response(task_definition.clone());
+        let response = watched(
+            "DescribeTaskDefinition",
+            format!("taskDefinition={task_definition}"),
+            client
+                .describe_task_definition()
+                .task_definition(&task_definition)
+                .send(),
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Failed to read task definition {task_definition}: {}",
+                DisplayErrorContext(err)
+            )
+        })?;
+        let mut groups: Vec<String> = Vec::new();
+        let containers = response
+            .task_definition()
+            .map(|definition| definition.container_definitions())
+            .unwrap_or_default();
+        for container in containers {
+            let group = container
+                .log_configuration()
+                .and_then(|logging| logging.options())
+                .and_then(|options| options.get("awslogs-group"));
+            if let Some(group) = group {
+                if !groups.contains(group) {
+                    groups.push(group.clone());
+                }
+            }
+        }
+        trace_response(format!("{} log groups", groups.len()));
+        if groups.is_empty() {
+            return Err(format!(
+                "No container of ECS service {service} logs to CloudWatch with awslogs-group"
+            ));
+        }
+        Ok(groups)
+    }
+}
+
+#[async_trait]
+impl LogFetcher for AwsLogFetcher {
+    async fn run_query(&self, params: QueryParams, log_groups: Vec<String>) -> QueryOutcome {
+        let client = self.client(&params.region, params.profile.as_deref()).await;
+
+        let (start_epoch, end_epoch) = params.epoch_seconds();
+        let request = format!(
+            "logGroupNames={} startTime={start_epoch} endTime={end_epoch} queryString={}",
+            log_groups.join(","),
+            params.effective_query()
+        );
+        let start_result = watched(
+            "StartQuery",
+            request,
+            client
+                .start_query()
+                .set_log_group_names(Some(log_groups))
+                .query_string(params.effective_query())
+                .start_time(start_epoch)
+                .end_time(end_epoch)
+                .send(),
+        )
+        .await;
 
         let start_response = match start_result {
             Ok(resp) => resp,
             Err(err) => {
+                trace_response(format!("error: {err}"));
                 return QueryOutcome::Error(format!("Failed to start query: {err:?}"));
             }
         };
@@ -54,43 +315,409 @@ impl LogFetcher for AwsLogFetcher {
             Some(id) => id.to_string(),
             None => return QueryOutcome::Error("Missing query id".into()),
         };
+        trace_response(format!("queryId={query_id}"));
 
+        let mut backoff = PollBackoff::new();
         loop {
-            match client
-                .get_query_results()
-                .query_id(query_id.clone())
-                .send()
-                .await
+            match watched(
+                "GetQueryResults",
+                format!("queryId={query_id}"),
+                client.get_query_results().query_id(query_id.clone()).send(),
+            )
+            .await
             {
-                Ok(resp) => match resp.status() {
-                    Some(QueryStatus::Complete) => {
-                        let mut records = Vec::new();
-                        for row in resp.results() {
-                            let record = row
-                                .iter()
-                                .map(|field| LogField {
-                                    name: field.field().map(|s| s.to_string()),
-                                    value: field.value().unwrap_or_default().to_string(),
-                                })
-                                .collect::<LogRecord>();
-                            records.push(record);
+                Ok(resp) => {
+                    let (matched, scanned) = resp
+                        .statistics()
+                        .map(|stats| (stats.records_matched(), stats.records_scanned()))
+                        .unwrap_or_default();
+                    trace_response(format!(
+                        "{}, {} rows, {matched} matched / {scanned} scanned",
+                        resp.status().map_or("no status", |status| status.as_str()),
+                        resp.results().len()
+                    ));
+                    match resp.status() {
+                        Some(QueryStatus::Complete) => {
+                            let mut records = Vec::new();
+                            for row in resp.results() {
+                                let record = row
+                                    .iter()
+                                    .map(|field| LogField {
+                                        name: field.field().map(|s| s.to_string()),
+                                        value: field.value().unwrap_or_default().to_string(),
+                                    })
+                                    .collect::<LogRecord>();
+                                records.push(record);
+                            }
+                            return QueryOutcome::Success(records);
+                        }
+                        Some(QueryStatus::Failed) => {
+                            return QueryOutcome::Error("Query failed".into());
+                        }
+                        Some(QueryStatus::Cancelled) => {
+                            return QueryOutcome::Error("Query cancelled".into());
+                        }
+                        _ => {
+                            sleep(backoff.next_delay(matched, scanned)).await;
                         }
-                        return QueryOutcome::Success(records);
                     }
-                    Some(QueryStatus::Failed) => {
-                        return QueryOutcome::Error("Query failed".into());
+                }
+                Err(err) => {
+                    trace_response(format!("error: {err}"));
+                    return QueryOutcome::Error(format!("Failed to poll query results: {err:?}"));
+                }
+            }
+        }
+    }
+
+    async fn filter_events(
+        &self,
+        params: QueryParams,
+        log_groups: Vec<String>,
+        filter: SimpleFilter,
+    ) -> QueryOutcome {
+        let client = self.client(&params.region, params.profile.as_deref()).await;
+        // (timestamp, log group, stream, message) across every group, oldest first.
+        let mut events: Vec<(i64, String, String, String)> = Vec::new();
+        for log_group in &log_groups {
+            let mut fetched = 0;
+            let mut next_token: Option<String> = None;
+            loop {
+                let request = format!(
+                    "logGroupName={log_group} filterPattern={} startTime={} endTime={}",
+                    filter.pattern, params.start_millis, params.end_millis
+                );
+                let response = match watched(
+                    "FilterLogEvents",
+                    request,
+                    client
+                        .filter_log_events()
+                        .log_group_name(log_group)
+                        .filter_pattern(filter.pattern.clone())
+                        .start_time(params.start_millis)
+                        .end_time(params.end_millis)
+                        .set_next_token(next_token.take())
+                        .send(),
+                )
+                .await
+                {
+                    Ok(response) => response,
+                    Err(err) => {
+                        trace_response(format!("error: {err}"));
+                        return QueryOutcome::Error(format!(
+                            "Failed to filter log events: {err:?}"
+                        ));
                     }
-                    Some(QueryStatus::Cancelled) => {
-                        return QueryOutcome::Error("Query cancelled".into());
+                };
+                trace_response(format!(
+                    "{} events{}",
+                    response.events().len(),
+                    if response.next_token().is_some() {
+                        ", more to fetch"
+                    } else {
+                        ""
                     }
-                    _ => {
-                        sleep(Duration::from_millis(500)).await;
+                ));
+                for event in response.events() {
+                    events.push((
+                        event.timestamp().unwrap_or_default(),
+                        log_group.clone(),
+                        event.log_stream_name().unwrap_or_default().to_string(),
+                        event.message().unwrap_or_default().to_string(),
+                    ));
+                    fetched += 1;
+                }
+                match response.next_token() {
+                    Some(token) if fetched < filter.limit => next_token = Some(token.to_string()),
+                    _ => break,
+                }
+            }
+        }
+        events.sort_by_key(|(timestamp, ..)| *timestamp);
+        events.truncate(filter.limit);
+
+        let records = events
+            .into_iter()
+            .map(|(timestamp, log_group, stream, message)| {
+                filter
+                    .fields
+                    .iter()
+                    .map(|name| {
+                        let value = match name.as_str() {
+                            // Same layout Insights uses for @timestamp.
+                            "@timestamp" => DateTime::from_timestamp_millis(timestamp)
+                                .map(|at| at.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                                .unwrap_or_default(),
+                            "@logStream" => stream.clone(),
+                            "@log" => log_group.clone(),
+                            _ => message.clone(),
+                        };
+                        LogField {
+                            name: Some(name.clone()),
+                            value,
+                        }
+                    })
+                    .collect::<LogRecord>()
+            })
+            .collect();
+        QueryOutcome::Success(records)
+    }
+
+    async fn check_connectivity(&self, params: &QueryParams) -> Result<(), String> {
+        // Behind a proxy the endpoint is usually not directly reachable; let the SDK try.
+        if self.proxied {
+            return Ok(());
+        }
+        let domain = if params.region.starts_with("cn-") {
+            "amazonaws.com.cn"
+        } else {
+            "amazonaws.com"
+        };
+        let host = format!("logs.{}.{domain}", params.region);
+        match timeout(PROBE_TIMEOUT, TcpStream::connect((host.as_str(), 443))).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(err)) => Err(format!("Network unreachable — check VPN ({host}: {err})")),
+            Err(_) => Err(format!(
+                "Network unreachable — check VPN ({host} did not answer within {}s)",
+                PROBE_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    async fn resolve_log_groups(&self, params: &QueryParams) -> Result<Vec<String>, String> {
+        let client = self.client(&params.region, params.profile.as_deref()).await;
+        let mut names: Vec<String> = Vec::new();
+        for entry in params.log_group_names() {
+            if let Some(resource) = Resource::parse(entry) {
+                let found = self
+                    .resource_log_groups(&resource, &params.region, params.profile.as_deref())
+                    .await?;
+                for name in found {
+                    if !names.contains(&name) {
+                        names.push(name);
                     }
-                },
-                Err(err) => {
-                    return QueryOutcome::Error(format!("Failed to poll query results: {err:?}"));
                 }
+                continue;
+            }
+            let Some(prefix) = entry.strip_suffix('*') else {
+                if !names.iter().any(|name| name == entry) {
+                    names.push(entry.to_string());
+                }
+                continue;
+            };
+            for name in expand_log_group_prefix(&client, prefix).await? {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            // One past the limit is enough to know the list was truncated.
+            if names.len() > MAX_QUERY_LOG_GROUPS {
+                break;
+            }
+        }
+        Ok(names)
+    }
+
+    async fn resource_log_groups(
+        &self,
+        resource: &Resource,
+        region: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        match resource {
+            Resource::Function(name) => self
+                .function_log_group(name, region, profile)
+                .await
+                .map(|group| vec![group]),
+            Resource::Service { cluster, service } => {
+                self.service_log_groups(cluster.as_deref(), service, region, profile)
+                    .await
+            }
+        }
+    }
+
+    fn forget_profile(&self, profile: &str) {
+        if let Ok(mut configs) = self.configs.lock() {
+            configs.retain(|(_, cached), _| cached.as_deref() != Some(profile));
+        }
+    }
+
+    async fn check_credentials(&self, region: &str, profile: Option<&str>) -> Result<(), String> {
+        let client = self.client(region, profile).await;
+        watched(
+            "DescribeLogGroups",
+            "limit=1".into(),
+            client.describe_log_groups().limit(1).send(),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|err| DisplayErrorContext(err).to_string())
+    }
+
+    async fn list_log_groups(
+        &self,
+        region: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let client = self.client(region, profile).await;
+        let mut names = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let response = watched(
+                "DescribeLogGroups",
+                String::new(),
+                client
+                    .describe_log_groups()
+                    .set_next_token(next_token.take())
+                    .send(),
+            )
+            .await
+            .map_err(|err| format!("Failed to list log groups: {err:?}"))?;
+            names.extend(
+                response
+                    .log_groups()
+                    .iter()
+                    .filter_map(|group| group.log_group_name())
+                    .map(|name| name.to_string()),
+            );
+            if names.len() >= MAX_LISTED_LOG_GROUPS {
+                break;
             }
+            match response.next_token() {
+                Some(token) => next_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+        Ok(names)
+    }
+
+    async fn log_group_retention_days(&self, params: &QueryParams) -> Result<Option<i32>, String> {
+        // Lists and patterns span several groups, each with its own retention.
+        if params.spans_log_groups() {
+            return Ok(None);
+        }
+        let client = self.client(&params.region, params.profile.as_deref()).await;
+        let response = watched(
+            "DescribeLogGroups",
+            format!("logGroupNamePrefix={}", params.log_group),
+            client
+                .describe_log_groups()
+                .log_group_name_prefix(params.log_group.clone())
+                .send(),
+        )
+        .await
+        .map_err(|err| format!("Failed to describe log group: {err:?}"))?;
+        trace_response(format!("{} log groups", response.log_groups().len()));
+        Ok(response
+            .log_groups()
+            .iter()
+            .find(|group| group.log_group_name() == Some(params.log_group.as_str()))
+            .and_then(|group| group.retention_in_days()))
+    }
+
+    async fn start_s3_export(&self, request: &S3ExportRequest) -> Result<String, String> {
+        let client = self
+            .client(&request.region, request.profile.as_deref())
+            .await;
+        let mut builder = client
+            .create_export_task()
+            .task_name(request.task_name.clone())
+            .log_group_name(request.log_group.clone())
+            .from(request.start_millis)
+            .to(request.end_millis)
+            .destination(request.bucket.clone());
+        if let Some(prefix) = request.prefix.as_deref() {
+            builder = builder.destination_prefix(prefix);
+        }
+        let detail = format!(
+            "logGroupName={} destination={}",
+            request.log_group, request.bucket
+        );
+        let response = watched("CreateExportTask", detail, builder.send())
+            .await
+            .map_err(|err| format!("Failed to start S3 export: {err:?}"))?;
+        response
+            .task_id()
+            .map(|id| id.to_string())
+            .ok_or_else(|| "Missing export task id".to_string())
+    }
+
+    async fn s3_export_state(
+        &self,
+        request: &S3ExportRequest,
+        task_id: &str,
+    ) -> Result<ExportTaskState, String> {
+        let client = self
+            .client(&request.region, request.profile.as_deref())
+            .await;
+        let response = watched(
+            "DescribeExportTasks",
+            format!("taskId={task_id}"),
+            client.describe_export_tasks().task_id(task_id).send(),
+        )
+        .await
+        .map_err(|err| format!("Failed to check S3 export: {err:?}"))?;
+        let Some(status) = response
+            .export_tasks()
+            .first()
+            .and_then(|task| task.status())
+        else {
+            return Ok(ExportTaskState::Pending);
+        };
+        let message = status.message().unwrap_or("Export task failed").to_string();
+        let state = match status.code() {
+            Some(ExportTaskStatusCode::Completed) => ExportTaskState::Completed,
+            Some(ExportTaskStatusCode::Failed) => ExportTaskState::Failed(message),
+            Some(ExportTaskStatusCode::Cancelled) | Some(ExportTaskStatusCode::PendingCancel) => {
+                ExportTaskState::Cancelled
+            }
+            Some(ExportTaskStatusCode::Running) => ExportTaskState::Running,
+            _ => ExportTaskState::Pending,
+        };
+        Ok(state)
+    }
+}
+
+/// Every log group whose name starts with `prefix`, stopping once past the query limit.
+async fn expand_log_group_prefix(client: &Client, prefix: &str) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    let mut next_token: Option<String> = None;
+    loop {
+        let response = watched(
+            "DescribeLogGroups",
+            format!("logGroupNamePrefix={prefix}"),
+            client
+                .describe_log_groups()
+                .log_group_name_prefix(prefix)
+                .set_next_token(next_token.take())
+                .send(),
+        )
+        .await
+        .map_err(|err| format!("Failed to list log groups matching {prefix}*: {err:?}"))?;
+        trace_response(format!(
+            "{} log groups{}",
+            response.log_groups().len(),
+            if response.next_token().is_some() {
+                ", more to fetch"
+            } else {
+                ""
+            }
+        ));
+        names.extend(
+            response
+                .log_groups()
+                .iter()
+                .filter_map(|group| group.log_group_name())
+                .map(|name| name.to_string()),
+        );
+        // One past the limit is enough to know the pattern was truncated.
+        if names.len() > MAX_QUERY_LOG_GROUPS {
+            break;
+        }
+        match response.next_token() {
+            Some(token) => next_token = Some(token.to_string()),
+            None => break,
         }
     }
+    Ok(names)
 }
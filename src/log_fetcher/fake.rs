@@ -1,10 +1,16 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use tokio::time::sleep;
 
-use super::{LogFetcher, LogField, LogRecord, QueryOutcome, QueryParams};
+use crate::resource_groups::Resource;
+
+use super::{
+    trace_response, watched, ExportTaskState, LogFetcher, LogField, LogRecord, QueryOutcome,
+    QueryParams, S3ExportRequest, SimpleFilter,
+};
 
 #[derive(Clone)]
 pub struct FakeLogFetcher {
@@ -23,9 +29,107 @@ impl FakeLogFetcher {
 
 #[async_trait]
 impl LogFetcher for FakeLogFetcher {
-    async fn run_query(&self, _params: QueryParams) -> QueryOutcome {
+    async fn run_query(&self, params: QueryParams, _log_groups: Vec<String>) -> QueryOutcome {
+        watched("GetQueryResults", "queryId=fake".into(), sleep(self.delay)).await;
+        let records = match count_by(&params.query) {
+            Some((count_name, field)) => count_records(&self.records, count_name, field),
+            None => (*self.records).clone(),
+        };
+        trace_response(format!("Complete, {} rows", records.len()));
+        QueryOutcome::Success(records)
+    }
+
+    async fn filter_events(
+        &self,
+        _params: QueryParams,
+        log_groups: Vec<String>,
+        filter: SimpleFilter,
+    ) -> QueryOutcome {
+        watched(
+            "FilterLogEvents",
+            format!("filterPattern={}", filter.pattern),
+            sleep(self.delay / 3),
+        )
+        .await;
+        let needle = filter.pattern.trim_matches('"');
+        let log_group = log_groups.first().cloned().unwrap_or_default();
+        let records = self
+            .records
+            .iter()
+            .filter(|record| {
+                record.iter().any(|field| {
+                    field.name.as_deref() == Some("@message") && field.value.contains(needle)
+                })
+            })
+            .take(filter.limit)
+            .map(|record| {
+                filter
+                    .fields
+                    .iter()
+                    .map(|name| {
+                        let value = match name.as_str() {
+                            "@logStream" => "fake-stream".to_string(),
+                            "@log" => log_group.clone(),
+                            _ => record
+                                .iter()
+                                .find(|field| field.name.as_deref() == Some(name.as_str()))
+                                .map(|field| field.value.clone())
+                                .unwrap_or_default(),
+                        };
+                        LogField {
+                            name: Some(name.clone()),
+                            value,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        QueryOutcome::Success(records)
+    }
+
+    async fn resource_log_groups(
+        &self,
+        resource: &Resource,
+        _region: &str,
+        _profile: Option<&str>,
+    ) -> Result<Vec<String>, String> {
+        let (call, group) = match resource {
+            Resource::Function(name) => ("GetFunctionConfiguration", format!("/aws/lambda/{name}")),
+            Resource::Service { service, .. } => ("DescribeServices", format!("/ecs/{service}")),
+        };
+        watched(call, resource.to_string(), sleep(self.delay / 3)).await;
+        Ok(vec![group])
+    }
+
+    async fn list_log_groups(
+        &self,
+        _region: &str,
+        _profile: Option<&str>,
+    ) -> Result<Vec<String>, String> {
         sleep(self.delay).await;
-        QueryOutcome::Success((*self.records).clone())
+        Ok(vec![
+            "/aws/lambda/checkout".to_string(),
+            "/aws/lambda/payments".to_string(),
+            "devg".to_string(),
+        ])
+    }
+
+    async fn log_group_retention_days(&self, _params: &QueryParams) -> Result<Option<i32>, String> {
+        Ok(None)
+    }
+
+    async fn start_s3_export(&self, request: &S3ExportRequest) -> Result<String, String> {
+        sleep(self.delay).await;
+        Ok(format!("fake-export-{}", request.start_millis))
+    }
+
+    async fn s3_export_state(
+        &self,
+        _request: &S3ExportRequest,
+        _task_id: &str,
+    ) -> Result<ExportTaskState, String> {
+        sleep(self.delay).await;
+        Ok(ExportTaskState::Completed)
     }
 }
 
@@ -139,6 +243,55 @@ fn build_fake_records() -> Vec<LogRecord> {
     records
 }
 
+/// `stats count(*) as <name> by <field>`, the one aggregation the fake understands.
+fn count_by(query: &str) -> Option<(&str, &str)> {
+    let rest = query.trim().strip_prefix("stats count(*) as ")?;
+    let (count_name, rest) = rest.split_once(" by ")?;
+    let field = rest.split(['\n', '|']).next()?.trim().trim_matches('`');
+    Some((count_name.trim(), field))
+}
+
+/// Count records per value of `field`, looking inside JSON messages like Insights does.
+fn count_records(records: &[LogRecord], count_name: &str, field: &str) -> Vec<LogRecord> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for record in records {
+        let direct = record
+            .iter()
+            .find(|entry| entry.name.as_deref() == Some(field))
+            .map(|entry| entry.value.clone());
+        let value = direct.or_else(|| {
+            let message = record
+                .iter()
+                .find(|entry| entry.name.as_deref() == Some("@message"))?;
+            let json: serde_json::Value = serde_json::from_str(&message.value).ok()?;
+            Some(match json.get(field)? {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+        });
+        if let Some(value) = value {
+            *counts.entry(value).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+        .into_iter()
+        .map(|(value, count)| {
+            vec![
+                LogField {
+                    name: Some(field.to_string()),
+                    value,
+                },
+                LogField {
+                    name: Some(count_name.to_string()),
+                    value: count.to_string(),
+                },
+            ]
+        })
+        .collect()
+}
+
 fn synthetic_timestamp(idx: usize) -> String {
     let day = 1 + (idx % 28);
     let hour = (idx * 5) % 24;
@@ -0,0 +1,83 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Caps how many Logs Insights queries run at once. The account-wide limit is
+/// shared with everyone else, so the rest wait here in arrival order instead of
+/// being throttled by AWS.
+pub struct QueryLimiter {
+    permits: Semaphore,
+    issued: AtomicU64,
+    /// Tickets not yet admitted nor given up.
+    waiting: Arc<Mutex<BTreeSet<u64>>>,
+}
+
+/// Place in the queue, taken when the query is submitted. It leaves the
+/// queue when admitted or dropped, so a query that fails or is cancelled
+/// before it runs holds up no one's position.
+pub struct QueueTicket {
+    place: QueuePlace,
+    waiting: Arc<Mutex<BTreeSet<u64>>>,
+}
+
+/// A ticket's place, for showing how many queries are ahead of it.
+#[derive(Clone, Copy)]
+pub struct QueuePlace(u64);
+
+impl QueueTicket {
+    pub fn place(&self) -> QueuePlace {
+        self.place
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        if let Ok(mut waiting) = self.waiting.lock() {
+            waiting.remove(&self.place.0);
+        }
+    }
+}
+
+impl QueryLimiter {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent.max(1)),
+            issued: AtomicU64::new(0),
+            waiting: Arc::default(),
+        }
+    }
+
+    pub fn ticket(&self) -> QueueTicket {
+        let number = self.issued.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut waiting) = self.waiting.lock() {
+            waiting.insert(number);
+        }
+        QueueTicket {
+            place: QueuePlace(number),
+            waiting: Arc::clone(&self.waiting),
+        }
+    }
+
+    /// Wait for a free slot; the query may run while the permit is held.
+    pub async fn admit(&self, ticket: QueueTicket) -> SemaphorePermit<'_> {
+        // Tokio's semaphore is fair, so tickets are admitted in order.
+        let permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("query limiter semaphore is never closed");
+        drop(ticket);
+        permit
+    }
+
+    /// Number of queries queued ahead of this place, or `None` once it has
+    /// left the queue.
+    pub fn queued_ahead(&self, place: QueuePlace) -> Option<u64> {
+        let waiting = self.waiting.lock().ok()?;
+        waiting
+            .contains(&place.0)
+            .then(|| waiting.range(..place.0).count() as u64)
+    }
+}
@@ -1,21 +1,73 @@
 use async_trait::async_trait;
 
+use crate::resource_groups::Resource;
+
 pub mod aws;
 pub mod fake;
+pub mod limiter;
+pub mod simple;
+pub mod watchdog;
 
 pub use aws::AwsLogFetcher;
 pub use fake::FakeLogFetcher;
+pub use limiter::{QueryLimiter, QueuePlace, QueueTicket};
+pub use simple::{simple_filter, QueryEngine, SimpleFilter};
+pub use watchdog::{trace_response, watched, CallWatch};
+
+/// StartQuery accepts at most this many log groups.
+pub const MAX_QUERY_LOG_GROUPS: usize = 50;
 
 #[derive(Clone)]
 pub struct QueryParams {
-    pub start_epoch: i64,
-    pub end_epoch: i64,
+    /// Inclusive window bounds in epoch milliseconds.
+    pub start_millis: i64,
+    pub end_millis: i64,
     pub log_group: String,
     pub query: String,
     pub region: String,
     pub profile: Option<String>,
 }
 
+impl QueryParams {
+    /// Whole-second bounds for StartQuery, widened to cover the millisecond window.
+    pub fn epoch_seconds(&self) -> (i64, i64) {
+        (
+            self.start_millis.div_euclid(1000),
+            (self.end_millis + 999).div_euclid(1000),
+        )
+    }
+
+    /// Entries of a comma-separated log group list; a single name yields one entry.
+    pub fn log_group_names(&self) -> impl Iterator<Item = &str> {
+        self.log_group
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Whether the query may span several groups, through a list, a pattern,
+    /// or a service that logs to more than one.
+    pub fn spans_log_groups(&self) -> bool {
+        self.log_group_names().nth(1).is_some()
+            || self
+                .log_group_names()
+                .any(|name| name.ends_with('*') || Resource::parse(name).is_some())
+    }
+
+    /// StartQuery only accepts seconds, so sub-second bounds are enforced with a
+    /// leading `@timestamp` filter. Windows ending now are taken in whole
+    /// seconds, so only bounds typed (or picked) with milliseconds get one.
+    pub fn effective_query(&self) -> String {
+        if self.start_millis % 1000 == 0 && self.end_millis % 1000 == 0 {
+            return self.query.clone();
+        }
+        format!(
+            "filter @timestamp >= {} and @timestamp <= {}\n| {}",
+            self.start_millis, self.end_millis, self.query
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct LogField {
     pub name: Option<String>,
@@ -29,7 +81,115 @@ pub enum QueryOutcome {
     Error(String),
 }
 
+/// A finished query plus a caveat worth showing alongside its results.
+pub struct QueryReport {
+    pub outcome: QueryOutcome,
+    pub warning: Option<String>,
+}
+
+/// CloudWatch Logs export task that copies a log group's events to S3.
+#[derive(Clone)]
+pub struct S3ExportRequest {
+    pub region: String,
+    pub profile: Option<String>,
+    pub log_group: String,
+    pub start_millis: i64,
+    pub end_millis: i64,
+    pub bucket: String,
+    pub prefix: Option<String>,
+    pub task_name: String,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum ExportTaskState {
+    Pending,
+    Running,
+    Completed,
+    Failed(String),
+    Cancelled,
+}
+
+impl ExportTaskState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed(_) | Self::Cancelled)
+    }
+}
+
 #[async_trait]
 pub trait LogFetcher: Send + Sync {
-    async fn run_query(&self, params: QueryParams) -> QueryOutcome;
+    /// Run the query against `log_groups`, as returned by `resolve_log_groups`.
+    async fn run_query(&self, params: QueryParams, log_groups: Vec<String>) -> QueryOutcome;
+
+    /// Run a simple keyword search with FilterLogEvents instead of Logs Insights,
+    /// producing records shaped like Insights results.
+    async fn filter_events(
+        &self,
+        params: QueryParams,
+        log_groups: Vec<String>,
+        filter: SimpleFilter,
+    ) -> QueryOutcome;
+
+    /// Fail fast when the service endpoint cannot be reached at all.
+    async fn check_connectivity(&self, _params: &QueryParams) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Resolve credentials for the profile and make one cheap call with them,
+    /// so problems show up before a query and the first query skips sign-in.
+    async fn check_credentials(&self, _region: &str, _profile: Option<&str>) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Drop whatever is cached for the profile, so the next call reads its
+    /// config, credentials, and region again.
+    fn forget_profile(&self, _profile: &str) {}
+
+    /// Log groups the query should cover; patterns are expanded by name prefix
+    /// and `fn:`/`ecs:` entries through `resource_log_groups`.
+    async fn resolve_log_groups(&self, params: &QueryParams) -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = Vec::new();
+        for entry in params.log_group_names() {
+            let found = match Resource::parse(entry) {
+                Some(resource) => {
+                    self.resource_log_groups(&resource, &params.region, params.profile.as_deref())
+                        .await?
+                }
+                None => vec![entry.to_string()],
+            };
+            for name in found {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// The log groups a Lambda function or the containers of an ECS service
+    /// write to, as their configuration says.
+    async fn resource_log_groups(
+        &self,
+        resource: &Resource,
+        region: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, String>;
+
+    /// Log group names in the account and region, for suggestions.
+    async fn list_log_groups(
+        &self,
+        region: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, String>;
+
+    /// Retention period of the queried log group in days; `None` means events never expire.
+    async fn log_group_retention_days(&self, params: &QueryParams) -> Result<Option<i32>, String>;
+
+    /// Start an export task and return its task id.
+    async fn start_s3_export(&self, request: &S3ExportRequest) -> Result<String, String>;
+
+    async fn s3_export_state(
+        &self,
+        request: &S3ExportRequest,
+        task_id: &str,
+    ) -> Result<ExportTaskState, String>;
 }
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+
+/// Which API runs a query.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QueryEngine {
+    /// FilterLogEvents for simple keyword searches, Logs Insights otherwise.
+    #[default]
+    Auto,
+    Insights,
+    FilterEvents,
+}
+
+impl QueryEngine {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Insights,
+            Self::Insights => Self::FilterEvents,
+            Self::FilterEvents => Self::Auto,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Insights => "Logs Insights",
+            Self::FilterEvents => "FilterLogEvents",
+        }
+    }
+}
+
+/// Fields FilterLogEvents can fill in.
+pub const FILTER_FIELDS: [&str; 4] = ["@timestamp", "@message", "@logStream", "@log"];
+
+/// Insights returns this many rows when the query has no `limit`.
+const DEFAULT_LIMIT: usize = 1000;
+
+/// A query simple enough to run as FilterLogEvents pages, oldest first.
+#[derive(Clone, Debug)]
+pub struct SimpleFilter {
+    /// Filter pattern syntax, e.g. `"timeout"`.
+    pub pattern: String,
+    pub fields: Vec<String>,
+    pub limit: usize,
+}
+
+/// Recognise `fields` / `filter @message like` / `sort @timestamp asc` / `limit`
+/// queries. Anything else, including newest-first sorting (which would need every
+/// page), is left to Logs Insights.
+pub fn simple_filter(query: &str) -> Option<SimpleFilter> {
+    let mut filter = SimpleFilter {
+        pattern: String::new(),
+        fields: vec!["@timestamp".to_string(), "@message".to_string()],
+        limit: DEFAULT_LIMIT,
+    };
+    let mut seen_filter = false;
+    for command in query.split('|').map(str::trim).filter(|c| !c.is_empty()) {
+        let (keyword, rest) = command
+            .split_once(char::is_whitespace)
+            .map(|(keyword, rest)| (keyword, rest.trim()))
+            .unwrap_or((command, ""));
+        match keyword {
+            "fields" => {
+                let fields: Vec<String> = rest
+                    .split(',')
+                    .map(|field| field.trim().to_string())
+                    .collect();
+                if fields.is_empty()
+                    || !fields
+                        .iter()
+                        .all(|field| FILTER_FIELDS.contains(&field.as_str()))
+                {
+                    return None;
+                }
+                filter.fields = fields;
+            }
+            "filter" if !seen_filter => {
+                let needle = rest.strip_prefix("@message")?.trim().strip_prefix("like")?;
+                filter.pattern = literal_pattern(needle.trim())?;
+                seen_filter = true;
+            }
+            "sort" if rest.split_whitespace().collect::<Vec<_>>() == ["@timestamp", "asc"] => {}
+            "limit" => filter.limit = rest.parse().ok().filter(|limit| *limit > 0)?,
+            _ => return None,
+        }
+    }
+    seen_filter.then_some(filter)
+}
+
+/// `'text'`, `"text"` or a regex without metacharacters becomes a quoted term.
+fn literal_pattern(needle: &str) -> Option<String> {
+    let (text, regex) = if let Some(text) = needle.strip_prefix('/') {
+        (text.strip_suffix('/')?, true)
+    } else {
+        let quoted = ['\'', '"'].iter().find_map(|quote| {
+            needle
+                .strip_prefix(*quote)
+                .and_then(|rest| rest.strip_suffix(*quote))
+        })?;
+        (quoted, false)
+    };
+    let plain = text.chars().all(|c| {
+        if regex {
+            c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | ':' | '=' | ',')
+        } else {
+            !matches!(c, '"' | '\\')
+        }
+    });
+    (plain && !text.trim().is_empty()).then(|| format!("\"{text}\""))
+}
@@ -0,0 +1,133 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    static WATCH: CallWatch;
+}
+
+/// One AWS call made by a watched task.
+#[derive(Clone)]
+pub struct TracedCall {
+    pub call: &'static str,
+    /// Parameters sent, e.g. `queryId=...`.
+    pub request: String,
+    /// When the call was made, relative to the start of the task.
+    pub at: Duration,
+    /// How long it took to answer; `None` while still waiting.
+    pub elapsed: Option<Duration>,
+    /// What came back, when the caller described it.
+    pub response: String,
+}
+
+/// AWS calls made by one task, shared with the UI so a call that never
+/// answers is visible instead of looking like a frozen app, and so the
+/// calls can be reviewed afterwards.
+#[derive(Clone)]
+pub struct CallWatch {
+    started: Instant,
+    calls: Arc<Mutex<Vec<TracedCall>>>,
+}
+
+impl Default for CallWatch {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            calls: Arc::default(),
+        }
+    }
+}
+
+impl CallWatch {
+    /// Run `task` with every `watched` call inside it reported to this watch.
+    pub fn scope<F: Future>(&self, task: F) -> impl Future<Output = F::Output> {
+        WATCH.scope(self.clone(), task)
+    }
+
+    /// The call that has been waiting longest, and for how long.
+    pub fn longest_wait(&self) -> Option<(&'static str, Duration)> {
+        let calls = self.calls.lock().ok()?;
+        calls
+            .iter()
+            .find(|call| call.elapsed.is_none())
+            .map(|call| (call.call, self.started.elapsed().saturating_sub(call.at)))
+    }
+
+    /// Every call so far, oldest first.
+    pub fn trace(&self) -> Vec<TracedCall> {
+        self.calls
+            .lock()
+            .map(|calls| calls.clone())
+            .unwrap_or_default()
+    }
+
+    fn update(&self, index: usize, change: impl FnOnce(&mut TracedCall)) {
+        if let Some(call) = self
+            .calls
+            .lock()
+            .ok()
+            .as_mut()
+            .and_then(|calls| calls.get_mut(index))
+        {
+            change(call);
+        }
+    }
+}
+
+/// Marks the call abandoned when the task is aborted before it answers.
+struct PendingCall {
+    watch: CallWatch,
+    index: usize,
+}
+
+impl Drop for PendingCall {
+    fn drop(&mut self) {
+        let now = self.watch.started.elapsed();
+        self.watch.update(self.index, |call| {
+            if call.elapsed.is_none() {
+                call.elapsed = Some(now.saturating_sub(call.at));
+                call.response = "abandoned without an answer".into();
+            }
+        });
+    }
+}
+
+/// Await one AWS call, e.g. `watched("StartQuery", request, builder.send())`,
+/// recording it on the current task's watch. Outside a watched task this is a
+/// plain await.
+pub async fn watched<F: Future>(call: &'static str, request: String, future: F) -> F::Output {
+    let pending = WATCH.try_with(CallWatch::clone).ok().and_then(|watch| {
+        let index = {
+            let mut calls = watch.calls.lock().ok()?;
+            calls.push(TracedCall {
+                call,
+                request,
+                at: watch.started.elapsed(),
+                elapsed: None,
+                response: String::new(),
+            });
+            calls.len() - 1
+        };
+        Some(PendingCall { watch, index })
+    });
+    let output = future.await;
+    if let Some(pending) = &pending {
+        let now = pending.watch.started.elapsed();
+        pending.watch.update(pending.index, |call| {
+            call.elapsed = Some(now.saturating_sub(call.at));
+        });
+    }
+    output
+}
+
+/// Describe what the call just awaited with `watched` returned. Call it
+/// before the next await so it lands on the right call.
+pub fn trace_response(response: impl Into<String>) {
+    let _ = WATCH.try_with(|watch| {
+        if let Ok(mut calls) = watch.calls.lock() {
+            if let Some(call) = calls.iter_mut().rev().find(|call| call.elapsed.is_some()) {
+                call.response = response.into();
+            }
+        }
+    });
+}
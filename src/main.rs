@@ -1,28 +1,88 @@
 use aws_config::BehaviorVersion;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-use crossterm::{execute, terminal};
+use crossterm::terminal::{self, enable_raw_mode};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::env;
 use std::error::Error;
-use std::io;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::PathBuf;
 use std::sync::Arc;
 mod app;
 mod aws_profiles;
+mod block_edit;
+mod clipboard;
+mod cloudtrail;
+mod clusters;
+mod column_stats;
+mod computed;
+mod config;
+mod console_url;
 mod defaults;
+mod drill_down;
+mod durations;
+mod enrich;
+mod explain;
+mod export;
+mod filter_clause;
+mod focus;
 mod help;
+mod highlight;
+mod hooks;
+mod i18n;
 mod input;
+mod input_panes;
+mod key_macro;
+mod keymap;
+mod linear_ui;
+mod links;
 mod log_fetcher;
+mod metadata_cache;
+mod metrics;
+mod opensearch;
+mod palette;
 mod presentation;
+mod query_diff;
+mod query_docs;
+mod query_lint;
+mod query_pairs;
+mod relative_ranges;
+mod renderers;
+mod resource_groups;
+mod result_cache;
+mod search_index;
+mod serve;
+mod share;
+mod snapshot;
+mod snippets;
+mod spill;
+mod table_image;
+mod theme;
+mod time_format;
+mod timeline;
 mod tui;
 mod ui;
+mod value_picker;
+mod variables;
 mod widgets;
+mod workspaces;
 use log_fetcher::{AwsLogFetcher, FakeLogFetcher, LogFetcher};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let args: Vec<String> = env::args().collect();
     let use_fake = args.iter().any(|arg| arg == "--fake" || arg == "-f");
+    let linear = args.iter().any(|arg| arg == "--linear");
+    let remote = args.iter().any(|arg| arg == "--remote");
+    theme::init(args.iter().any(|arg| arg == "--monochrome"), remote);
+    // Read before the terminal switches to raw mode so piped input is consumed normally.
+    let initial_query = launch_query(&args)?;
+    let console_link = args
+        .iter()
+        .skip(1)
+        .find(|arg| console_url::is_console_url(arg))
+        .map(|url| console_url::parse(url))
+        .transpose()?;
     let (fetcher, status_override): (Arc<dyn LogFetcher>, Option<String>) = if use_fake {
         (
             Arc::new(FakeLogFetcher::new()),
@@ -30,30 +90,81 @@ async fn main() -> Result<(), Box<dyn Error>> {
         )
     } else {
         (
-            Arc::new(AwsLogFetcher::new(BehaviorVersion::latest())),
+            Arc::new(
+                AwsLogFetcher::new(BehaviorVersion::latest())
+                    .with_network(&config::load_config().0.network)?,
+            ),
             None,
         )
     };
 
+    if args.get(1).is_some_and(|arg| arg == "serve") {
+        // JSON-RPC on stdio: no terminal setup, and stdout carries only responses.
+        return serve::run(fetcher).await.map_err(Into::into);
+    }
+
     enable_raw_mode()?;
+    // Where supported, the kitty keyboard protocol reports modifiers on Enter reliably.
+    let modes = tui::TerminalModes {
+        mouse_capture: !remote,
+        keyboard_enhanced: terminal::supports_keyboard_enhancement().unwrap_or(false),
+    };
     let mut stdout = io::stdout();
-    execute!(
-        stdout,
-        terminal::EnterAlternateScreen,
-        crossterm::event::EnableMouseCapture
-    )?;
+    tui::enter_terminal_modes(&mut stdout, modes)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let app_result = tui::run_app(fetcher, status_override, &mut terminal).await;
+    let options = tui::LaunchOptions {
+        initial_status: status_override,
+        linear,
+        remote,
+        modes,
+        initial_query,
+        console_link,
+    };
+    let app_result = tui::run_app(fetcher, options, &mut terminal).await;
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        crossterm::event::DisableMouseCapture,
-        terminal::LeaveAlternateScreen
-    )?;
+    tui::leave_terminal_modes(terminal.backend_mut(), modes)?;
     terminal.show_cursor()?;
 
     app_result
 }
+
+/// Query text from `--query TEXT`, `--query -` (stdin), or `--query-file PATH`.
+fn launch_query(args: &[String]) -> Result<Option<(String, Option<PathBuf>)>, String> {
+    let value_of = |flag: &str| {
+        args.iter().enumerate().find_map(|(idx, arg)| {
+            if arg == flag {
+                Some(
+                    args.get(idx + 1)
+                        .cloned()
+                        .ok_or(format!("{flag} needs a value")),
+                )
+            } else {
+                arg.strip_prefix(&format!("{flag}="))
+                    .map(|value| Ok(value.to_string()))
+            }
+        })
+    };
+    if let Some(path) = value_of("--query-file").transpose()? {
+        let path = PathBuf::from(path);
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+        return Ok(Some((text, Some(path))));
+    }
+    match value_of("--query").transpose()? {
+        Some(value) if value == "-" => {
+            let mut stdin = io::stdin();
+            if stdin.is_terminal() {
+                return Err("--query - expects the query on piped stdin".to_string());
+            }
+            let mut text = String::new();
+            stdin
+                .read_to_string(&mut text)
+                .map_err(|err| format!("Failed to read query from stdin: {err}"))?;
+            Ok(Some((text, None)))
+        }
+        Some(value) => Ok(Some((value, None))),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,97 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::aws_profiles::home_dir;
+
+/// Log group names and discovered field names for one profile and region,
+/// kept on disk so suggestions are available the moment the app starts.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Epoch seconds when `log_groups` was last listed from AWS.
+    pub fetched_at: i64,
+    pub log_groups: Vec<String>,
+    /// Result columns seen in earlier queries, in first-seen order.
+    pub fields: Vec<String>,
+}
+
+/// The profile stands in for the account, since each profile names one set of credentials.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MetadataKey {
+    pub profile: Option<String>,
+    pub region: String,
+}
+
+impl MetadataKey {
+    fn path(&self) -> Option<PathBuf> {
+        let name = format!(
+            "{}@{}.json",
+            self.profile.as_deref().unwrap_or("default"),
+            self.region
+        );
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        cache_dir().map(|dir| dir.join(name))
+    }
+}
+
+impl Metadata {
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        let age = Utc::now().timestamp() - self.fetched_at;
+        age >= 0 && (age as u64) < ttl.as_secs()
+    }
+
+    /// Record result columns worth suggesting; returns whether anything was new.
+    pub fn add_fields<'a>(&mut self, fields: impl IntoIterator<Item = &'a String>) -> bool {
+        let mut changed = false;
+        for field in fields {
+            // Skip `stats` expressions like `count(*)`; only plain names complete well.
+            if field.contains(['(', ' ']) || self.fields.contains(field) {
+                continue;
+            }
+            self.fields.push(field.clone());
+            changed = true;
+        }
+        changed
+    }
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let base = env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".cache")))?;
+    Some(base.join("awslogs").join("metadata"))
+}
+
+/// Cached metadata for the key, or an empty (stale) entry when there is none.
+pub fn load(key: &MetadataKey) -> Metadata {
+    key.path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(key: &MetadataKey, metadata: &Metadata) -> Result<(), String> {
+    let path = key.path().ok_or("No cache directory available")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Unable to prepare cache directory: {err}"))?;
+    }
+    let payload = serde_json::to_string(metadata)
+        .map_err(|err| format!("Failed to encode metadata cache: {err}"))?;
+    fs::write(&path, payload).map_err(|err| format!("Failed to write metadata cache: {err}"))
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::MetricsConfig;
+use crate::drill_down;
+use crate::export::{parse_timestamp, ExportTable};
+
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+const METRIC_PREFIX: &str = "awslogs_";
+
+/// Numeric columns of time-bucketed stats results, one sample per row.
+struct Series {
+    /// `(metric name, column)` for every numeric column.
+    metrics: Vec<(String, usize)>,
+    /// Per row: bucket start in epoch milliseconds, rendered `{label="..."}`,
+    /// and row index, ordered by label set and then time so each series'
+    /// samples sit together.
+    samples: Vec<(i64, String, usize)>,
+}
+
+/// Stats results as OpenMetrics text, one gauge per numeric column with the
+/// bucket start as each sample's timestamp. The `by` fields of `query`, the
+/// stats query behind the table, are the labels.
+pub fn openmetrics(table: &ExportTable, query: Option<&str>) -> Result<String, String> {
+    let rows = table.rows.clone().into_vec();
+    let series = series(&table.headers, &rows, query)?;
+    let mut text = String::new();
+    for (name, column) in &series.metrics {
+        text.push_str(&format!("# TYPE {name} gauge\n"));
+        for (millis, labels, row) in &series.samples {
+            if let Some(value) = number(&rows[*row][*column]) {
+                let seconds = *millis as f64 / 1000.0;
+                text.push_str(&format!("{name}{labels} {value} {seconds}\n"));
+            }
+        }
+    }
+    text.push_str("# EOF\n");
+    Ok(text)
+}
+
+/// Push the latest bucket of every series to the Pushgateway; returns the
+/// number of samples sent. The gateway rejects timestamps, so none are sent.
+pub async fn push(
+    config: MetricsConfig,
+    table: ExportTable,
+    query: Option<String>,
+) -> Result<usize, String> {
+    let Some(url) = config.resolved_pushgateway_url() else {
+        return Err("No Pushgateway configured; set metrics.pushgateway_url".into());
+    };
+    let rows = table.rows.into_vec();
+    let series = series(&table.headers, &rows, query.as_deref())?;
+    let mut body = String::new();
+    let mut count = 0;
+    for (name, column) in &series.metrics {
+        let mut latest: BTreeMap<&str, (i64, f64)> = BTreeMap::new();
+        for (millis, labels, row) in &series.samples {
+            let Some(value) = number(&rows[*row][*column]) else {
+                continue;
+            };
+            let entry = latest.entry(labels.as_str()).or_insert((*millis, value));
+            if *millis >= entry.0 {
+                *entry = (*millis, value);
+            }
+        }
+        body.push_str(&format!("# TYPE {name} gauge\n"));
+        for (labels, (_, value)) in latest {
+            body.push_str(&format!("{name}{labels} {value}\n"));
+            count += 1;
+        }
+    }
+    let endpoint = format!(
+        "{}/metrics/job/{}",
+        url.trim_end_matches('/'),
+        config.job.trim()
+    );
+    let client = reqwest::Client::builder()
+        .timeout(PUSH_TIMEOUT)
+        .build()
+        .map_err(|err| format!("Unable to create HTTP client: {err}"))?;
+    let response = client
+        .post(endpoint)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| format!("Pushgateway request failed: {err}"))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(count)
+    } else {
+        Err(format!("Pushgateway responded with {status}"))
+    }
+}
+
+/// Find the `bin(...)` column, the numeric columns, and the label columns:
+/// the `by` fields of the query, even numeric ones such as a status code.
+/// Without the query, non-numeric columns are taken as labels.
+/// Stats results are small, so their rows are taken in memory.
+fn series(headers: &[String], rows: &[Vec<String>], query: Option<&str>) -> Result<Series, String> {
+    let bucket = headers
+        .iter()
+        .position(|header| header.trim().to_ascii_lowercase().starts_with("bin("))
+        .ok_or("Metrics need time-bucketed results: a stats query with `by bin(...)`")?;
+    let groups = query.and_then(drill_down::group_columns);
+    let mut metrics = Vec::new();
+    let mut labels = Vec::new();
+    for (column, header) in headers.iter().enumerate() {
+        if column == bucket {
+            continue;
+        }
+        if let Some(groups) = groups.as_ref() {
+            if groups.contains(header) {
+                labels.push((label_name(header), column));
+                continue;
+            }
+        }
+        let mut values = rows
+            .iter()
+            .map(|row| row.get(column).map_or("", String::as_str))
+            .filter(|value| !value.trim().is_empty())
+            .peekable();
+        if values.peek().is_some() && values.all(|value| number(value).is_some()) {
+            metrics.push((metric_name(header), column));
+        } else {
+            labels.push((label_name(header), column));
+        }
+    }
+    if metrics.is_empty() {
+        return Err("The results have no numeric columns to turn into metrics".into());
+    }
+    let mut samples = rows
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, row)| {
+            let millis = parse_timestamp(row.get(bucket)?)?
+                .and_utc()
+                .timestamp_millis();
+            let rendered: Vec<String> = labels
+                .iter()
+                .map(|(name, column)| {
+                    let value = row.get(*column).map_or("", String::as_str);
+                    format!("{name}=\"{}\"", escape_label(value))
+                })
+                .collect();
+            let rendered = if rendered.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", rendered.join(","))
+            };
+            Some((millis, rendered, idx))
+        })
+        .collect::<Vec<_>>();
+    samples.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    Ok(Series { metrics, samples })
+}
+
+fn number(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+}
+
+/// `count(*)` → `awslogs_count`, `avg(@duration)` → `awslogs_avg_duration`.
+fn metric_name(header: &str) -> String {
+    let name = sanitize(&header.replace("(*)", ""));
+    format!(
+        "{METRIC_PREFIX}{}",
+        if name.is_empty() { "value" } else { &name }
+    )
+}
+
+fn label_name(header: &str) -> String {
+    let name = sanitize(header);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("label_{name}")
+    } else {
+        name
+    }
+}
+
+/// Lowercase, with runs of anything but letters and digits turned into `_`.
+fn sanitize(text: &str) -> String {
+    let mut name = String::new();
+    for ch in text.chars() {
+        if ch.is_ascii_alphanumeric() {
+            name.push(ch.to_ascii_lowercase());
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name.trim_matches('_').to_string()
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
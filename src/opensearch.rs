@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+
+use crate::config::OpenSearchConfig;
+use crate::export::{parse_timestamp, ExportTable};
+
+const BULK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the indexed rows came from; stored on every document.
+pub struct IndexSource {
+    pub log_group: String,
+    pub region: String,
+    pub query: String,
+}
+
+/// Bulk-index every row as a document; returns how many were indexed.
+pub async fn bulk_index(
+    config: OpenSearchConfig,
+    table: ExportTable,
+    source: IndexSource,
+) -> Result<usize, String> {
+    let Some(url) = config
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    else {
+        return Err("No OpenSearch endpoint configured; set opensearch.url".into());
+    };
+    let endpoint = format!("{}/_bulk", url.trim_end_matches('/'));
+    let client = reqwest::Client::builder()
+        .timeout(BULK_TIMEOUT)
+        .build()
+        .map_err(|err| format!("Unable to create HTTP client: {err}"))?;
+    let action = json!({ "index": { "_index": config.index } }).to_string();
+    let mut indexed = 0;
+    let mut failed = 0;
+    let mut first_error = None;
+    let mut rows = table.rows.iter().peekable();
+    while rows.peek().is_some() {
+        let mut body = String::new();
+        let mut documents = 0;
+        for row in rows.by_ref().take(config.batch_size.max(1)) {
+            documents += 1;
+            body.push_str(&action);
+            body.push('\n');
+            body.push_str(&document(&table.headers, &row, &source).to_string());
+            body.push('\n');
+        }
+        let mut request = client
+            .post(&endpoint)
+            .header("Content-Type", "application/x-ndjson")
+            .body(body);
+        if let Some(key) = config.resolved_api_key() {
+            request = request.header("Authorization", format!("ApiKey {}", key.trim()));
+        } else if let Some(user) = config.username.as_deref() {
+            request = request.basic_auth(user, config.resolved_password());
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|err| format!("OpenSearch request failed: {err}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!(
+                "OpenSearch responded with {status} after {indexed} documents"
+            ));
+        }
+        let reply: Value = response
+            .json()
+            .await
+            .map_err(|err| format!("Unreadable OpenSearch response: {err}"))?;
+        let errors = item_errors(&reply);
+        failed += errors.len();
+        indexed += documents - errors.len();
+        if first_error.is_none() {
+            first_error = errors.into_iter().next();
+        }
+    }
+    match first_error {
+        Some(reason) => Err(format!(
+            "Indexed {indexed} documents, {failed} rejected: {reason}"
+        )),
+        None => Ok(indexed),
+    }
+}
+
+/// One result row as a JSON document; `@timestamp` becomes ISO 8601 so it maps as a date.
+fn document(headers: &[String], row: &[String], source: &IndexSource) -> Value {
+    let mut fields = Map::new();
+    for (header, value) in headers.iter().zip(row) {
+        let value = match parse_timestamp(value).filter(|_| header == "@timestamp") {
+            Some(parsed) => parsed.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            None => value.clone(),
+        };
+        fields.insert(header.clone(), Value::String(value));
+    }
+    fields.insert(
+        "awslogs".to_string(),
+        json!({
+            "log_group": source.log_group,
+            "region": source.region,
+            "query": source.query,
+        }),
+    );
+    Value::Object(fields)
+}
+
+/// Reasons given for the documents the bulk request rejected.
+fn item_errors(reply: &Value) -> Vec<String> {
+    if reply.get("errors").and_then(Value::as_bool) != Some(true) {
+        return Vec::new();
+    }
+    reply
+        .get("items")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let error = item.get("index")?.get("error")?;
+            Some(
+                error
+                    .get("reason")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| error.to_string(), str::to_string),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,330 @@
+use std::cmp::Reverse;
+
+use tui_input::Input as SingleLineInput;
+
+/// Everything reachable from the command palette (Ctrl+K).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAction {
+    RunQuery,
+    RefreshQuery,
+    OpenQuery,
+    SaveQuery,
+    ToggleColumns,
+    FilterResults,
+    ExportResults,
+    ExportToS3,
+    ShareResults,
+    IndexToOpenSearch,
+    PushMetrics,
+    CopyConsoleLink,
+    SaveSnapshot,
+    OpenSnapshot,
+    FocusRegion,
+    FocusProfile,
+    ApplyProfile,
+    SwitchEnvironment,
+    NewWorkspace,
+    NextWorkspace,
+    RenameWorkspace,
+    CloseWorkspace,
+    FocusTimeRange,
+    FocusLogGroup,
+    FocusQuery,
+    FocusResults,
+    ZoomToMarked,
+    EditRowNote,
+    ToggleDurations,
+    ColumnStats,
+    MessageClusters,
+    PushFilterToQuery,
+    SetBaseline,
+    ClearBaseline,
+    AddComputedColumn,
+    EditVariables,
+    PickFieldValues,
+    ShowCloudTrail,
+    ExplainRow,
+    ToggleScratchpad,
+    TableImage,
+    ToggleSampling,
+    ToggleTimeMode,
+    CycleQueryEngine,
+    ShowQueryTrace,
+    ShowQueryDiff,
+    ChooseInputPanes,
+    ZoomQueryEditor,
+    CollapseInputs,
+    ExpandInputs,
+    ShowHelp,
+    Quit,
+}
+
+impl PaletteAction {
+    pub const ALL: [PaletteAction; 52] = [
+        Self::RunQuery,
+        Self::RefreshQuery,
+        Self::OpenQuery,
+        Self::SaveQuery,
+        Self::ToggleColumns,
+        Self::FilterResults,
+        Self::ExportResults,
+        Self::ExportToS3,
+        Self::ShareResults,
+        Self::IndexToOpenSearch,
+        Self::PushMetrics,
+        Self::CopyConsoleLink,
+        Self::SaveSnapshot,
+        Self::OpenSnapshot,
+        Self::FocusRegion,
+        Self::FocusProfile,
+        Self::ApplyProfile,
+        Self::SwitchEnvironment,
+        Self::NewWorkspace,
+        Self::NextWorkspace,
+        Self::RenameWorkspace,
+        Self::CloseWorkspace,
+        Self::FocusTimeRange,
+        Self::FocusLogGroup,
+        Self::FocusQuery,
+        Self::FocusResults,
+        Self::ZoomToMarked,
+        Self::EditRowNote,
+        Self::ToggleDurations,
+        Self::ColumnStats,
+        Self::MessageClusters,
+        Self::PushFilterToQuery,
+        Self::SetBaseline,
+        Self::ClearBaseline,
+        Self::AddComputedColumn,
+        Self::EditVariables,
+        Self::PickFieldValues,
+        Self::ShowCloudTrail,
+        Self::ExplainRow,
+        Self::ToggleScratchpad,
+        Self::TableImage,
+        Self::ToggleSampling,
+        Self::ToggleTimeMode,
+        Self::CycleQueryEngine,
+        Self::ShowQueryTrace,
+        Self::ShowQueryDiff,
+        Self::ChooseInputPanes,
+        Self::ZoomQueryEditor,
+        Self::CollapseInputs,
+        Self::ExpandInputs,
+        Self::ShowHelp,
+        Self::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RunQuery => "Run query",
+            Self::RefreshQuery => "Re-run query, skipping cached results",
+            Self::OpenQuery => "Open saved query",
+            Self::SaveQuery => "Save query",
+            Self::ToggleColumns => "Show/hide columns",
+            Self::FilterResults => "Filter results",
+            Self::ExportResults => "Export results to file",
+            Self::ExportToS3 => "Export log group to S3",
+            Self::ShareResults => "Share results via webhook",
+            Self::IndexToOpenSearch => "Index results into OpenSearch/Elasticsearch",
+            Self::PushMetrics => "Push stats series (by bin) to the Prometheus Pushgateway",
+            Self::CopyConsoleLink => "Copy AWS console link",
+            Self::SaveSnapshot => "Save results snapshot",
+            Self::OpenSnapshot => "Open results snapshot",
+            Self::FocusRegion => "Switch region",
+            Self::FocusProfile => "Switch AWS profile",
+            Self::ApplyProfile => "Apply the selected AWS profile and check its credentials",
+            Self::SwitchEnvironment => "Switch environment preset",
+            Self::NewWorkspace => "New workspace tab",
+            Self::NextWorkspace => "Next workspace tab",
+            Self::RenameWorkspace => "Rename workspace tab",
+            Self::CloseWorkspace => "Close workspace tab",
+            Self::FocusTimeRange => "Change time range",
+            Self::FocusLogGroup => "Edit log group",
+            Self::FocusQuery => "Edit query",
+            Self::FocusResults => "Go to results",
+            Self::ZoomToMarked => "Zoom time range to marked rows",
+            Self::EditRowNote => "Add or edit a note on the selected row",
+            Self::ToggleDurations => "Toggle readable durations (12500 → 12.5 s)",
+            Self::ColumnStats => "Show column statistics for the filtered rows",
+            Self::MessageClusters => "Group similar messages (numbers and IDs masked)",
+            Self::PushFilterToQuery => {
+                "Apply the filter server-side (add it to the query and re-run)"
+            }
+            Self::SetBaseline => {
+                "Use these results as the baseline (highlight new message patterns)"
+            }
+            Self::ClearBaseline => "Clear the baseline",
+            Self::AddComputedColumn => "Add a computed column (Name = expression)",
+            Self::EditVariables => "Edit workspace variables ({{name}} in the query)",
+            Self::PickFieldValues => "Pick field values to filter on (samples the last hour)",
+            Self::ShowCloudTrail => "Show CloudTrail changes for the same window",
+            Self::ExplainRow => "Explain the selected row's error",
+            Self::ToggleScratchpad => "Show or hide the workspace scratchpad",
+            Self::TableImage => "Save the visible table as a PNG and copy it",
+            Self::ToggleSampling => "Toggle sampled results (1 in N rows) / full view",
+            Self::ToggleTimeMode => "Toggle relative/absolute time",
+            Self::CycleQueryEngine => "Switch query engine (auto/Insights/FilterLogEvents)",
+            Self::ShowQueryTrace => "Show the AWS calls made by the last query",
+            Self::ShowQueryDiff => "Show diff of the query against its saved file",
+            Self::ChooseInputPanes => "Show or hide input panes",
+            Self::ZoomQueryEditor => "Zoom the query editor to the full screen",
+            Self::CollapseInputs => "Collapse input panes",
+            Self::ExpandInputs => "Restore input panes",
+            Self::ShowHelp => "Show keyboard shortcuts",
+            Self::Quit => "Quit",
+        }
+    }
+
+    /// Existing keybinding, shown next to the label so the palette teaches shortcuts.
+    pub fn shortcut(self) -> Option<&'static str> {
+        match self {
+            Self::RunQuery => Some("Ctrl+R"),
+            Self::RefreshQuery => Some("Shift+F5"),
+            Self::OpenQuery => Some("Ctrl+O"),
+            Self::SaveQuery => Some("Ctrl+S"),
+            Self::ToggleColumns => Some("h"),
+            Self::FilterResults => Some("Alt+F"),
+            Self::ExportResults => Some("Ctrl+E"),
+            Self::ExportToS3 => Some("Ctrl+Shift+E"),
+            Self::ShareResults => Some("Ctrl+W"),
+            Self::CopyConsoleLink => Some("Ctrl+L"),
+            Self::SaveSnapshot => Some("Ctrl+Shift+S"),
+            Self::OpenSnapshot => Some("Ctrl+Shift+O"),
+            Self::FocusRegion => Some("Alt+E"),
+            Self::SwitchEnvironment => Some("Ctrl+P"),
+            Self::NewWorkspace => Some("Ctrl+T"),
+            Self::NextWorkspace => Some("Ctrl+PgDn"),
+            Self::RenameWorkspace => Some("F2"),
+            Self::CloseWorkspace => Some("Ctrl+F4"),
+            Self::FocusTimeRange => Some("Alt+T"),
+            Self::FocusLogGroup => Some("Alt+G"),
+            Self::FocusQuery => Some("q"),
+            Self::FocusResults => Some("r"),
+            Self::ZoomToMarked => Some("z"),
+            Self::EditRowNote => Some("n"),
+            Self::ToggleScratchpad => Some("F3"),
+            Self::TableImage => Some("i"),
+            Self::ToggleSampling => Some("d"),
+            Self::ColumnStats => Some("s"),
+            Self::MessageClusters => Some("g"),
+            Self::PushFilterToQuery => Some("Ctrl+F"),
+            Self::ZoomQueryEditor => Some("F11"),
+            Self::CollapseInputs => Some("Ctrl+Up"),
+            Self::ExpandInputs => Some("Ctrl+Down"),
+            Self::ShowHelp => Some("Ctrl+H"),
+            Self::Quit => Some("Ctrl+Q"),
+            Self::FocusProfile
+            | Self::ToggleTimeMode
+            | Self::CycleQueryEngine
+            | Self::ShowQueryTrace
+            | Self::ShowQueryDiff
+            | Self::ApplyProfile
+            | Self::ToggleDurations
+            | Self::PickFieldValues
+            | Self::ShowCloudTrail
+            | Self::ExplainRow
+            | Self::ChooseInputPanes
+            | Self::SetBaseline
+            | Self::ClearBaseline
+            | Self::AddComputedColumn
+            | Self::IndexToOpenSearch
+            | Self::PushMetrics
+            | Self::EditVariables => None,
+        }
+    }
+}
+
+pub struct PaletteState {
+    pub filter_input: SingleLineInput,
+    pub matches: Vec<PaletteAction>,
+    pub selected: usize,
+    pub scroll: usize,
+}
+
+impl PaletteState {
+    pub fn new() -> Self {
+        let mut state = Self {
+            filter_input: SingleLineInput::new(String::new()),
+            matches: Vec::new(),
+            selected: 0,
+            scroll: 0,
+        };
+        state.apply_filter();
+        state
+    }
+
+    pub fn apply_filter(&mut self) {
+        let needle = self.filter_input.value().trim().to_lowercase();
+        let mut scored: Vec<(i32, PaletteAction)> = PaletteAction::ALL
+            .iter()
+            .filter_map(|&action| fuzzy_score(&needle, action.label()).map(|s| (s, action)))
+            .collect();
+        // Stable sort keeps the declaration order for equal scores.
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        self.matches = scored.into_iter().map(|(_, action)| action).collect();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() as i32 - 1;
+        self.selected = (self.selected as i32 + delta).clamp(0, last) as usize;
+    }
+
+    pub fn selected_action(&self) -> Option<PaletteAction> {
+        self.matches.get(self.selected).copied()
+    }
+
+    pub fn visible_bounds(&mut self, view_height: usize) -> (usize, usize) {
+        if view_height == 0 {
+            return (0, 0);
+        }
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + view_height {
+            self.scroll = self.selected + 1 - view_height;
+        }
+        let end = (self.scroll + view_height).min(self.matches.len());
+        (self.scroll, end)
+    }
+}
+
+/// Subsequence match: every needle character must appear in order. Consecutive
+/// characters and matches at word starts score higher.
+fn fuzzy_score(needle: &str, haystack: &str) -> Option<i32> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    let haystack = haystack.to_lowercase();
+    let mut score = 0;
+    let mut previous_match: Option<usize> = None;
+    let mut chars = haystack.char_indices();
+    for wanted in needle.chars().filter(|c| !c.is_whitespace()) {
+        let mut found = None;
+        for (idx, c) in chars.by_ref() {
+            if c == wanted {
+                found = Some(idx);
+                break;
+            }
+        }
+        let idx = found?;
+        let word_start = idx == 0
+            || haystack[..idx]
+                .chars()
+                .last()
+                .is_some_and(|c| !c.is_alphanumeric());
+        if word_start {
+            score += 8;
+        }
+        if previous_match.is_some_and(|prev| idx == prev + 1) {
+            score += 5;
+        }
+        score -= idx as i32 / 8;
+        previous_match = Some(idx);
+    }
+    Some(score)
+}
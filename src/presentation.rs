@@ -1,68 +1,88 @@
 use crate::log_fetcher::LogField;
 
-#[derive(Default)]
+/// Cells larger than this show a preview in the table instead of their full text.
+pub const LARGE_CELL_BYTES: usize = 2 * 1024;
+/// Characters of a large cell kept for its table preview; wider than any column.
+const PREVIEW_CHARS: usize = 240;
+
+#[derive(Clone, Default)]
 pub struct FormattedResults {
     pub headers: Vec<String>,
     pub rows: Vec<Vec<String>>,
 }
 
-pub fn format_results(results: &[Vec<LogField>]) -> FormattedResults {
-    if results.is_empty() {
-        return FormattedResults::default();
+pub fn format_results(results: Vec<Vec<LogField>>) -> FormattedResults {
+    let (headers, rows) = stream_results(results);
+    FormattedResults {
+        headers,
+        rows: rows.collect(),
     }
-    let mut headers: Vec<String> = Vec::new();
-    let mut formatted_rows: Vec<Vec<String>> = Vec::new();
-
-    for row in results {
-        let mut current_row: Vec<String> = Vec::new();
-        let mut column_index = 0usize;
+}
 
-        for field in row {
+/// Headers of the results, and their rows' cells one at a time as they are
+/// taken, so the rows can be stored (or spilled) without a formatted copy
+/// of them all. Field values are moved, not copied.
+pub fn stream_results(
+    results: Vec<Vec<LogField>>,
+) -> (Vec<String>, impl Iterator<Item = Vec<String>>) {
+    let shown = |field: &LogField| field.name.as_deref() != Some("@ptr");
+    let mut headers: Vec<String> = Vec::new();
+    for row in &results {
+        for (column_index, field) in row.iter().filter(|field| shown(field)).enumerate() {
             let label = field.name.as_deref().unwrap_or_default();
-            if label == "@ptr" {
-                continue;
-            }
-
             if headers.len() <= column_index {
-                let column_name = if label.is_empty() {
+                headers.push(if label.is_empty() {
                     format!("Column {}", column_index + 1)
                 } else {
                     label.to_string()
-                };
-                headers.push(column_name);
-                for existing_row in &mut formatted_rows {
-                    existing_row.push(String::new());
-                }
+                });
             } else if !label.is_empty() {
                 headers[column_index] = label.to_string();
             }
-
-            current_row.push(field.value.clone());
-            column_index += 1;
-        }
-
-        if column_index == 0 {
-            continue;
         }
-
-        if !headers.is_empty() {
-            current_row.resize(headers.len(), String::new());
-        }
-
-        formatted_rows.push(current_row);
     }
+    let width = headers.len();
+    let rows = results.into_iter().filter_map(move |row| {
+        let mut cells: Vec<String> = row
+            .into_iter()
+            .filter(shown)
+            .map(|field| field.value)
+            .collect();
+        if cells.is_empty() {
+            return None;
+        }
+        cells.resize(width, String::new());
+        Some(cells)
+    });
+    (headers, rows)
+}
 
-    for row in &mut formatted_rows {
-        row.resize(headers.len(), String::new());
+/// First line of a large cell, cut to `PREVIEW_CHARS`, and how much is left
+/// out, e.g. `{"level":"info",... (+8.4 KB)`. `None` for cells that fit.
+pub fn large_cell_preview(value: &str) -> Option<String> {
+    if value.len() <= LARGE_CELL_BYTES {
+        return None;
     }
+    let first_line = value.lines().next().unwrap_or_default();
+    let cut = first_line
+        .char_indices()
+        .nth(PREVIEW_CHARS)
+        .map_or(first_line.len(), |(idx, _)| idx);
+    let hidden = value.len() - cut;
+    Some(format!(
+        "{}… (+{})",
+        &first_line[..cut],
+        format_size(hidden)
+    ))
+}
 
-    if formatted_rows.is_empty() {
-        FormattedResults::default()
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= KB * KB {
+        format!("{:.1} MB", bytes / (KB * KB))
     } else {
-        FormattedResults {
-            headers,
-            rows: formatted_rows,
-        }
+        format!("{:.1} KB", bytes / KB)
     }
 }
 
@@ -86,6 +106,95 @@ pub fn format_modal_message(value: &str) -> Vec<String> {
     format_modal_value(value)
 }
 
+/// Piece of a JSON line, for syntax highlighting in the detail modal.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum JsonToken {
+    Key,
+    String,
+    Number,
+    /// `true`, `false`, or `null`.
+    Literal,
+    Punctuation,
+    Plain,
+}
+
+/// Whether a cell holds a JSON object or array and should be highlighted.
+pub fn looks_like_json(value: &str) -> bool {
+    let trimmed = value.trim_start();
+    trimmed.starts_with('{') || trimmed.starts_with('[')
+}
+
+/// Split one line of pretty-printed JSON into highlighted pieces. A string
+/// is a key when the next non-space character is `:`.
+pub fn json_tokens(line: &str) -> Vec<(JsonToken, &str)> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let start = idx;
+        let token = match bytes[idx] {
+            b'"' => {
+                idx += 1;
+                while idx < bytes.len() && bytes[idx] != b'"' {
+                    idx += if bytes[idx] == b'\\' { 2 } else { 1 };
+                }
+                idx = (idx + 1).min(bytes.len());
+                let is_key = line[idx..].trim_start().starts_with(':');
+                if is_key {
+                    JsonToken::Key
+                } else {
+                    JsonToken::String
+                }
+            }
+            b'-' | b'0'..=b'9' => {
+                idx += 1;
+                while idx < bytes.len()
+                    && matches!(bytes[idx], b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')
+                {
+                    idx += 1;
+                }
+                JsonToken::Number
+            }
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => {
+                idx += 1;
+                JsonToken::Punctuation
+            }
+            c if c.is_ascii_alphabetic() => {
+                while idx < bytes.len() && bytes[idx].is_ascii_alphabetic() {
+                    idx += 1;
+                }
+                if matches!(&line[start..idx], "true" | "false" | "null") {
+                    JsonToken::Literal
+                } else {
+                    JsonToken::Plain
+                }
+            }
+            _ => {
+                // Whitespace and anything unexpected, up to the next interesting byte.
+                idx += 1;
+                while idx < bytes.len()
+                    && !bytes[idx].is_ascii_alphanumeric()
+                    && !matches!(
+                        bytes[idx],
+                        b'"' | b'-' | b'{' | b'}' | b'[' | b']' | b':' | b','
+                    )
+                {
+                    idx += 1;
+                }
+                JsonToken::Plain
+            }
+        };
+        // A trailing `\` escape can step past the end; keep slicing on a char boundary.
+        let end = idx.min(bytes.len());
+        let end = (end..=bytes.len())
+            .find(|&pos| line.is_char_boundary(pos))
+            .unwrap_or(bytes.len());
+        idx = end;
+        tokens.push((token, &line[start..end]));
+    }
+    tokens
+}
+
 fn try_pretty_json(raw: &str) -> Option<String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -0,0 +1,128 @@
+/// Unchanged lines kept around each change, as `diff -u` does.
+const CONTEXT: usize = 3;
+
+pub enum DiffLine {
+    /// `@@ -a,b +c,d @@` header opening a hunk.
+    Hunk(String),
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+/// One step of the diff: its line in the old text and in the new one,
+/// `None` on the side that lacks it.
+type Edit = (Option<usize>, Option<usize>);
+
+/// The query buffer compared with the saved file it was loaded from.
+pub struct QueryDiff {
+    pub file: String,
+    /// The file as it is on disk, put back by reverting.
+    pub saved: String,
+    pub lines: Vec<DiffLine>,
+    pub scroll: usize,
+}
+
+impl QueryDiff {
+    pub fn new(file: String, saved: String, buffer: &str) -> Self {
+        let lines = unified(&saved, buffer);
+        Self {
+            file,
+            saved,
+            lines,
+            scroll: 0,
+        }
+    }
+
+    pub fn added(&self) -> usize {
+        self.count(|line| matches!(line, DiffLine::Added(_)))
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(|line| matches!(line, DiffLine::Removed(_)))
+    }
+
+    fn count(&self, test: fn(&DiffLine) -> bool) -> usize {
+        self.lines.iter().filter(|line| test(line)).count()
+    }
+}
+
+/// A unified diff from `old` to `new`; empty when they hold the same lines.
+pub fn unified(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.trim_end().lines().collect();
+    let new: Vec<&str> = new.trim_end().lines().collect();
+
+    // Longest common subsequence table, filled from the end so the walk
+    // below can go forwards.
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    // Removals come before the additions that replace them.
+    let mut edits: Vec<Edit> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push((Some(i), Some(j)));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            edits.push((Some(i), None));
+            i += 1;
+        } else {
+            edits.push((None, Some(j)));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| edit.0.is_none() || edit.1.is_none())
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < changed.len() {
+        // Changes closer than twice the context share one hunk.
+        let mut end = start;
+        while end + 1 < changed.len() && changed[end + 1] - changed[end] <= CONTEXT * 2 + 1 {
+            end += 1;
+        }
+        let from = changed[start].saturating_sub(CONTEXT);
+        let to = (changed[end] + CONTEXT + 1).min(edits.len());
+        let hunk = &edits[from..to];
+        // Where the hunk starts on each side: its first line there, or the
+        // line before when it has none, as `diff -u` counts.
+        let origin = |side: fn(&Edit) -> Option<usize>| {
+            let count = hunk.iter().filter(|edit| side(edit).is_some()).count();
+            let first = hunk.iter().find_map(side).map(|line| line + 1);
+            let before = edits[..from]
+                .iter()
+                .filter(|edit| side(edit).is_some())
+                .count();
+            (first.unwrap_or(before), count)
+        };
+        let (old_start, old_count) = origin(|edit| edit.0);
+        let (new_start, new_count) = origin(|edit| edit.1);
+        lines.push(DiffLine::Hunk(format!(
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@"
+        )));
+        for edit in hunk {
+            lines.push(match *edit {
+                (Some(line), Some(_)) => DiffLine::Context(old[line].to_string()),
+                (Some(line), None) => DiffLine::Removed(old[line].to_string()),
+                (None, Some(line)) => DiffLine::Added(new[line].to_string()),
+                (None, None) => continue,
+            });
+        }
+        start = end + 1;
+    }
+    lines
+}
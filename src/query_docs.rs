@@ -0,0 +1,15 @@
+/// Logs Insights commands and functions, one `name<TAB>signature<TAB>description` per line.
+const REFERENCE: &str = include_str!("../assets/insights_reference.tsv");
+
+/// `signature — description` for a command or function name, matched case-insensitively.
+pub fn describe(word: &str) -> Option<String> {
+    REFERENCE
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            Some((parts.next()?, parts.next()?, parts.next()?))
+        })
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(word))
+        .map(|(_, signature, description)| format!("{signature} — {description}"))
+}
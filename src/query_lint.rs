@@ -0,0 +1,71 @@
+/// Logs Insights refuses limits above this; asking for all of it is rarely intended.
+const MAX_LIMIT: u64 = 10_000;
+/// A maxed-out limit is only worth flagging once the range is longer than this.
+const LONG_RANGE_DAYS: f64 = 1.0;
+
+/// Hints about patterns that make a query slow or expensive, in query order.
+/// `range_days` is the length of the chosen time range when it can be worked out.
+pub fn lint(query: &str, range_days: Option<f64>) -> Vec<String> {
+    let mut hints = Vec::new();
+    let mut seen_filter = false;
+    let mut flagged_stats = false;
+    for stage in query.split(['|', '\n']).map(str::trim) {
+        let (command, rest) = stage
+            .split_once(char::is_whitespace)
+            .map(|(command, rest)| (command, rest.trim()))
+            .unwrap_or((stage, ""));
+        match command.to_ascii_lowercase().as_str() {
+            "filter" => {
+                seen_filter = true;
+                if let Some(regex) = leading_wildcard_regex(rest) {
+                    hints.push(format!(
+                        "/{regex}/ starts with a wildcard; patterns already match anywhere, so drop the leading .*"
+                    ));
+                }
+            }
+            "stats" if !seen_filter && !flagged_stats => {
+                flagged_stats = true;
+                hints.push(
+                    "stats runs over every event in the range; add a filter before it".to_string(),
+                );
+            }
+            "limit" => {
+                if let (Ok(limit), Some(days)) = (rest.parse::<u64>(), range_days) {
+                    if limit >= MAX_LIMIT && days > LONG_RANGE_DAYS {
+                        hints.push(format!(
+                            "limit {limit} over {days:.1} days scans and returns a lot; narrow the range or lower the limit"
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    hints
+}
+
+/// Body of the first `like /.*.../` or `=~ /.*.../` regex in a filter expression.
+fn leading_wildcard_regex(expression: &str) -> Option<&str> {
+    let lower = expression.to_ascii_lowercase();
+    let mut search_from = 0;
+    while let Some(offset) = lower[search_from..].find(['l', '=']) {
+        let at = search_from + offset;
+        search_from = at + 1;
+        let operator_len = if lower[at..].starts_with("like") {
+            4
+        } else if lower[at..].starts_with("=~") {
+            2
+        } else {
+            continue;
+        };
+        let after = expression[at + operator_len..].trim_start();
+        let Some(body) = after.strip_prefix('/') else {
+            continue;
+        };
+        let body = body.split_once('/').map_or(body, |(body, _)| body);
+        if body.starts_with(".*") || body.starts_with(".+") {
+            return Some(body);
+        }
+    }
+    None
+}
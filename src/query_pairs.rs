@@ -0,0 +1,135 @@
+use tui_textarea::{CursorMove, TextArea};
+
+const PAIRS: [(char, char); 2] = [('(', ')'), ('[', ']')];
+
+/// Auto-pair brackets and double quotes as they are typed in the query editor;
+/// returns true when the keystroke was handled here.
+pub fn type_char(area: &mut TextArea, ch: char) -> bool {
+    let (before, after) = around_cursor(area);
+    if let Some(&(_, close)) = PAIRS.iter().find(|(open, _)| *open == ch) {
+        if after.is_some_and(|next| !next.is_whitespace() && !is_closer(next)) {
+            return false;
+        }
+        area.insert_char(ch);
+        area.insert_char(close);
+        area.move_cursor(CursorMove::Back);
+        return true;
+    }
+    if is_closer(ch) || ch == '"' {
+        if after == Some(ch) {
+            // Type over the closer inserted with its opener.
+            area.move_cursor(CursorMove::Forward);
+            return true;
+        }
+        if ch != '"' || before.is_some_and(|prev| prev.is_alphanumeric() || prev == '\\') {
+            return false;
+        }
+        area.insert_char('"');
+        area.insert_char('"');
+        area.move_cursor(CursorMove::Back);
+        return true;
+    }
+    false
+}
+
+/// Backspace between an empty pair removes both halves.
+pub fn backspace(area: &mut TextArea) -> bool {
+    let (Some(before), Some(after)) = around_cursor(area) else {
+        return false;
+    };
+    let empty_pair = PAIRS.contains(&(before, after)) || (before == '"' && after == '"');
+    if empty_pair {
+        area.delete_next_char();
+        area.delete_char();
+    }
+    empty_pair
+}
+
+fn is_closer(ch: char) -> bool {
+    PAIRS.iter().any(|(_, close)| *close == ch)
+}
+
+fn around_cursor(area: &TextArea) -> (Option<char>, Option<char>) {
+    let (row, col) = area.cursor();
+    let Some(line) = area.lines().get(row) else {
+        return (None, None);
+    };
+    let before = col.checked_sub(1).and_then(|idx| line.chars().nth(idx));
+    (before, line.chars().nth(col))
+}
+
+/// Positions `(row, col)` of the bracket at or just before the cursor and its
+/// partner. Brackets inside quotes and regex literals are not counted.
+pub fn matching_bracket(lines: &[String], cursor: (usize, usize)) -> Option<[(usize, usize); 2]> {
+    let brackets = code_brackets(lines);
+    let (row, col) = cursor;
+    let here = brackets
+        .iter()
+        .position(|&(r, c, _)| r == row && c == col)
+        .or_else(|| {
+            brackets
+                .iter()
+                .position(|&(r, c, _)| r == row && c + 1 == col)
+        })?;
+    let (_, _, ch) = brackets[here];
+    let forward = PAIRS.iter().any(|(open, _)| *open == ch);
+    let (open, close) = *PAIRS
+        .iter()
+        .find(|(open, close)| *open == ch || *close == ch)?;
+    let candidates: Vec<(usize, usize, char)> = if forward {
+        brackets[here..].to_vec()
+    } else {
+        brackets[..=here].iter().rev().copied().collect()
+    };
+    let (same, other) = if forward {
+        (open, close)
+    } else {
+        (close, open)
+    };
+    let mut depth = 0usize;
+    for (r, c, bracket) in candidates {
+        if bracket == same {
+            depth += 1;
+        } else if bracket == other {
+            depth -= 1;
+            if depth == 0 {
+                return Some([(row, brackets[here].1), (r, c)]);
+            }
+        }
+    }
+    None
+}
+
+/// Every bracket outside comments and string or regex literals, in text order.
+fn code_brackets(lines: &[String]) -> Vec<(usize, usize, char)> {
+    let mut brackets = Vec::new();
+    for (row, line) in lines.iter().enumerate() {
+        let mut literal: Option<char> = None;
+        let mut escaped = false;
+        for (col, (byte, ch)) in line.char_indices().enumerate() {
+            match literal {
+                Some(_) if escaped => escaped = false,
+                Some(_) if ch == '\\' => escaped = true,
+                Some(end) if ch == end => literal = None,
+                Some(_) => {}
+                None if ch == '#' => break,
+                None if matches!(ch, '"' | '\'' | '`') => literal = Some(ch),
+                None if ch == '/' && starts_regex(&line[..byte]) => literal = Some('/'),
+                None if PAIRS
+                    .iter()
+                    .any(|(open, close)| *open == ch || *close == ch) =>
+                {
+                    brackets.push((row, col, ch));
+                }
+                None => {}
+            }
+        }
+    }
+    brackets
+}
+
+/// A slash opens a regex after `like`, `=~`, `(`, or `,`; elsewhere it divides.
+pub fn starts_regex(before: &str) -> bool {
+    let before = before.trim_end().to_ascii_lowercase();
+    before.ends_with("like") || before.ends_with("=~") || before.ends_with(['(', ','])
+}
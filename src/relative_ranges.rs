@@ -0,0 +1,108 @@
+/// A window ending now, offered in the relative range picker.
+pub struct RelativeRangeOption {
+    pub label: &'static str,
+    pub seconds: i64,
+}
+
+pub const fn minutes(value: i64) -> i64 {
+    value * 60
+}
+
+pub const fn hours(value: i64) -> i64 {
+    minutes(value * 60)
+}
+
+pub const fn days(value: i64) -> i64 {
+    hours(value * 24)
+}
+
+pub const RELATIVE_RANGE_OPTIONS: [RelativeRangeOption; 17] = [
+    RelativeRangeOption {
+        label: "1 minute",
+        seconds: minutes(1),
+    },
+    RelativeRangeOption {
+        label: "5 minutes",
+        seconds: minutes(5),
+    },
+    RelativeRangeOption {
+        label: "10 minutes",
+        seconds: minutes(10),
+    },
+    RelativeRangeOption {
+        label: "15 minutes",
+        seconds: minutes(15),
+    },
+    RelativeRangeOption {
+        label: "30 minutes",
+        seconds: minutes(30),
+    },
+    RelativeRangeOption {
+        label: "1 hour",
+        seconds: hours(1),
+    },
+    RelativeRangeOption {
+        label: "2 hours",
+        seconds: hours(2),
+    },
+    RelativeRangeOption {
+        label: "3 hours",
+        seconds: hours(3),
+    },
+    RelativeRangeOption {
+        label: "5 hours",
+        seconds: hours(5),
+    },
+    RelativeRangeOption {
+        label: "12 hours",
+        seconds: hours(12),
+    },
+    RelativeRangeOption {
+        label: "1 day",
+        seconds: days(1),
+    },
+    RelativeRangeOption {
+        label: "2 days",
+        seconds: days(2),
+    },
+    RelativeRangeOption {
+        label: "3 days",
+        seconds: days(3),
+    },
+    RelativeRangeOption {
+        label: "5 days",
+        seconds: days(5),
+    },
+    RelativeRangeOption {
+        label: "7 days",
+        seconds: days(7),
+    },
+    RelativeRangeOption {
+        label: "14 days",
+        seconds: days(14),
+    },
+    RelativeRangeOption {
+        label: "30 days",
+        seconds: days(30),
+    },
+];
+
+/// Index of the relative window written as a label (`15 minutes`) or
+/// shorthand (`15m`, `3h`, `7d`).
+pub fn relative_range_index(text: &str) -> Option<usize> {
+    let text = text.trim().to_ascii_lowercase();
+    let digits = text.find(|c: char| !c.is_ascii_digit())?;
+    let count: i64 = text[..digits].parse().ok()?;
+    let unit = match text[digits..].trim() {
+        "m" | "min" | "minute" | "minutes" => minutes(1),
+        "h" | "hour" | "hours" => hours(1),
+        "d" | "day" | "days" => days(1),
+        _ => return None,
+    };
+    RELATIVE_RANGE_OPTIONS
+        .iter()
+        .position(|option| option.seconds == count * unit)
+}
+
+/// Windows bound to Alt+1 … Alt+5.
+pub const QUICK_RELATIVE_RANGES: [i64; 5] = [minutes(15), hours(1), hours(3), days(1), days(7)];
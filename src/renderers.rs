@@ -0,0 +1,170 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::config::wildcard_match;
+
+/// Instructions a plugin may execute per row before it is stopped.
+const FUEL_PER_RENDER: u64 = 10_000_000;
+/// Linear memory a plugin may hold per row.
+const MEMORY_PER_RENDER: usize = 64 * 1024 * 1024;
+
+/// WebAssembly module that renders `@message` in the row detail, e.g.
+/// `path = "/home/me/.config/awslogs/vpc-flow.wasm"`, `log_groups = ["vpc-flow-*"]`.
+#[derive(Clone, Deserialize)]
+pub struct RendererConfig {
+    pub path: PathBuf,
+    /// Log groups the plugin is offered rows from; `*` matches any run of
+    /// characters. Empty offers every row and leaves the choice to the plugin.
+    #[serde(default)]
+    pub log_groups: Vec<String>,
+}
+
+/// Compiled renderer plugin. It exports `memory`, `alloc(len) -> ptr`, and
+/// `render(ptr, len) -> (out_ptr << 32) | out_len`: the input is a JSON
+/// object of `log_group` and the row's `fields`, the output UTF-8 text shown
+/// in place of the message. An empty output declines the row.
+pub struct RendererPlugin {
+    pub name: String,
+    log_groups: Vec<String>,
+    engine: Engine,
+    module: Module,
+}
+
+/// A plugin's rendering of one row, or the reason it failed.
+pub struct Rendered {
+    pub plugin: String,
+    pub lines: Result<Vec<String>, String>,
+}
+
+pub fn load(configs: &[RendererConfig]) -> (Vec<RendererPlugin>, Option<String>) {
+    let mut engine_config = Config::default();
+    engine_config.consume_fuel(true);
+    let engine = Engine::new(&engine_config);
+    let mut plugins = Vec::with_capacity(configs.len());
+    let mut warning = None;
+    for config in configs {
+        let path = &config.path;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let module = fs::read(path)
+            .map_err(|err| format!("unable to read {}: {err}", path.display()))
+            .and_then(|bytes| Module::new(&engine, &bytes).map_err(|err| err.to_string()));
+        match module {
+            Ok(module) => plugins.push(RendererPlugin {
+                name,
+                log_groups: config.log_groups.clone(),
+                engine: engine.clone(),
+                module,
+            }),
+            Err(err) => {
+                warning.get_or_insert_with(|| format!("Ignoring renderer {name}: {err}"));
+            }
+        }
+    }
+    (plugins, warning)
+}
+
+/// A row to offer the plugins, run off the UI thread since a plugin may
+/// take up to its fuel to answer.
+pub struct RenderRequest {
+    pub row: usize,
+    pub log_group: String,
+    pub fields: Vec<(String, String)>,
+    pub plugins: Arc<Vec<RendererPlugin>>,
+}
+
+impl RenderRequest {
+    pub fn run(self) -> (usize, Option<Rendered>) {
+        let rendered = render(&self.plugins, &self.log_group, &self.fields);
+        (self.row, rendered)
+    }
+}
+
+/// The row as rendered by the first plugin that takes it.
+fn render(
+    plugins: &[RendererPlugin],
+    log_group: &str,
+    fields: &[(String, String)],
+) -> Option<Rendered> {
+    let fields: Map<String, Value> = fields
+        .iter()
+        .map(|(header, value)| (header.clone(), Value::String(value.clone())))
+        .collect();
+    let input = json!({ "log_group": log_group, "fields": fields }).to_string();
+    plugins
+        .iter()
+        .filter(|plugin| plugin.accepts(log_group))
+        .find_map(|plugin| {
+            let lines = match plugin.call(&input) {
+                Ok(text) if text.trim().is_empty() => return None,
+                Ok(text) => Ok(text.lines().map(str::to_string).collect()),
+                Err(err) => Err(err),
+            };
+            Some(Rendered {
+                plugin: plugin.name.clone(),
+                lines,
+            })
+        })
+}
+
+impl RendererPlugin {
+    fn accepts(&self, log_group: &str) -> bool {
+        self.log_groups.is_empty()
+            || self
+                .log_groups
+                .iter()
+                .any(|pattern| wildcard_match(pattern, log_group))
+    }
+
+    /// Run `render` in a fresh instance so no state leaks between rows.
+    fn call(&self, input: &str) -> Result<String, String> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MEMORY_PER_RENDER)
+            .instances(1)
+            .memories(1)
+            .tables(1)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store
+            .set_fuel(FUEL_PER_RENDER)
+            .map_err(|err| err.to_string())?;
+        let instance = Linker::<StoreLimits>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|err| format!("unable to instantiate: {err}"))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or("the plugin exports no memory")?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|err| format!("alloc: {err}"))?;
+        let render = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "render")
+            .map_err(|err| format!("render: {err}"))?;
+
+        let len = i32::try_from(input.len()).map_err(|_| "the row is too large")?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(|err| format!("alloc: {err}"))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input.as_bytes())
+            .map_err(|err| format!("alloc returned an unusable buffer: {err}"))?;
+        let packed = render
+            .call(&mut store, (ptr, len))
+            .map_err(|err| format!("render: {err}"))? as u64;
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        let output = memory
+            .data(&store)
+            .get(out_ptr..out_ptr + out_len)
+            .ok_or("render returned a range outside its memory")?;
+        String::from_utf8(output.to_vec()).map_err(|_| "render returned invalid UTF-8".into())
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+
+/// A Lambda function or ECS service written in the Log group field in place
+/// of its log group: `fn:payments-api` (or `lambda:`), `ecs:checkout` for a
+/// service in the default cluster, or `ecs:prod/checkout` for one in `prod`.
+/// The fetcher looks up the groups it writes to before the query runs.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Resource {
+    Function(String),
+    Service {
+        cluster: Option<String>,
+        service: String,
+    },
+}
+
+impl Resource {
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (kind, name) = entry.trim().split_once(':')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        match kind.to_ascii_lowercase().as_str() {
+            "fn" | "lambda" => Some(Self::Function(name.to_string())),
+            "ecs" => {
+                let (cluster, service) = match name.split_once('/') {
+                    Some((cluster, service)) => (Some(cluster.trim()), service.trim()),
+                    None => (None, name),
+                };
+                if service.is_empty() || cluster.is_some_and(str::is_empty) {
+                    return None;
+                }
+                Some(Self::Service {
+                    cluster: cluster.map(str::to_string),
+                    service: service.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Function(name) => write!(f, "fn:{name}"),
+            Self::Service {
+                cluster: Some(cluster),
+                service,
+            } => write!(f, "ecs:{cluster}/{service}"),
+            Self::Service {
+                cluster: None,
+                service,
+            } => write!(f, "ecs:{service}"),
+        }
+    }
+}
+
+/// A lookup of a resource's log groups, for the UI loop to run.
+pub struct ResourceLookup {
+    pub resource: Resource,
+    pub region: String,
+    pub profile: Option<String>,
+}
+
+/// Rest of a function or service name that the listed log groups suggest,
+/// e.g. `ments` after `fn:pay` when `/aws/lambda/payments` is listed. It only
+/// completes the name; which group the query runs against is still looked up.
+pub fn suggestion<'a>(entry: &str, known: &'a [String]) -> Option<&'a str> {
+    let (kind, typed) = entry.trim_start().split_once(':')?;
+    let prefix = match kind.to_ascii_lowercase().as_str() {
+        "fn" | "lambda" => "/aws/lambda/",
+        // Only plain service names; the cluster is not in the group name.
+        "ecs" if !typed.contains('/') => "/ecs/",
+        _ => return None,
+    };
+    if typed.is_empty() {
+        return None;
+    }
+    known.iter().find_map(|group| {
+        let name = group.strip_prefix(prefix)?;
+        name.strip_prefix(typed)
+            .filter(|rest| !rest.is_empty() && !rest.contains('/'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_functions_and_services() {
+        assert_eq!(
+            Resource::parse(" fn:payments-api"),
+            Some(Resource::Function("payments-api".into()))
+        );
+        assert_eq!(
+            Resource::parse("Lambda:checkout"),
+            Some(Resource::Function("checkout".into()))
+        );
+        assert_eq!(
+            Resource::parse("ecs:prod/checkout"),
+            Some(Resource::Service {
+                cluster: Some("prod".into()),
+                service: "checkout".into(),
+            })
+        );
+        assert_eq!(
+            Resource::parse("ecs:checkout").map(|resource| resource.to_string()),
+            Some("ecs:checkout".into())
+        );
+    }
+
+    #[test]
+    fn leaves_other_entries_alone() {
+        for entry in [
+            "/aws/lambda/payments",
+            "fn:",
+            "ecs:/checkout",
+            "ecs:prod/",
+            "s3:bucket",
+        ] {
+            assert_eq!(Resource::parse(entry), None, "{entry}");
+        }
+    }
+
+    #[test]
+    fn suggests_names_from_listed_groups_only() {
+        let known = vec![
+            "/aws/lambda/payments-api-canary".to_string(),
+            "/ecs/checkout".to_string(),
+        ];
+        assert_eq!(suggestion("fn:payments", &known), Some("-api-canary"));
+        assert_eq!(suggestion("ecs:check", &known), Some("out"));
+        assert_eq!(suggestion("ecs:prod/check", &known), None);
+        // A complete name gets no suggestion, even when a longer one is listed.
+        assert_eq!(suggestion("fn:payments-api-canary", &known), None);
+        assert_eq!(suggestion("fn:orders", &known), None);
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+use crate::app::{InputSnapshot, LastRun, QueryResults};
+
+/// Oldest entries are dropped beyond this many, so large result sets don't pile up.
+const MAX_ENTRIES: usize = 8;
+
+/// Results of recent runs, reused when identical inputs are run again within the TTL.
+pub struct ResultCache {
+    ttl: Duration,
+    entries: Vec<CachedRun>,
+}
+
+#[derive(Clone)]
+pub struct CachedRun {
+    pub results: QueryResults,
+    pub last_run: LastRun,
+    pub warning: Option<String>,
+    pub stored_at: Instant,
+}
+
+impl ResultCache {
+    /// A zero TTL disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    pub fn get(&mut self, inputs: &InputSnapshot) -> Option<&CachedRun> {
+        let ttl = self.ttl;
+        self.entries.retain(|entry| entry.stored_at.elapsed() < ttl);
+        self.entries
+            .iter()
+            .find(|entry| &entry.last_run.inputs == inputs)
+    }
+
+    pub fn insert(&mut self, results: QueryResults, last_run: LastRun, warning: Option<String>) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.entries
+            .retain(|entry| entry.last_run.inputs != last_run.inputs);
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(CachedRun {
+            results,
+            last_run,
+            warning,
+            stored_at: Instant::now(),
+        });
+    }
+}
+
+/// Compact age for the results badge, e.g. `45s` or `2m`.
+pub fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m", seconds / 60)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Rows indexed between checks for a cancelled build.
+const CANCEL_CHECK: usize = 1024;
+/// Rough heap bytes of a posting list besides its entries: the map slot,
+/// the trigram, and the `Vec` header.
+const LIST_OVERHEAD_BYTES: usize = 48;
+
+/// Trigram postings over each row's cells, ASCII-lowercased as the filter
+/// compares them. A filter token of three or more bytes can only match rows
+/// holding every one of its trigrams, so those are the only rows left to
+/// check. Tokens hold no spaces, so trigrams across cells are not needed.
+pub struct SearchIndex {
+    postings: HashMap<[u8; 3], Vec<u32>>,
+}
+
+/// Why no index was built.
+pub enum IndexStop {
+    Cancelled,
+    /// The index would pass the memory it was allowed.
+    OverBudget,
+}
+
+impl SearchIndex {
+    /// Index `rows`, each given as its cells, in order, within about
+    /// `max_bytes` of memory.
+    pub fn build<R, S>(
+        rows: impl IntoIterator<Item = R>,
+        max_bytes: usize,
+        cancel: &AtomicBool,
+    ) -> Result<Self, IndexStop>
+    where
+        R: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let mut postings: HashMap<[u8; 3], Vec<u32>> = HashMap::new();
+        let mut bytes = 0usize;
+        let mut trigrams = Vec::new();
+        for (row, cells) in rows.into_iter().enumerate() {
+            if row % CANCEL_CHECK == 0 && cancel.load(Ordering::Relaxed) {
+                return Err(IndexStop::Cancelled);
+            }
+            let row = u32::try_from(row).map_err(|_| IndexStop::OverBudget)?;
+            trigrams.clear();
+            for cell in cells.as_ref() {
+                trigrams.extend(cell.as_ref().as_bytes().windows(3).map(|window| {
+                    [
+                        window[0].to_ascii_lowercase(),
+                        window[1].to_ascii_lowercase(),
+                        window[2].to_ascii_lowercase(),
+                    ]
+                }));
+            }
+            trigrams.sort_unstable();
+            trigrams.dedup();
+            for trigram in &trigrams {
+                let list = postings.entry(*trigram).or_insert_with(|| {
+                    bytes += LIST_OVERHEAD_BYTES;
+                    Vec::new()
+                });
+                list.push(row);
+                bytes += std::mem::size_of::<u32>();
+            }
+            if bytes > max_bytes {
+                return Err(IndexStop::OverBudget);
+            }
+        }
+        postings.shrink_to_fit();
+        Ok(Self { postings })
+    }
+
+    /// Rows that may contain `token`, ascending; `None` when the token is
+    /// too short to look up and every row is a candidate.
+    pub fn candidates(&self, token: &str) -> Option<Vec<usize>> {
+        let mut lists = Vec::new();
+        for window in token.as_bytes().windows(3) {
+            match self.postings.get(&[window[0], window[1], window[2]]) {
+                Some(rows) => lists.push(rows),
+                None => return Some(Vec::new()),
+            }
+        }
+        lists.sort_by_key(|rows| rows.len());
+        let (shortest, rest) = lists.split_first()?;
+        Some(
+            shortest
+                .iter()
+                .filter(|row| rest.iter().all(|rows| rows.binary_search(row).is_ok()))
+                .map(|&row| row as usize)
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde_json::{json, Value};
+use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task;
+
+use crate::app::resolve_default_region;
+use crate::aws_profiles;
+use crate::config::{self, ProductionConfig};
+use crate::input::queries_directory;
+use crate::log_fetcher::{
+    LogFetcher, QueryLimiter, QueryOutcome, QueryParams, MAX_QUERY_LOG_GROUPS,
+};
+use crate::presentation::format_results;
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Failures of the request itself, such as a query error from CloudWatch.
+const REQUEST_FAILED: i64 = -32000;
+const DEFAULT_MINUTES: i64 = 60;
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+type RpcError = (i64, String);
+
+/// What concurrent requests share: the fetcher, and the TUI's limit on
+/// running queries and its production rules.
+struct Server {
+    fetcher: Arc<dyn LogFetcher>,
+    limiter: QueryLimiter,
+    production: ProductionConfig,
+    profile_accounts: HashMap<String, String>,
+    /// Set once a query against a production profile was confirmed.
+    production_confirmed: AtomicBool,
+}
+
+/// `awslogs serve`: answer JSON-RPC 2.0 requests, one per line on stdin, with
+/// one response per line on stdout. The methods are also offered as MCP tools
+/// (`initialize`, `tools/list`, `tools/call`). Requests run concurrently.
+pub async fn run(fetcher: Arc<dyn LogFetcher>) -> Result<(), String> {
+    let config = config::load_config().0;
+    let profile_accounts = task::spawn_blocking(aws_profiles::discover_profile_accounts)
+        .await
+        .unwrap_or_default();
+    let server = Arc::new(Server {
+        fetcher,
+        limiter: QueryLimiter::new(config.query.max_concurrent),
+        production: config.production,
+        profile_accounts,
+        production_confirmed: AtomicBool::new(false),
+    });
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = io::stdout();
+        while let Some(message) = out_rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if stdout.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+            let _ = stdout.flush().await;
+        }
+    });
+    let mut lines = BufReader::new(io::stdin()).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|err| format!("Failed to read request: {err}"))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(err) => {
+                let _ = out_tx.send(error_response(
+                    Value::Null,
+                    (PARSE_ERROR, format!("Invalid JSON: {err}")),
+                ));
+                continue;
+            }
+        };
+        let server = Arc::clone(&server);
+        let out_tx = out_tx.clone();
+        tokio::spawn(async move {
+            // Requests without an id are notifications and get no reply.
+            let Some(id) = request.get("id").cloned() else {
+                return;
+            };
+            let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+            let params = request.get("params").cloned().unwrap_or(Value::Null);
+            let response = match dispatch(&server, method, &params).await {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(err) => error_response(id, err),
+            };
+            let _ = out_tx.send(response);
+        });
+    }
+    drop(out_tx);
+    let _ = writer.await;
+    Ok(())
+}
+
+fn error_response(id: Value, (code, message): RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+async fn dispatch(server: &Server, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(MCP_PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "awslogs", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            // Tool failures are reported inside the result, as MCP expects.
+            Ok(match call(server, name, &arguments).await {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": value.to_string() }],
+                    "isError": false,
+                }),
+                Err((_, message)) => json!({
+                    "content": [{ "type": "text", "text": message }],
+                    "isError": true,
+                }),
+            })
+        }
+        _ => call(server, method, params).await,
+    }
+}
+
+async fn call(server: &Server, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "run_query" => run_query(server, params).await,
+        "list_log_groups" => {
+            let region = region(params);
+            let profile = string(params, "profile");
+            let prefix = string(params, "prefix").unwrap_or_default();
+            let groups = server
+                .fetcher
+                .list_log_groups(&region, profile.as_deref())
+                .await
+                .map_err(|err| (REQUEST_FAILED, err))?;
+            let groups: Vec<String> = groups
+                .into_iter()
+                .filter(|group| group.starts_with(&prefix))
+                .collect();
+            Ok(json!({ "log_groups": groups }))
+        }
+        "list_saved_queries" => {
+            blocking(|| {
+                let directory = queries_directory()?;
+                let mut names: Vec<String> = match fs::read_dir(&directory) {
+                    Ok(entries) => entries
+                        .filter_map(Result::ok)
+                        .filter(|entry| entry.path().is_file())
+                        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                        .collect(),
+                    Err(_) => Vec::new(),
+                };
+                names.sort();
+                Ok(json!({ "queries": names }))
+            })
+            .await
+        }
+        "read_saved_query" => {
+            let path = saved_query_path(params)?;
+            blocking(move || {
+                let query = fs::read_to_string(&path)
+                    .map_err(|err| format!("Failed to read file: {err}"))?;
+                Ok(json!({ "query": query }))
+            })
+            .await
+        }
+        "save_query" => {
+            let path = saved_query_path(params)?;
+            let query = string(params, "query")
+                .filter(|query| !query.trim().is_empty())
+                .ok_or((INVALID_PARAMS, "query is required".to_string()))?;
+            blocking(move || {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|err| format!("Unable to prepare save directory: {err}"))?;
+                }
+                fs::write(&path, query).map_err(|err| format!("Failed to write file: {err}"))?;
+                Ok(json!({ "saved": true }))
+            })
+            .await
+        }
+        "delete_saved_query" => {
+            let path = saved_query_path(params)?;
+            blocking(move || {
+                fs::remove_file(&path).map_err(|err| format!("Failed to delete file: {err}"))?;
+                Ok(json!({ "deleted": true }))
+            })
+            .await
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method {method}"))),
+    }
+}
+
+/// Run a Logs Insights query the way the TUI does: confirm production,
+/// resolve patterns, cap the log group count, wait for a free query slot,
+/// and return the rows as columns plus string cells.
+async fn run_query(server: &Server, params: &Value) -> Result<Value, RpcError> {
+    let log_group = string(params, "log_group")
+        .filter(|group| !group.trim().is_empty())
+        .ok_or((INVALID_PARAMS, "log_group is required".to_string()))?;
+    let query = string(params, "query")
+        .filter(|query| !query.trim().is_empty())
+        .ok_or((INVALID_PARAMS, "query is required".to_string()))?;
+    let end = match params.get("end") {
+        Some(value) => timestamp(value)?,
+        // Whole seconds unless asked for, so no `@timestamp` filter is added.
+        None => Utc::now().trunc_subsecs(0),
+    };
+    let start = match params.get("start") {
+        Some(value) => timestamp(value)?,
+        None => {
+            let minutes = match params.get("minutes") {
+                Some(value) => value.as_i64(),
+                None => Some(DEFAULT_MINUTES),
+            };
+            minutes
+                .and_then(chrono::Duration::try_minutes)
+                .and_then(|window| end.checked_sub_signed(window))
+                .ok_or((
+                    INVALID_PARAMS,
+                    "minutes must be a whole number of minutes in range".to_string(),
+                ))?
+        }
+    };
+    if end <= start {
+        return Err((INVALID_PARAMS, "end must be after start".to_string()));
+    }
+    let confirmed = params.get("confirm_production").and_then(Value::as_bool) == Some(true);
+    let params = QueryParams {
+        start_millis: start.timestamp_millis(),
+        end_millis: end.timestamp_millis(),
+        log_group,
+        query,
+        region: region(params),
+        profile: string(params, "profile"),
+    };
+    if let Some(profile) = params.profile.as_deref() {
+        server.check_production(profile, confirmed)?;
+    }
+    let failed = |err: String| (REQUEST_FAILED, err);
+    let fetcher = server.fetcher.as_ref();
+    fetcher.check_connectivity(&params).await.map_err(failed)?;
+    let mut log_groups = fetcher.resolve_log_groups(&params).await.map_err(failed)?;
+    if log_groups.is_empty() {
+        return Err(failed(format!("No log groups match {}", params.log_group)));
+    }
+    let mut warning = None;
+    if log_groups.len() > MAX_QUERY_LOG_GROUPS {
+        log_groups.truncate(MAX_QUERY_LOG_GROUPS);
+        warning = Some(format!(
+            "Only the first {MAX_QUERY_LOG_GROUPS} matching log groups were queried"
+        ));
+    }
+    let ticket = server.limiter.ticket();
+    let _permit = server.limiter.admit(ticket).await;
+    match fetcher.run_query(params, log_groups).await {
+        QueryOutcome::Success(records) => {
+            let formatted = format_results(records);
+            Ok(json!({
+                "columns": formatted.headers,
+                "rows": formatted.rows,
+                "warning": warning,
+            }))
+        }
+        QueryOutcome::Error(err) => Err(failed(err)),
+    }
+}
+
+impl Server {
+    /// The TUI asks before the session's first production query; here the
+    /// caller confirms with `confirm_production: true`.
+    fn check_production(&self, profile: &str, confirmed: bool) -> Result<(), RpcError> {
+        let account = self.profile_accounts.get(profile).map(String::as_str);
+        if !self.production.confirm_first_query
+            || !self.production.matches(profile, account)
+            || self.production_confirmed.load(Ordering::Relaxed)
+        {
+            return Ok(());
+        }
+        if !confirmed {
+            return Err((
+                INVALID_PARAMS,
+                format!("Profile {profile} is production; pass confirm_production: true to run the query"),
+            ));
+        }
+        self.production_confirmed.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+fn tools() -> Value {
+    let range = json!({
+        "start": { "type": ["string", "integer"], "description": "RFC 3339 time or epoch milliseconds" },
+        "end": { "type": ["string", "integer"], "description": "RFC 3339 time or epoch milliseconds; defaults to now" },
+        "minutes": { "type": "integer", "description": "Window before end when start is omitted (default 60)" },
+    });
+    let account = json!({
+        "region": { "type": "string" },
+        "profile": { "type": "string" },
+    });
+    let mut query_props = json!({
+        "log_group": { "type": "string", "description": "Log group name, comma-separated list, or prefix ending in *" },
+        "query": { "type": "string", "description": "Logs Insights query" },
+        "confirm_production": { "type": "boolean", "description": "Required for the first query against a production profile" },
+    });
+    for source in [&range, &account] {
+        for (key, value) in source.as_object().into_iter().flatten() {
+            query_props[key] = value.clone();
+        }
+    }
+    let mut group_props = json!({ "prefix": { "type": "string" } });
+    for (key, value) in account.as_object().into_iter().flatten() {
+        group_props[key] = value.clone();
+    }
+    let name = json!({ "name": { "type": "string", "description": "File name under ./queries" } });
+    json!([
+        {
+            "name": "run_query",
+            "description": "Run a CloudWatch Logs Insights query and return its rows",
+            "inputSchema": { "type": "object", "properties": query_props, "required": ["log_group", "query"] },
+        },
+        {
+            "name": "list_log_groups",
+            "description": "List log group names, optionally by prefix",
+            "inputSchema": { "type": "object", "properties": group_props },
+        },
+        {
+            "name": "list_saved_queries",
+            "description": "List the saved query files",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "read_saved_query",
+            "description": "Read a saved query",
+            "inputSchema": { "type": "object", "properties": name, "required": ["name"] },
+        },
+        {
+            "name": "save_query",
+            "description": "Save a query under a file name, replacing any existing one",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "name": name["name"], "query": { "type": "string" } },
+                "required": ["name", "query"],
+            },
+        },
+        {
+            "name": "delete_saved_query",
+            "description": "Delete a saved query",
+            "inputSchema": { "type": "object", "properties": name, "required": ["name"] },
+        },
+    ])
+}
+
+fn string(params: &Value, key: &str) -> Option<String> {
+    params.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn region(params: &Value) -> String {
+    string(params, "region")
+        .filter(|region| !region.trim().is_empty())
+        .unwrap_or_else(resolve_default_region)
+}
+
+fn timestamp(value: &Value) -> Result<DateTime<Utc>, RpcError> {
+    let parsed = match value {
+        Value::Number(millis) => millis.as_i64().and_then(DateTime::from_timestamp_millis),
+        Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|time| time.with_timezone(&Utc)),
+        _ => None,
+    };
+    parsed.ok_or((
+        INVALID_PARAMS,
+        format!("{value} is not an RFC 3339 time or epoch milliseconds"),
+    ))
+}
+
+/// A plain file name inside the saved queries directory.
+fn saved_query_path(params: &Value) -> Result<PathBuf, RpcError> {
+    let name = string(params, "name").unwrap_or_default();
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err((
+            INVALID_PARAMS,
+            format!("'{name}' is not a saved query name"),
+        ));
+    }
+    let directory = queries_directory().map_err(|err| (REQUEST_FAILED, err))?;
+    Ok(directory.join(name))
+}
+
+async fn blocking<F>(work: F) -> Result<Value, RpcError>
+where
+    F: FnOnce() -> Result<Value, String> + Send + 'static,
+{
+    task::spawn_blocking(work)
+        .await
+        .map_err(|err| (REQUEST_FAILED, format!("Request interrupted: {err}")))?
+        .map_err(|err| (REQUEST_FAILED, err))
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::config::{ShareConfig, ShareFormat};
+use crate::export::{render_text_table, ExportTable};
+
+const SHARE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What gets posted when results are shared to a webhook.
+pub struct ShareSummary {
+    pub query: String,
+    pub log_group: String,
+    pub region: String,
+    pub time_range: Option<(String, String)>,
+    pub row_count: usize,
+    pub table: ExportTable,
+}
+
+pub async fn post_summary(
+    config: ShareConfig,
+    text_max_width: usize,
+    summary: ShareSummary,
+) -> Result<(), String> {
+    let Some(url) = config.resolved_webhook_url() else {
+        return Err("No webhook configured; set share.webhook_url or AWSLOGS_WEBHOOK_URL".into());
+    };
+    let payload = match config.format {
+        ShareFormat::Slack => slack_payload(&summary, text_max_width),
+        ShareFormat::Json => json_payload(&summary),
+    };
+    let client = reqwest::Client::builder()
+        .timeout(SHARE_TIMEOUT)
+        .build()
+        .map_err(|err| format!("Unable to create HTTP client: {err}"))?;
+    let response = client
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|err| format!("Webhook request failed: {err}"))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("Webhook responded with {status}"))
+    }
+}
+
+fn slack_payload(summary: &ShareSummary, max_width: usize) -> serde_json::Value {
+    let mut text = format!(
+        "*CloudWatch Logs Insights* — `{}` ({})\n",
+        summary.log_group, summary.region
+    );
+    if let Some((start, end)) = &summary.time_range {
+        text.push_str(&format!("*Range:* {start} → {end}\n"));
+    }
+    text.push_str(&format!("*Rows:* {}\n", summary.row_count));
+    text.push_str(&format!("```{}```\n", summary.query));
+    if !summary.table.rows.is_empty() {
+        text.push_str(&format!(
+            "*Top {} rows:*\n```{}```",
+            summary.table.rows.len(),
+            render_text_table(&summary.table, max_width)
+        ));
+    }
+    json!({ "text": text })
+}
+
+fn json_payload(summary: &ShareSummary) -> serde_json::Value {
+    let (start, end) = summary
+        .time_range
+        .clone()
+        .map(|(start, end)| (Some(start), Some(end)))
+        .unwrap_or((None, None));
+    json!({
+        "query": summary.query,
+        "log_group": summary.log_group,
+        "region": summary.region,
+        "start": start,
+        "end": end,
+        "row_count": summary.row_count,
+        "headers": summary.table.headers,
+        "rows": summary.table.rows,
+    })
+}
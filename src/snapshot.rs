@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::export::ExportRows;
+
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+pub const SNAPSHOT_EXTENSION: &str = "json.gz";
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Query parameters that produced a saved result set.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SnapshotQuery {
+    pub query: String,
+    pub log_group: String,
+    pub region: String,
+    pub profile: Option<String>,
+    pub start_epoch: i64,
+    pub end_epoch: i64,
+    /// Millisecond bounds; absent in snapshots written before they were recorded.
+    #[serde(default)]
+    pub start_millis: Option<i64>,
+    #[serde(default)]
+    pub end_millis: Option<i64>,
+}
+
+/// Results captured to disk so they can be reviewed without re-querying AWS.
+#[derive(Serialize, Deserialize)]
+pub struct ResultSnapshot {
+    pub version: u32,
+    pub saved_at: String,
+    pub query: Option<SnapshotQuery>,
+    pub headers: Vec<String>,
+    pub rows: ExportRows,
+    /// Row notes keyed by index into `rows`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notes: BTreeMap<usize, String>,
+    /// The workspace scratchpad at the time of the snapshot.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scratchpad: String,
+}
+
+impl ResultSnapshot {
+    pub fn new(
+        saved_at: String,
+        query: Option<SnapshotQuery>,
+        headers: Vec<String>,
+        rows: ExportRows,
+    ) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            saved_at,
+            query,
+            headers,
+            rows,
+            notes: BTreeMap::new(),
+            scratchpad: String::new(),
+        }
+    }
+}
+
+pub fn write_snapshot(path: &Path, snapshot: &ResultSnapshot) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Unable to prepare snapshot directory: {err}"))?;
+    }
+    let file = File::create(path).map_err(|err| format!("Failed to create snapshot: {err}"))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    serde_json::to_writer(&mut encoder, snapshot)
+        .map_err(|err| format!("Failed to encode snapshot: {err}"))?;
+    encoder
+        .finish()
+        .map_err(|err| format!("Failed to write snapshot: {err}"))?;
+    Ok(())
+}
+
+pub fn read_snapshot(path: &Path) -> Result<ResultSnapshot, String> {
+    let file = File::open(path).map_err(|err| format!("Failed to open snapshot: {err}"))?;
+    let decoder = GzDecoder::new(BufReader::new(file));
+    let snapshot: ResultSnapshot = serde_json::from_reader(decoder)
+        .map_err(|err| format!("Snapshot is not readable: {err}"))?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "Snapshot version {} is newer than supported version {SNAPSHOT_VERSION}",
+            snapshot.version
+        ));
+    }
+    Ok(snapshot)
+}
+
+/// Append the snapshot extension unless the name already carries it.
+pub fn snapshot_file_name(name: &str) -> String {
+    let trimmed = name.trim();
+    if trimmed.ends_with(&format!(".{SNAPSHOT_EXTENSION}")) {
+        trimmed.to_string()
+    } else {
+        format!("{trimmed}.{SNAPSHOT_EXTENSION}")
+    }
+}
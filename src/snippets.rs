@@ -0,0 +1,202 @@
+use serde::Deserialize;
+use tui_textarea::{CursorMove, TextArea};
+
+use crate::block_edit::jump;
+
+/// Query snippet from the config, e.g. `trigger = "lat"`,
+/// `body = "stats pct(@duration, ${1:95}) by bin(${2:5m})"`.
+#[derive(Clone, Deserialize)]
+pub struct Snippet {
+    pub trigger: String,
+    /// Text with `${1:default}` or `$1` tab stops; `$0` is where the cursor ends.
+    pub body: String,
+}
+
+/// Snippets available without any config; config entries with the same trigger win.
+const BUILTIN: &[(&str, &str)] = &[
+    ("stats", "stats count(*) by bin(${1:5m})"),
+    ("filt", "filter ${1:@message} like /${2:error}/"),
+    ("parse", "parse @message \"${1:* *}\" as ${2:first}, ${3:second}"),
+    (
+        "errors",
+        "fields @timestamp, @message\n| filter @message like /(?i)${1:error}/\n| sort @timestamp desc\n| limit ${2:100}",
+    ),
+    (
+        "top",
+        "stats count(*) as events by ${1:field}\n| sort events desc\n| limit ${2:10}",
+    ),
+    (
+        "lat",
+        "stats avg(@duration), pct(@duration, ${1:95}), max(@duration) by bin(${2:5m})",
+    ),
+];
+
+/// Tab stops of an expanded snippet, visited in order with Tab and Shift+Tab.
+pub struct SnippetSession {
+    /// `(row, col, len)` of each stop's text, ending with the final cursor spot.
+    stops: Vec<(usize, usize, usize)>,
+    current: usize,
+}
+
+/// Replace the trigger word before the cursor with its snippet and select the
+/// first tab stop; `None` when the word is not a trigger.
+pub fn expand(area: &mut TextArea, user: &[Snippet]) -> Option<SnippetSession> {
+    let (row, col) = area.cursor();
+    let line = area.lines().get(row)?;
+    let before: Vec<char> = line.chars().take(col).collect();
+    let start = before
+        .iter()
+        .rposition(|c| !c.is_alphanumeric() && *c != '_')
+        .map_or(0, |idx| idx + 1);
+    let trigger: String = before[start..].iter().collect();
+    if trigger.is_empty() {
+        return None;
+    }
+    let body = user
+        .iter()
+        .find(|snippet| snippet.trigger == trigger)
+        .map(|snippet| snippet.body.as_str())
+        .or_else(|| {
+            BUILTIN
+                .iter()
+                .find(|(name, _)| *name == trigger)
+                .map(|(_, body)| *body)
+        })?;
+    let (text, offsets) = parse(body);
+    for _ in 0..trigger.chars().count() {
+        area.delete_char();
+    }
+    let origin = area.cursor();
+    area.insert_str(&text);
+    let stops: Vec<(usize, usize, usize)> = offsets
+        .into_iter()
+        .map(|(offset, len)| {
+            let (row, col) = position(&text, offset, origin);
+            (row, col, len)
+        })
+        .collect();
+    let session = SnippetSession { stops, current: 0 };
+    session.select(area);
+    Some(session)
+}
+
+impl SnippetSession {
+    /// True while stops after the current one remain.
+    pub fn is_active(&self) -> bool {
+        self.current + 1 < self.stops.len()
+    }
+
+    /// Move to the next (or previous) stop; false once the last stop is passed.
+    pub fn advance(&mut self, area: &mut TextArea, forward: bool) -> bool {
+        self.account_for_edit(area);
+        if forward {
+            self.current += 1;
+        } else {
+            self.current = self.current.saturating_sub(1);
+        }
+        if self.current >= self.stops.len() {
+            return false;
+        }
+        self.select(area);
+        self.is_active()
+    }
+
+    /// Typing in a stop shifts the stops after it on the same line.
+    fn account_for_edit(&mut self, area: &mut TextArea) {
+        area.cancel_selection();
+        let (row, col) = area.cursor();
+        let (stop_row, stop_col, len) = self.stops[self.current];
+        if row != stop_row || col < stop_col {
+            return;
+        }
+        let delta = col as isize - (stop_col + len) as isize;
+        self.stops[self.current].2 = col - stop_col;
+        for stop in self.stops.iter_mut().skip(self.current + 1) {
+            if stop.0 == stop_row && stop.1 >= stop_col + len {
+                stop.1 = (stop.1 as isize + delta).max(0) as usize;
+            }
+        }
+    }
+
+    fn select(&self, area: &mut TextArea) {
+        let (row, col, len) = self.stops[self.current];
+        jump(area, row, col);
+        if len > 0 {
+            area.start_selection();
+            for _ in 0..len {
+                area.move_cursor(CursorMove::Forward);
+            }
+        }
+    }
+}
+
+/// Snippet text without markers, plus `(char offset, default length)` per stop
+/// in visiting order: `$1`, `$2`, ... and then `$0` (or the end of the text).
+/// A number used again only keeps its first position.
+fn parse(body: &str) -> (String, Vec<(usize, usize)>) {
+    let chars: Vec<char> = body.chars().collect();
+    let mut text = String::new();
+    let mut length = 0;
+    let mut stops: Vec<(u32, usize, usize)> = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        if chars[idx] == '$' {
+            let braced = chars.get(idx + 1) == Some(&'{');
+            let digits_from = idx + 1 + usize::from(braced);
+            let digits: String = chars[digits_from..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if let Ok(number) = digits.parse::<u32>() {
+                let mut next = digits_from + digits.len();
+                let mut default = String::new();
+                let closed = if braced {
+                    if chars.get(next) == Some(&':') {
+                        next += 1;
+                        while next < chars.len() && chars[next] != '}' {
+                            default.push(chars[next]);
+                            next += 1;
+                        }
+                    }
+                    chars.get(next) == Some(&'}')
+                } else {
+                    true
+                };
+                if closed {
+                    if !stops.iter().any(|(seen, _, _)| *seen == number) {
+                        stops.push((number, length, default.chars().count()));
+                    }
+                    length += default.chars().count();
+                    text.push_str(&default);
+                    idx = next + usize::from(braced);
+                    continue;
+                }
+            }
+        }
+        text.push(chars[idx]);
+        length += 1;
+        idx += 1;
+    }
+    // `$0` sorts last; without one the cursor ends after the text.
+    stops.sort_by_key(|(number, _, _)| if *number == 0 { u32::MAX } else { *number });
+    if stops.last().is_none_or(|(number, _, _)| *number != 0) {
+        stops.push((0, length, 0));
+    }
+    let offsets = stops
+        .into_iter()
+        .map(|(_, offset, len)| (offset, len))
+        .collect();
+    (text, offsets)
+}
+
+/// Editor position of a char offset into text inserted at `origin`.
+fn position(text: &str, offset: usize, origin: (usize, usize)) -> (usize, usize) {
+    let before: String = text.chars().take(offset).collect();
+    match before.rsplit_once('\n') {
+        Some((head, tail)) => (
+            origin.0 + head.matches('\n').count() + 1,
+            tail.chars().count(),
+        ),
+        None => (origin.0, origin.1 + before.chars().count()),
+    }
+}
@@ -0,0 +1,74 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Local;
+
+use crate::export;
+
+/// Where one spilled row sits in the file.
+#[derive(Clone, Copy)]
+pub struct SpillSlot {
+    offset: u64,
+    len: u32,
+}
+
+/// Result rows moved out of memory once `query.results_memory_mb` is used
+/// up, one JSON array of cells per row, read back when a row is needed.
+/// The file is removed when the results it holds are replaced.
+pub struct SpillFile {
+    path: PathBuf,
+    /// The file and how much of it is written.
+    file: Mutex<(File, u64)>,
+}
+
+impl SpillFile {
+    pub fn create() -> Result<Self, String> {
+        let path = env::temp_dir().join(format!(
+            "awslogs-spill-{}-{}.jsonl",
+            std::process::id(),
+            Local::now().format("%Y%m%d%H%M%S%3f")
+        ));
+        // Rows hold raw log data, so only the user can read them back.
+        let file = export::create_private(&path)
+            .map_err(|err| format!("Unable to create {}: {err}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new((file, 0)),
+        })
+    }
+
+    pub fn append(&self, cells: &[String]) -> Result<SpillSlot, String> {
+        let mut line = serde_json::to_vec(cells).map_err(|err| err.to_string())?;
+        line.push(b'\n');
+        let len = u32::try_from(line.len()).map_err(|_| "Row too large to spill".to_string())?;
+        let mut guard = self.file.lock().map_err(|err| err.to_string())?;
+        let (file, end) = &mut *guard;
+        file.seek(SeekFrom::Start(*end))
+            .and_then(|_| file.write_all(&line))
+            .map_err(|err| format!("Unable to write {}: {err}", self.path.display()))?;
+        let slot = SpillSlot { offset: *end, len };
+        *end += u64::from(len);
+        Ok(slot)
+    }
+
+    pub fn read(&self, slot: SpillSlot) -> Result<Vec<String>, String> {
+        let mut line = vec![0; slot.len as usize];
+        {
+            let mut guard = self.file.lock().map_err(|err| err.to_string())?;
+            let file = &mut guard.0;
+            file.seek(SeekFrom::Start(slot.offset))
+                .and_then(|_| file.read_exact(&mut line))
+                .map_err(|err| format!("Unable to read {}: {err}", self.path.display()))?;
+        }
+        serde_json::from_slice(&line).map_err(|err| format!("Corrupt spilled row: {err}"))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,145 @@
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+/// The public-domain X11 misc-fixed 7x13 font: printable ASCII (0x20-0x7F),
+/// 16 glyphs per row, one bit per pixel, most significant bit first.
+const FONT: &[u8] = include_bytes!("../assets/font_7x13.raw");
+const GLYPH_WIDTH: usize = 7;
+const GLYPH_HEIGHT: usize = 13;
+const GLYPHS_PER_ROW: usize = 16;
+const FONT_ROW_BYTES: usize = GLYPH_WIDTH * GLYPHS_PER_ROW / 8;
+const MARGIN: usize = 10;
+
+/// Widest line drawn, in characters.
+pub const MAX_LINE_CHARS: usize = 220;
+
+/// Background, body text, and header text, as indices into `PALETTE`.
+const BACKGROUND: u8 = 0;
+const TEXT: u8 = 1;
+const HEADER: u8 = 2;
+const PALETTE: [[u8; 3]; 3] = [[30, 30, 30], [220, 220, 220], [230, 180, 80]];
+
+/// Text rasterised onto a paletted canvas.
+pub struct Picture {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<u8>,
+}
+
+/// Draw text lines in the terminal font; the first `header_lines` stand out.
+pub fn draw(text: &str, header_lines: usize) -> Picture {
+    let lines: Vec<&str> = text.lines().collect();
+    let columns = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_LINE_CHARS);
+    let width = columns * GLYPH_WIDTH + 2 * MARGIN;
+    let height = lines.len() * GLYPH_HEIGHT + 2 * MARGIN;
+    let mut picture = Picture {
+        width,
+        height,
+        pixels: vec![BACKGROUND; width * height],
+    };
+    for (row, line) in lines.iter().enumerate() {
+        let color = if row < header_lines { HEADER } else { TEXT };
+        for (column, ch) in line.chars().take(MAX_LINE_CHARS).enumerate() {
+            picture.draw_glyph(
+                MARGIN + column * GLYPH_WIDTH,
+                MARGIN + row * GLYPH_HEIGHT,
+                ch,
+                color,
+            );
+        }
+    }
+    picture
+}
+
+impl Picture {
+    fn draw_glyph(&mut self, left: usize, top: usize, ch: char, color: u8) {
+        let code = if (' '..='\u{7f}').contains(&ch) {
+            ch as usize
+        } else {
+            '?' as usize
+        };
+        let glyph = code - ' ' as usize;
+        let glyph_left = (glyph % GLYPHS_PER_ROW) * GLYPH_WIDTH;
+        let glyph_top = (glyph / GLYPHS_PER_ROW) * GLYPH_HEIGHT;
+        for y in 0..GLYPH_HEIGHT {
+            for x in 0..GLYPH_WIDTH {
+                let bit = glyph_left + x;
+                let byte = FONT[(glyph_top + y) * FONT_ROW_BYTES + bit / 8];
+                if byte & (0x80 >> (bit % 8)) != 0 {
+                    self.pixels[(top + y) * self.width + left + x] = color;
+                }
+            }
+        }
+    }
+
+    /// RGBA bytes, as clipboards take images.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&index| {
+                let [r, g, b] = PALETTE[index as usize];
+                [r, g, b, 255]
+            })
+            .collect()
+    }
+
+    /// Encode as an indexed-colour PNG.
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut scanlines = Vec::with_capacity((self.width + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // Filter type 0: the row is stored as is.
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&scanlines)
+            .map_err(|err| format!("Failed to compress image: {err}"))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|err| format!("Failed to compress image: {err}"))?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8-bit palette indices, default compression and filtering, no interlace.
+        header.extend_from_slice(&[8, 3, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"PLTE", PALETTE.concat().as_slice());
+        write_chunk(&mut png, b"IDAT", &compressed);
+        write_chunk(&mut png, b"IEND", &[]);
+        Ok(png)
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+/// The CRC-32 PNG chunks end with.
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
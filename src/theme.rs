@@ -0,0 +1,269 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::border;
+use ratatui::widgets::{Block, Borders};
+
+use crate::highlight::HighlightColor;
+use crate::presentation::JsonToken;
+use crate::widgets::tab_bar::TabState;
+
+static MONOCHROME: AtomicBool = AtomicBool::new(false);
+static ASCII_BORDERS: AtomicBool = AtomicBool::new(false);
+
+const ASCII_BORDER_SET: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Switch to modifier-only styling for `--monochrome` or a non-empty `NO_COLOR`,
+/// and to plain ASCII borders when `ascii_borders` is set.
+pub fn init(monochrome_flag: bool, ascii_borders: bool) {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    MONOCHROME.store(monochrome_flag || no_color, Ordering::Relaxed);
+    ASCII_BORDERS.store(ascii_borders, Ordering::Relaxed);
+}
+
+pub fn is_monochrome() -> bool {
+    MONOCHROME.load(Ordering::Relaxed)
+}
+
+/// Spinner glyph for the given elapsed time; plain ASCII alongside ASCII borders.
+pub fn spinner_frame(elapsed: Duration) -> &'static str {
+    const BRAILLE: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    const ASCII: [&str; 4] = ["|", "/", "-", "\\"];
+    let step = (elapsed.as_millis() / 100) as usize;
+    if ASCII_BORDERS.load(Ordering::Relaxed) {
+        ASCII[step % ASCII.len()]
+    } else {
+        BRAILLE[step % BRAILLE.len()]
+    }
+}
+
+/// Bordered block using the active border set.
+pub fn block<'a>() -> Block<'a> {
+    let block = Block::default().borders(Borders::ALL);
+    if ASCII_BORDERS.load(Ordering::Relaxed) {
+        block.border_set(ASCII_BORDER_SET)
+    } else {
+        block
+    }
+}
+
+pub fn focus_border() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Highlight for the selected row in tables and lists.
+pub fn selection() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Rgb(255, 246, 199))
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Per-cell style inside a selected table row; the row style supplies the background.
+pub fn selection_cell() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Rows marked as endpoints for zooming the time range.
+pub fn marked() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::UNDERLINED)
+    } else {
+        Style::default().fg(Color::Rgb(230, 180, 80))
+    }
+}
+
+/// The bracket at the query cursor and its partner.
+pub fn bracket_match() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        Style::default()
+            .fg(Color::Rgb(120, 200, 230))
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Filter term chips above the table; switched-off terms are struck through.
+pub fn chip(enabled: bool) -> Style {
+    let style = if is_monochrome() {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default()
+            .fg(Color::Rgb(220, 220, 220))
+            .bg(Color::Rgb(60, 60, 80))
+    };
+    if enabled {
+        style
+    } else {
+        style.add_modifier(Modifier::CROSSED_OUT | Modifier::DIM)
+    }
+}
+
+/// Rows whose message pattern the baseline run did not have.
+pub fn new_pattern() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Rgb(120, 210, 120))
+    }
+}
+
+/// Separator lines for deploys and other timeline markers.
+pub fn timeline_marker() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Rgb(120, 200, 230))
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Badge naming the active environment preset; production stands out in red.
+pub fn environment(production: bool) -> Style {
+    match (is_monochrome(), production) {
+        (true, true) => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+        (true, false) => Style::default().add_modifier(Modifier::BOLD),
+        (false, true) => Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD),
+        (false, false) => Style::default()
+            .fg(Color::Black)
+            .bg(Color::Rgb(120, 190, 120))
+            .add_modifier(Modifier::BOLD),
+    }
+}
+
+/// Title of the workspace tab on screen.
+pub fn active_tab() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Flash on a background tab whose query just finished.
+pub fn attention() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Indicator next to a workspace tab: yellow while running, green when done, red on error.
+pub fn tab_state(state: TabState) -> Style {
+    if is_monochrome() {
+        return Style::default();
+    }
+    match state {
+        TabState::Idle => Style::default().fg(Color::DarkGray),
+        TabState::Running => Style::default().fg(Color::Yellow),
+        TabState::Complete => Style::default().fg(Color::Rgb(120, 190, 120)),
+        TabState::Failed => Style::default().fg(Color::Rgb(200, 90, 90)),
+    }
+}
+
+/// Result cells matched by a `[[highlights]]` rule; monochrome terminals get reverse video.
+pub fn highlight(color: HighlightColor) -> Style {
+    if is_monochrome() {
+        return Style::default().add_modifier(Modifier::REVERSED);
+    }
+    let color = match color {
+        HighlightColor::Red => Color::Rgb(230, 90, 90),
+        HighlightColor::Yellow => Color::Rgb(230, 200, 80),
+        HighlightColor::Green => Color::Rgb(120, 200, 120),
+        HighlightColor::Blue => Color::Rgb(110, 150, 240),
+        HighlightColor::Magenta => Color::Rgb(210, 120, 220),
+        HighlightColor::Cyan => Color::Rgb(100, 200, 220),
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+/// Syntax colors for JSON shown in the detail modal.
+pub fn json(token: JsonToken) -> Style {
+    if is_monochrome() {
+        return match token {
+            JsonToken::Key => Style::default().add_modifier(Modifier::BOLD),
+            _ => Style::default(),
+        };
+    }
+    match token {
+        JsonToken::Key => Style::default().fg(Color::Rgb(120, 170, 230)),
+        JsonToken::String => Style::default().fg(Color::Rgb(150, 200, 120)),
+        JsonToken::Number => Style::default().fg(Color::Rgb(220, 170, 90)),
+        JsonToken::Literal => Style::default().fg(Color::Rgb(200, 130, 200)),
+        JsonToken::Punctuation => Style::default().fg(Color::DarkGray),
+        JsonToken::Plain => Style::default(),
+    }
+}
+
+/// Lines a diff adds (`added`) or removes.
+pub fn diff(added: bool) -> Style {
+    match (is_monochrome(), added) {
+        (true, true) => Style::default().add_modifier(Modifier::BOLD),
+        (true, false) => Style::default().add_modifier(Modifier::CROSSED_OUT),
+        (false, true) => Style::default().fg(Color::Rgb(120, 210, 120)),
+        (false, false) => Style::default().fg(Color::Rgb(200, 90, 90)),
+    }
+}
+
+pub fn error() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Rgb(200, 90, 90))
+    }
+}
+
+/// Query linter notes under the editor.
+pub fn lint() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::ITALIC)
+    } else {
+        Style::default().fg(Color::Rgb(230, 180, 80))
+    }
+}
+
+pub fn hint() -> Style {
+    if is_monochrome() {
+        Style::default().add_modifier(Modifier::DIM)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    }
+}
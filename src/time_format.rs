@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+
+static DAY_FIRST: AtomicBool = AtomicBool::new(false);
+
+/// How dates are typed into and shown in the From/To fields.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateOrder {
+    /// `YYYY-MM-DD`
+    #[default]
+    Iso,
+    /// `DD-MM-YYYY`
+    DayFirst,
+}
+
+pub fn init(order: DateOrder) {
+    DAY_FIRST.store(order == DateOrder::DayFirst, Ordering::Relaxed);
+}
+
+fn day_first() -> bool {
+    DAY_FIRST.load(Ordering::Relaxed)
+}
+
+fn date_pattern() -> &'static str {
+    if day_first() {
+        "%d-%m-%Y"
+    } else {
+        "%Y-%m-%d"
+    }
+}
+
+/// Short reminder of the active entry format, shown in field titles.
+pub fn hint() -> &'static str {
+    if day_first() {
+        "DD-MM-YYYY"
+    } else {
+        "YYYY-MM-DD"
+    }
+}
+
+pub fn format(value: NaiveDateTime, with_millis: bool) -> String {
+    let time = if with_millis {
+        "%H:%M:%S%.3f"
+    } else {
+        "%H:%M:%S"
+    };
+    value
+        .format(&format!("{} {time}", date_pattern()))
+        .to_string()
+}
+
+/// Parse a date with optional time in the active order. ISO input is always
+/// accepted too, since a leading four-digit year is unambiguous.
+pub fn parse(input: &str) -> Option<NaiveDateTime> {
+    let mut patterns = vec![date_pattern()];
+    if day_first() {
+        patterns.extend(["%d/%m/%Y", "%Y-%m-%d"]);
+    }
+    patterns.into_iter().find_map(|date| {
+        NaiveDateTime::parse_from_str(input, &format!("{date} %H:%M:%S%.f"))
+            .or_else(|_| NaiveDateTime::parse_from_str(input, &format!("{date} %H:%M")))
+            .or_else(|_| NaiveDate::parse_from_str(input, date).map(|d| d.and_time(NaiveTime::MIN)))
+            .ok()
+    })
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::TimelineConfig;
+use crate::export;
+
+/// Named point in time, such as a deploy, drawn between result rows.
+#[derive(Clone)]
+pub struct TimelineMarker {
+    pub name: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Markers from the config and the marker file, oldest first, plus a warning
+/// describing the first entry that could not be read.
+pub fn load(config: &TimelineConfig) -> (Vec<TimelineMarker>, Option<String>) {
+    let mut markers = Vec::new();
+    let mut warning = None;
+    for entry in &config.markers {
+        match parse_time(&entry.at) {
+            Some(at) => markers.push(TimelineMarker {
+                name: entry.name.clone(),
+                at,
+            }),
+            None => {
+                warning.get_or_insert_with(|| {
+                    format!(
+                        "Ignoring marker '{}': unreadable time '{}'",
+                        entry.name, entry.at
+                    )
+                });
+            }
+        }
+    }
+    if let Some(path) = config.file.as_deref() {
+        if let Err(err) = read_file(path, &mut markers) {
+            warning.get_or_insert(err);
+        }
+    }
+    markers.sort_by_key(|marker| marker.at);
+    (markers, warning)
+}
+
+/// One marker per line as `<timestamp> <name>`; blank lines and `#` comments are skipped.
+fn read_file(path: &Path, markers: &mut Vec<TimelineMarker>) -> Result<(), String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Unable to read markers file {}: {err}", path.display()))?;
+    for (line_no, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = split_line(line).and_then(|(time, name)| {
+            Some(TimelineMarker {
+                name: name.to_string(),
+                at: parse_time(time)?,
+            })
+        });
+        match parsed {
+            Some(marker) => markers.push(marker),
+            None => {
+                return Err(format!(
+                    "Markers file {} line {}: expected '<timestamp> <name>'",
+                    path.display(),
+                    line_no + 1
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split off the timestamp, which may itself contain one space (`2024-05-01 12:00:00`).
+fn split_line(line: &str) -> Option<(&str, &str)> {
+    let mut parts = line.splitn(3, ' ');
+    let first = parts.next()?;
+    let second = parts.next()?;
+    let joined_len = first.len() + 1 + second.len();
+    if parse_time(&line[..joined_len]).is_some() {
+        let name = parts.next()?.trim();
+        return (!name.is_empty()).then_some((&line[..joined_len], name));
+    }
+    let name = line[first.len()..].trim();
+    (!name.is_empty()).then_some((first, name))
+}
+
+/// RFC 3339 with an offset, or a bare timestamp read as UTC like `@timestamp` values.
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|parsed| parsed.with_timezone(&Utc))
+        .ok()
+        .or_else(|| export::parse_timestamp(value).map(|naive| naive.and_utc()))
+}
+
+/// Markers falling between two neighbouring rows' timestamps, in either sort order.
+pub fn between(
+    markers: &[TimelineMarker],
+    previous: DateTime<Utc>,
+    current: DateTime<Utc>,
+) -> Vec<&TimelineMarker> {
+    let (low, high) = if previous <= current {
+        (previous, current)
+    } else {
+        (current, previous)
+    };
+    let mut crossed: Vec<&TimelineMarker> = markers
+        .iter()
+        .filter(|marker| marker.at > low && marker.at <= high)
+        .collect();
+    if previous > current {
+        crossed.reverse();
+    }
+    crossed
+}
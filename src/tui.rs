@@ -1,48 +1,244 @@
 use std::error::Error;
-use std::io;
+use std::io::{self, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{Event, EventStream};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event,
+    EventStream, KeyEvent, KeyEventKind, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::execute;
+use crossterm::terminal::{self, disable_raw_mode, enable_raw_mode};
 use futures::StreamExt;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use tokio::sync::mpsc;
-use tokio::time::interval;
+use tokio::time::{self, interval};
 
-use crate::app::App;
+use crate::app::{App, Notice, WorkspaceCommand};
+use crate::aws_profiles;
+use crate::console_url::ConsoleLink;
+use crate::explain;
+use crate::hooks;
+use crate::i18n;
 use crate::input;
-use crate::log_fetcher::{LogFetcher, QueryOutcome};
-use crate::presentation::format_results;
+use crate::log_fetcher::{
+    LogFetcher, QueryLimiter, QueryOutcome, QueryParams, QueryReport, MAX_QUERY_LOG_GROUPS,
+};
+use crate::metadata_cache::MetadataKey;
+use crate::presentation::stream_results;
+use crate::renderers::Rendered;
+use crate::resource_groups::{Resource, ResourceLookup};
 use crate::ui;
+use crate::workspaces::{TaggedReport, Workspace, Workspaces};
+
+/// Startup switches parsed from the command line.
+#[derive(Default)]
+pub struct LaunchOptions {
+    pub initial_status: Option<String>,
+    pub linear: bool,
+    /// Slow-link mode: fewer redraws and no mouse capture.
+    pub remote: bool,
+    pub modes: TerminalModes,
+    /// Query text given on the command line, and the file it was read from.
+    pub initial_query: Option<(String, Option<PathBuf>)>,
+    /// Logs Insights console URL passed as an argument.
+    pub console_link: Option<ConsoleLink>,
+}
+
+/// Optional terminal features switched on at startup, undone on suspend and exit.
+#[derive(Clone, Copy, Default)]
+pub struct TerminalModes {
+    pub mouse_capture: bool,
+    /// Whether the kitty keyboard protocol is enabled for this session.
+    pub keyboard_enhanced: bool,
+}
+
+/// Enter the alternate screen and optional modes; raw mode must already be enabled.
+pub fn enter_terminal_modes(out: &mut impl Write, modes: TerminalModes) -> io::Result<()> {
+    // Bracketed paste keeps pasted newlines from arriving as Enter presses.
+    execute!(out, terminal::EnterAlternateScreen, EnableBracketedPaste)?;
+    if modes.mouse_capture {
+        execute!(out, EnableMouseCapture)?;
+    }
+    if modes.keyboard_enhanced {
+        execute!(
+            out,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
+    Ok(())
+}
+
+/// Restore the terminal to the state the shell expects, including leaving raw mode.
+pub fn leave_terminal_modes(out: &mut impl Write, modes: TerminalModes) -> io::Result<()> {
+    if modes.keyboard_enhanced {
+        execute!(out, PopKeyboardEnhancementFlags)?;
+    }
+    disable_raw_mode()?;
+    if modes.mouse_capture {
+        execute!(out, DisableMouseCapture)?;
+    }
+    execute!(out, DisableBracketedPaste, terminal::LeaveAlternateScreen)
+}
+
+const REMOTE_REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+/// About one frame at 60 Hz; key repeat faster than this is drawn once per frame.
+const LOCAL_REDRAW_INTERVAL: Duration = Duration::from_millis(16);
 
 pub async fn run_app(
     fetcher: Arc<dyn LogFetcher>,
-    initial_status: Option<String>,
+    options: LaunchOptions,
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
 ) -> Result<(), Box<dyn Error>> {
-    let mut app = App::default();
-    if let Some(status) = initial_status {
+    let mut app = App {
+        linear_mode: options.linear,
+        keyboard_enhanced: options.modes.keyboard_enhanced,
+        ..App::default()
+    };
+    if let Some((query, path)) = options.initial_query {
+        app.replace_query_text(query.trim_end().to_string());
+        match path {
+            Some(path) => app.set_saved_query_path(path),
+            None => app.query_baseline = app.query_text(),
+        }
+    }
+    if let Some(link) = options.console_link {
+        app.apply_console_link(link);
+    }
+    if let Some(status) = options.initial_status {
         app.set_status(status);
     }
     let mut events = EventStream::new();
     let mut ticker = interval(Duration::from_millis(100));
-    let (tx, mut rx) = mpsc::unbounded_channel::<QueryOutcome>();
+    let (tx, mut rx) = mpsc::unbounded_channel::<TaggedReport>();
+    let (notice_tx, mut notice_rx) = mpsc::unbounded_channel::<Notice>();
+    let (listing_tx, mut listing_rx) = mpsc::unbounded_channel::<LogGroupListing>();
+    let (sample_tx, mut sample_rx) = mpsc::unbounded_channel::<ValueSample>();
+    let (trail_tx, mut trail_rx) = mpsc::unbounded_channel::<(u64, u64, QueryOutcome)>();
+    let (explain_tx, mut explain_rx) =
+        mpsc::unbounded_channel::<(u64, u64, Result<String, String>)>();
+    let (lookup_tx, mut lookup_rx) = mpsc::unbounded_channel::<ResourceLookupReply>();
+    let (render_tx, mut render_rx) = mpsc::unbounded_channel::<(u64, usize, Option<Rendered>)>();
+    let mut profiles = tokio::task::spawn_blocking(aws_profiles::discover);
+    let mut profiles_pending = true;
+    let mut workspaces = Workspaces::new(app, tx);
+    let min_redraw_interval = if options.remote {
+        REMOTE_REDRAW_INTERVAL
+    } else {
+        LOCAL_REDRAW_INTERVAL
+    };
+    let mut needs_redraw = true;
+    let mut last_draw: Option<Instant> = None;
 
     loop {
-        terminal.draw(|f| ui::draw_ui(f, &mut app))?;
+        let redraw_due = last_draw.is_none_or(|at| at.elapsed() >= min_redraw_interval);
+        if needs_redraw && redraw_due {
+            let tabs = workspaces.tabs();
+            let active_tab = workspaces.active_index();
+            let app = &mut workspaces.active().app;
+            terminal.draw(|f| ui::draw_ui(f, app, &tabs, active_tab))?;
+            last_draw = Some(Instant::now());
+            needs_redraw = false;
+        }
+        // Events that arrive before the next frame is due are applied without
+        // drawing, so held keys coalesce; wake up to draw when the frame is due.
+        let deferred_draw = last_draw
+            .filter(|_| needs_redraw)
+            .map(|at| time::Instant::from_std(at + min_redraw_interval));
+
+        // A replaying macro feeds its next key once no query is running and
+        // the quick filter has caught up, so keys act on the rows they would
+        // have when recorded.
+        let replayed_key = {
+            let app = &mut workspaces.active().app;
+            if app.submitting || app.filter_dirty || app.filter_job.is_some() {
+                None
+            } else {
+                app.key_macro.peek()
+            }
+        };
 
         tokio::select! {
-            maybe_event = events.next() => {
+            maybe_event = next_event(&mut events, replayed_key) => {
+                needs_redraw = true;
+                let id = workspaces.active_id();
+                let Workspace { app, tx, .. } = workspaces.active();
+                let (replayed, maybe_event) = maybe_event;
+                if replayed {
+                    app.key_macro.advance();
+                } else if let Some(Ok(Event::Key(key))) = &maybe_event {
+                    if key.kind == KeyEventKind::Press {
+                        if app.key_macro.is_replaying() {
+                            app.key_macro.cancel();
+                            app.set_status("Macro replay stopped");
+                        }
+                        app.key_macro.record(*key);
+                    }
+                }
                 match maybe_event {
                     Some(Ok(Event::Key(key))) => {
-                        if input::is_ctrl_enter(&key) {
-                            input::start_query_submission(&mut app, &fetcher, &tx);
+                        if input::is_suspend_key(&key) {
+                            suspend(terminal, options.modes, app)?;
+                            continue;
+                        }
+                        if input::is_run_key(&key, app) {
+                            input::start_query_submission(app, &fetcher, tx);
                             continue;
-                        } else if input::handle_key_event(key, &mut app, &fetcher, &tx).await? {
+                        } else if input::handle_key_event(key, app, &fetcher, tx, &notice_tx).await? {
                             break;
                         }
+                        // Jump keys may have landed on a hidden input pane.
+                        app.ensure_focus_shown();
+                        if let Some((field, params)) = app.take_value_sample() {
+                            let limiter = Arc::clone(&app.query_limiter);
+                            spawn_value_sample(&fetcher, &limiter, id, field, params, &sample_tx);
+                        }
+                        if let Some((request, params)) = app.cloudtrail_request.take() {
+                            let limiter = Arc::clone(&app.query_limiter);
+                            let tx = trail_tx.clone();
+                            spawn_queued_query(&fetcher, &limiter, params, move |outcome| {
+                                let _ = tx.send((id, request, outcome));
+                            });
+                        }
+                        if let Some(request) = app.explain_request.take() {
+                            let config = app.config.explain.clone();
+                            let tx = explain_tx.clone();
+                            tokio::spawn(async move {
+                                let request_id = request.id;
+                                let reply = explain::explain(config, request).await;
+                                let _ = tx.send((id, request_id, reply));
+                            });
+                        }
+                        if let Some(lookup) = app.resource_lookup.take() {
+                            spawn_resource_lookup(&fetcher, id, lookup, &lookup_tx);
+                        }
+                        if let Some(request) = app.render_request.take() {
+                            let tx = render_tx.clone();
+                            tokio::task::spawn_blocking(move || {
+                                let (row, rendered) = request.run();
+                                let _ = tx.send((id, row, rendered));
+                            });
+                        }
+                        match app.workspace_command.take() {
+                            Some(WorkspaceCommand::Open) => workspaces.open(),
+                            Some(WorkspaceCommand::Close) => workspaces.close_active(),
+                            Some(WorkspaceCommand::Next) => workspaces.cycle(1),
+                            Some(WorkspaceCommand::Previous) => workspaces.cycle(-1),
+                            Some(WorkspaceCommand::NextUnseen) => workspaces.next_unseen(),
+                            Some(WorkspaceCommand::DrillDown) => {
+                                if let Some((title, params)) = app.drill_down.take() {
+                                    workspaces.open_drill_down(title, params);
+                                    let Workspace { app, tx, .. } = workspaces.active();
+                                    input::start_query_submission(app, &fetcher, tx);
+                                }
+                            }
+                            None => {}
+                        }
                     }
+                    Some(Ok(Event::Paste(text))) => input::handle_paste(app, &text),
                     Some(Ok(Event::Resize(_, _))) => {}
                     Some(Err(err)) => {
                         app.set_error(format!("Event error: {err}"));
@@ -50,24 +246,251 @@ pub async fn run_app(
                     _ => {}
                 }
             }
-            Some(outcome) = rx.recv() => {
-                app.submitting = false;
-                match outcome {
-                    QueryOutcome::Success(data) => {
-                        app.set_status("Query complete");
-                        let formatted = format_results(&data);
-                        app.set_results(formatted);
+            Some((id, report)) = rx.recv() => {
+                needs_redraw = true;
+                let background = !workspaces.is_active(id);
+                let Some(app) = workspaces.app_by_id(id) else {
+                    continue;
+                };
+                apply_report(app, report, &notice_tx);
+                if background {
+                    app.results_unseen_since = Some(Instant::now());
+                    if app.config.general.focus_follows_activity {
+                        let title = app.workspace_title();
+                        workspaces
+                            .active()
+                            .app
+                            .set_status(format!("Results ready in \"{title}\" (Alt+N to jump there)"));
                     }
-                    QueryOutcome::Error(err) => {
-                        app.set_error(err);
+                }
+            }
+            Some(notice) = notice_rx.recv() => {
+                needs_redraw = true;
+                workspaces.active().app.apply_notice(notice);
+            }
+            Some((id, field, outcome)) = sample_rx.recv() => {
+                needs_redraw = true;
+                if let Some(app) = workspaces.app_by_id(id) {
+                    app.apply_value_sample(&field, outcome);
+                }
+            }
+            Some((id, request, outcome)) = trail_rx.recv() => {
+                needs_redraw = true;
+                if let Some(app) = workspaces.app_by_id(id) {
+                    app.apply_cloudtrail(request, outcome);
+                }
+            }
+            Some((id, request, reply)) = explain_rx.recv() => {
+                needs_redraw = true;
+                if let Some(app) = workspaces.app_by_id(id) {
+                    app.apply_explanation(request, reply);
+                }
+            }
+            Some((id, row, rendered)) = render_rx.recv() => {
+                needs_redraw = true;
+                if let Some(app) = workspaces.app_by_id(id) {
+                    app.apply_rendering(row, rendered);
+                }
+            }
+            Some((id, resource, outcome)) = lookup_rx.recv() => {
+                needs_redraw = true;
+                if let Some(app) = workspaces.app_by_id(id) {
+                    app.apply_resource_lookup(resource, outcome);
+                }
+            }
+            discovery = &mut profiles, if profiles_pending => {
+                needs_redraw = true;
+                profiles_pending = false;
+                let discovery = discovery.unwrap_or_default();
+                for app in workspaces.apps_mut() {
+                    app.apply_profile_discovery(discovery.clone());
+                }
+            }
+            Some((key, listing)) = listing_rx.recv() => {
+                // Every tab on that profile and region shares the refreshed list.
+                for app in workspaces.apps_mut() {
+                    if app.metadata_key.as_ref() == Some(&key) {
+                        app.apply_log_group_listing(key.clone(), listing.clone());
                     }
                 }
             }
+            _ = time::sleep_until(deferred_draw.unwrap_or_else(time::Instant::now)), if deferred_draw.is_some() => {}
             _ = ticker.tick() => {
-                app.on_tick();
+                let mut changed = false;
+                for app in workspaces.apps_mut() {
+                    if let Some(key) = app.take_metadata_refresh() {
+                        spawn_log_group_listing(&fetcher, key, &listing_tx);
+                    }
+                    changed |= app.on_tick();
+                }
+                needs_redraw |= changed || !options.remote;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The next terminal event, or `replayed` straight away; the flag says which.
+async fn next_event(
+    events: &mut EventStream,
+    replayed: Option<KeyEvent>,
+) -> (bool, Option<io::Result<Event>>) {
+    match replayed {
+        Some(key) => (true, Some(Ok(Event::Key(key)))),
+        None => (false, events.next().await),
+    }
+}
+
+fn apply_report(app: &mut App, report: QueryReport, notices: &mpsc::UnboundedSender<Notice>) {
+    let elapsed = app.finish_submission();
+    let failure = match &report.outcome {
+        QueryOutcome::Error(err) => Some(err.clone()),
+        _ => None,
+    };
+    match report.outcome {
+        QueryOutcome::Success(data) => {
+            match &report.warning {
+                Some(warning) => app.set_error(i18n::fill(
+                    "status.complete_with_warning",
+                    &[("warning", warning)],
+                )),
+                None => app.set_status(i18n::text("status.complete")),
             }
+            let (headers, rows) = stream_results(data);
+            app.set_results(headers, rows);
+            app.record_last_run(elapsed);
+            app.cache_results(report.warning);
+            app.remember_result_fields();
         }
+        QueryOutcome::Error(err) => {
+            app.query_failed = true;
+            // The rest of a replaying macro expected these results.
+            app.key_macro.cancel();
+            app.set_error(err);
+        }
+    }
+    let hook = app.config.hooks.on_query_complete.as_deref().map(str::trim);
+    if let Some(command) = hook.filter(|command| !command.is_empty()) {
+        let outcome = match failure {
+            Some(err) => Err(err),
+            None => Ok(app.results.clone()),
+        };
+        let completion = app.query_completion(elapsed, outcome);
+        let timeout = Duration::from_secs(app.config.hooks.timeout_secs.max(1));
+        hooks::spawn_on_query_complete(command.to_string(), timeout, completion, notices);
     }
+}
+
+type ResourceLookupReply = (u64, Resource, Result<Vec<String>, String>);
+
+/// Find the log groups of a `fn:`/`ecs:` entry in the background.
+fn spawn_resource_lookup(
+    fetcher: &Arc<dyn LogFetcher>,
+    id: u64,
+    lookup: ResourceLookup,
+    tx: &mpsc::UnboundedSender<ResourceLookupReply>,
+) {
+    let fetcher = Arc::clone(fetcher);
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let ResourceLookup {
+            resource,
+            region,
+            profile,
+        } = lookup;
+        let outcome = fetcher
+            .resource_log_groups(&resource, &region, profile.as_deref())
+            .await;
+        let _ = tx.send((id, resource, outcome));
+    });
+}
+
+type LogGroupListing = (MetadataKey, Result<Vec<String>, String>);
+
+/// List log groups in the background to refresh the on-disk suggestions.
+fn spawn_log_group_listing(
+    fetcher: &Arc<dyn LogFetcher>,
+    key: MetadataKey,
+    tx: &mpsc::UnboundedSender<LogGroupListing>,
+) {
+    let fetcher = Arc::clone(fetcher);
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let listing = fetcher
+            .list_log_groups(&key.region, key.profile.as_deref())
+            .await;
+        let _ = tx.send((key, listing));
+    });
+}
+
+/// Sampling query result for a field, tagged with the workspace that asked.
+type ValueSample = (u64, String, QueryOutcome);
+
+/// Run a field's sampling query in the background, queued like any other Insights query.
+fn spawn_value_sample(
+    fetcher: &Arc<dyn LogFetcher>,
+    limiter: &Arc<QueryLimiter>,
+    id: u64,
+    field: String,
+    params: QueryParams,
+    tx: &mpsc::UnboundedSender<ValueSample>,
+) {
+    let tx = tx.clone();
+    spawn_queued_query(fetcher, limiter, params, move |outcome| {
+        let _ = tx.send((id, field, outcome));
+    });
+}
+
+/// Run a side query in the background, queued like any other Insights query,
+/// and hand its outcome to `done`.
+fn spawn_queued_query(
+    fetcher: &Arc<dyn LogFetcher>,
+    limiter: &Arc<QueryLimiter>,
+    params: QueryParams,
+    done: impl FnOnce(QueryOutcome) + Send + 'static,
+) {
+    let fetcher = Arc::clone(fetcher);
+    let limiter = Arc::clone(limiter);
+    let ticket = limiter.ticket();
+    tokio::spawn(async move {
+        let outcome = match fetcher.resolve_log_groups(&params).await {
+            Ok(mut log_groups) => {
+                log_groups.truncate(MAX_QUERY_LOG_GROUPS);
+                let _permit = limiter.admit(ticket).await;
+                fetcher.run_query(params, log_groups).await
+            }
+            Err(err) => QueryOutcome::Error(err),
+        };
+        done(outcome);
+    });
+}
+
+/// Hand the terminal back to the shell and stop the process until `fg` resumes it.
+#[cfg(unix)]
+fn suspend(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    modes: TerminalModes,
+    _app: &mut App,
+) -> io::Result<()> {
+    leave_terminal_modes(terminal.backend_mut(), modes)?;
+    terminal.show_cursor()?;
+    // Raw mode disables ISIG, so the key never became a signal; raise it ourselves.
+    // SAFETY: raise only delivers a signal to this process.
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+    enable_raw_mode()?;
+    enter_terminal_modes(terminal.backend_mut(), modes)?;
+    terminal.clear()
+}
 
+#[cfg(not(unix))]
+fn suspend(
+    _terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    _modes: TerminalModes,
+    app: &mut App,
+) -> io::Result<()> {
+    app.set_status("Suspending to the shell is not supported on this platform.");
     Ok(())
 }
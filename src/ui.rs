@@ -1,33 +1,93 @@
 use std::borrow::Cow;
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, Cell, Clear, Paragraph, Row, Table, Wrap};
 use ratatui::Frame;
 use tui_input::Input as SingleLineInput;
 
-use crate::app::{App, FocusField, OpenDialogState, SaveDialogMode, SaveDialogState, StatusKind};
-use crate::help;
-use crate::presentation::{format_modal_message, format_modal_value};
+use crate::app::{
+    App, OpenDialogMode, OpenDialogState, SaveDialogMode, SaveDialogState, StatusKind,
+    REFRESH_KEY_HINT,
+};
+use crate::block_edit::BlockCaret;
+use crate::cloudtrail::CloudTrailPane;
+use crate::clusters::ClusterView;
+use crate::column_stats::ColumnStats;
+use crate::explain::ExplainPane;
+use crate::export::SUPPORTED_EXTENSIONS;
+use crate::focus::FocusField;
+use crate::i18n;
+use crate::input_panes::InputPane;
+use crate::linear_ui;
+use crate::palette::PaletteState;
+use crate::presentation::{json_tokens, looks_like_json};
+use crate::query_diff::{DiffLine, QueryDiff};
+use crate::query_pairs;
+use crate::theme;
+use crate::time_format;
+use crate::value_picker::{self, ValuePicker};
 use crate::widgets::column_picker::ColumnVisibilityModal;
+use crate::widgets::date_picker::{DatePicker, DATE_PICKER_SIZE};
+use crate::widgets::tab_bar::{TabBar, TabItem};
 use crate::widgets::toggle::Toggle;
 
 // Longest known region identifier (ap-southeast-3) is 15 characters; add two for borders.
 const AWS_REGION_FIELD_WIDTH: u16 = 18;
+/// Linter hints shown under the query editor; the rest wait until these are fixed.
+const MAX_LINT_LINES: usize = 3;
+/// Prefixed to the first cell of rows that carry a note.
+const NOTE_MARKER: &str = "✎ ";
 
-pub fn draw_ui(frame: &mut Frame, app: &mut App) {
+/// `tabs` lists every open workspace; the bar only appears once there is more than one.
+pub fn draw_ui(frame: &mut Frame, app: &mut App, tabs: &[TabItem], active_tab: usize) {
+    if app.linear_mode {
+        linear_ui::draw_linear_ui(frame, app, tabs, active_tab);
+        return;
+    }
     let frame_height = frame.size().height;
-    let has_inputs = !app.inputs_collapsed;
+    let zoomed = app.query_zoomed;
+    let has_inputs = !app.inputs_collapsed && !zoomed;
+    let shown = |pane: InputPane| has_inputs && !app.hidden_inputs.contains(&pane);
+    let show_region = shown(InputPane::Region);
+    let show_profile = shown(InputPane::Profile) && app.show_profile_picker();
+    let show_time_range = shown(InputPane::TimeRange);
+    let show_log_group = shown(InputPane::LogGroup);
+    let show_query = zoomed || shown(InputPane::Query);
+    let show_filter = shown(InputPane::Filter);
+    let show_top_row = show_region || show_profile || show_time_range || show_log_group;
+    let show_query_row = show_query || show_filter;
+    let show_tabs = tabs.len() > 1;
+    let tab_bar_height = if show_tabs { 1 } else { 0 };
+    let production_banner = app.production_banner();
+    let banner_height = if production_banner.is_some() { 1 } else { 0 };
     let show_status = app.submitting || matches!(app.status_kind, StatusKind::Error);
     let status_height = if show_status { 3 } else { 0 };
-    let top_row_height = if has_inputs { 3 } else { 0 };
-    let fixed_height = top_row_height + status_height;
+    let top_row_height = if show_top_row { 3 } else { 0 };
+    let lint_hints = if show_query {
+        app.query_lint_hints()
+    } else {
+        Vec::new()
+    };
+    let lint_height = lint_hints.len().min(MAX_LINT_LINES) as u16;
+    let fixed_height =
+        tab_bar_height + banner_height + top_row_height + lint_height + status_height;
     let available_for_query_and_results = frame_height.saturating_sub(fixed_height);
 
     let mut constraints = Vec::new();
+    if show_tabs {
+        constraints.push(Constraint::Length(tab_bar_height));
+    }
+    if production_banner.is_some() {
+        constraints.push(Constraint::Length(banner_height));
+    }
 
-    if has_inputs {
+    if show_top_row {
+        constraints.push(Constraint::Length(top_row_height));
+    }
+    if show_query {
         let min_query_height = 5;
         let min_results_height = 6;
         let mut desired_query_height = (app.query_area.lines().len() as u16)
@@ -37,7 +97,9 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             desired_query_height = min_query_height;
         }
         let mut max_query_height = available_for_query_and_results;
-        if available_for_query_and_results > min_results_height {
+        if zoomed {
+            desired_query_height = available_for_query_and_results;
+        } else if available_for_query_and_results > min_results_height {
             max_query_height = available_for_query_and_results.saturating_sub(min_results_height);
             if max_query_height < min_query_height {
                 max_query_height = min_query_height.min(available_for_query_and_results);
@@ -47,8 +109,12 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             desired_query_height = max_query_height;
         }
         let query_row_height = desired_query_height.min(available_for_query_and_results);
-        constraints.push(Constraint::Length(top_row_height));
         constraints.push(Constraint::Length(query_row_height));
+    } else if show_filter {
+        constraints.push(Constraint::Length(3));
+    }
+    if lint_height > 0 {
+        constraints.push(Constraint::Length(lint_height));
     }
 
     constraints.push(Constraint::Min(0)); // results
@@ -70,19 +136,19 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
     if let Some(status_chunk) = status_chunk {
         let mut help_text = Vec::new();
         let mut first_line_style = Style::default();
-        let mut block = Block::default().title("Status").borders(Borders::ALL);
+        let mut block = theme::block().title(i18n::text("title.status"));
         if matches!(app.status_kind, StatusKind::Error) {
-            let accent = Color::Rgb(200, 90, 90);
-            first_line_style = first_line_style.fg(accent);
-            block = block.border_style(Style::default().fg(accent));
+            first_line_style = theme::error();
+            block = block.border_style(theme::error());
         }
         help_text.push(Line::from(Span::styled(
             app.status.clone(),
             first_line_style,
         )));
-        help_text.push(Line::from(
-            "Tab: Next • Shift+Tab: Previous • Ctrl+Enter/Ctrl+R/F5: Run • Ctrl+H: Help • Ctrl+C/Esc: Quit",
-        ));
+        help_text.push(Line::from(i18n::fill(
+            "hint.status",
+            &[("run", &app.run_key_hint()), ("quit", app.quit_key_hint())],
+        )));
         let status = Paragraph::new(help_text)
             .wrap(Wrap { trim: true })
             .block(block);
@@ -109,36 +175,132 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
         };
 
     let mut chunk_index = 0;
-    let top_chunk = if has_inputs {
+    if show_tabs {
+        frame.render_widget(TabBar::new(tabs, active_tab), chunks[chunk_index]);
+        chunk_index += 1;
+    }
+    if let Some(banner) = production_banner {
+        let banner = Paragraph::new(format!(" {banner} "))
+            .alignment(Alignment::Center)
+            .style(theme::environment(true));
+        frame.render_widget(banner, chunks[chunk_index]);
+        chunk_index += 1;
+    }
+    let top_chunk = if show_top_row {
         let area = chunks[chunk_index];
         chunk_index += 1;
         Some(area)
     } else {
         None
     };
-    let query_chunk = if has_inputs {
+    let query_chunk = if show_query_row {
         let area = chunks[chunk_index];
         chunk_index += 1;
         Some(area)
     } else {
         None
     };
-    let results_area = chunks[chunk_index];
+    if lint_height > 0 {
+        let lines: Vec<Line> = lint_hints
+            .iter()
+            .take(MAX_LINT_LINES)
+            .map(|hint| Line::from(Span::styled(format!(" ⚠ {hint}"), theme::lint())))
+            .collect();
+        frame.render_widget(Paragraph::new(lines), chunks[chunk_index]);
+        chunk_index += 1;
+    }
+    let side_panes = usize::from(app.scratchpad_open)
+        + usize::from(app.cloudtrail.is_some())
+        + usize::from(app.explain.is_some());
+    let (results_area, side_area) = if side_panes > 0 {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Percentage(30)])
+            .split(chunks[chunk_index]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (chunks[chunk_index], None)
+    };
+    // The scratchpad, CloudTrail, and explanation panes share the side column.
+    let mut side_rows = side_area
+        .map(|area| {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Ratio(1, side_panes as u32); side_panes])
+                .split(area)
+                .to_vec()
+        })
+        .unwrap_or_default()
+        .into_iter();
+    let scratchpad_area = app.scratchpad_open.then(|| side_rows.next()).flatten();
+    let cloudtrail_area = app.cloudtrail.as_ref().and_then(|_| side_rows.next());
+    let explain_area = app.explain.as_ref().and_then(|_| side_rows.next());
+    let chips = app.filter_chips();
+    let results_area = if chips.is_empty() || results_area.height < 4 {
+        results_area
+    } else {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(results_area);
+        let chip_focus = app.chip_cursor.filter(|_| app.focus == FocusField::Results);
+        let mut spans = Vec::with_capacity(chips.len() * 2);
+        for (idx, (token, enabled)) in chips.into_iter().enumerate() {
+            let style = if chip_focus == Some(idx) {
+                theme::selection()
+            } else {
+                theme::chip(enabled)
+            };
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(format!(" {token} ×"), style));
+        }
+        if chip_focus.is_some() {
+            spans.push(Span::styled(
+                "  Space: On/off • -: Include/exclude • Del: Remove • Esc: Done",
+                theme::hint(),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), rows[0]);
+        rows[1]
+    };
+    if let Some(area) = scratchpad_area {
+        let focused = app.focus == FocusField::Scratchpad;
+        app.scratchpad.set_cursor_line_style(Style::default());
+        app.scratchpad.set_cursor_style(if focused {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        });
+        app.scratchpad
+            .set_block(input_block(i18n::text("title.scratchpad"), focused));
+        frame.render_widget(app.scratchpad.widget(), area);
+    }
+    if let (Some(area), Some(pane)) = (cloudtrail_area, &app.cloudtrail) {
+        render_cloudtrail(frame, area, pane);
+    }
+    if let (Some(area), Some(pane)) = (explain_area, &app.explain) {
+        render_explain(frame, area, pane);
+    }
 
     if let Some(top_chunk) = top_chunk {
         let mut top_constraints = Vec::new();
-        top_constraints.push(Constraint::Length(AWS_REGION_FIELD_WIDTH));
-        if app.show_profile_picker() {
+        if show_region {
+            top_constraints.push(Constraint::Length(AWS_REGION_FIELD_WIDTH));
+        }
+        if show_profile {
             top_constraints.push(Constraint::Length(40));
         }
-        top_constraints.push(Constraint::Length(18));
-        if app.relative_mode {
-            top_constraints.push(Constraint::Length(24));
-        } else {
-            top_constraints.push(Constraint::Length(28));
-            top_constraints.push(Constraint::Length(28));
+        if show_time_range {
+            top_constraints.push(Constraint::Length(18));
+            if app.relative_mode {
+                top_constraints.push(Constraint::Length(24));
+            } else {
+                top_constraints.push(Constraint::Length(28));
+                top_constraints.push(Constraint::Length(28));
+            }
         }
-        top_constraints.push(Constraint::Min(20));
+        // Without the log group the fixed-width blocks keep their size.
+        top_constraints.push(Constraint::Min(if show_log_group { 20 } else { 0 }));
 
         let top_row = Layout::default()
             .direction(Direction::Horizontal)
@@ -147,93 +309,155 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
 
         let mut column = 0;
 
-        let region_area = top_row[column];
-        column += 1;
-        render_input_field(
-            frame,
-            region_area,
-            "AWS region",
-            app.focus == FocusField::AwsRegion,
-            &app.aws_region_input,
-        );
+        if show_region {
+            let region_area = top_row[column];
+            column += 1;
+            render_input_field(
+                frame,
+                region_area,
+                &mnemonic_title(app, i18n::text("title.aws_region"), 'E'),
+                app.focus == FocusField::AwsRegion,
+                &app.aws_region_input,
+            );
+        }
 
-        if app.show_profile_picker() {
+        if show_profile {
             let area = top_row[column];
             column += 1;
-            let block = input_block("AWS profile", app.focus == FocusField::AwsProfile);
-            let display = app.selected_profile_name().unwrap_or("Auto");
-            let total = app.aws_profiles.len();
+            let block = input_block(
+                i18n::text("title.aws_profile"),
+                app.focus == FocusField::AwsProfile,
+            );
+            let display = match (app.selected_profile_group(), app.selected_profile_name()) {
+                (Some(group), _) => format!("{} ({} profiles)", group.name, group.profiles.len()),
+                (None, Some(name)) => name.to_string(),
+                (None, None) if app.profiles_loading => "Loading profiles…".to_string(),
+                (None, None) => "Auto".to_string(),
+            };
+            let total = app.profile_choices();
             let profile_text = if total > 1 {
-                let current = app.selected_profile_index.unwrap_or(0) + 1;
+                let current = app.selected_profile_position().unwrap_or(0) + 1;
                 format!("{display} ({current}/{total})")
             } else {
-                display.to_string()
+                display
             };
             let widget = Paragraph::new(profile_text).block(block);
             frame.render_widget(widget, area);
         }
 
-        let toggle_area = top_row[column];
-        column += 1;
-        let toggle_block = input_block("Time range", app.focus == FocusField::TimeMode);
-        let toggle_widget = Toggle::new("Relative", app.relative_mode)
-            .on_text("ON")
-            .off_text("OFF")
-            .focused(app.focus == FocusField::TimeMode)
-            .block(toggle_block);
-        frame.render_widget(toggle_widget, toggle_area);
-
-        if app.relative_mode {
-            let area = top_row[column];
+        if show_time_range {
+            let toggle_area = top_row[column];
             column += 1;
-            let block = input_block("Relative range", app.focus == FocusField::RelativeRange);
-            let style = if app.focus == FocusField::RelativeRange {
-                Style::default().add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            };
-            let label = app.current_relative_option().label;
-            let lines = vec![Line::from(Span::styled(label, style))];
-            let widget = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
-            frame.render_widget(widget, area);
-        } else {
-            render_input_field(
-                frame,
-                top_row[column],
-                "From (local)",
-                app.focus == FocusField::From,
-                &app.from_input,
+            let toggle_block = input_block(
+                mnemonic_title(app, i18n::text("title.time_range"), 'T'),
+                app.focus == FocusField::TimeMode,
             );
-            column += 1;
+            let toggle_widget = Toggle::new(i18n::text("toggle.relative"), app.relative_mode)
+                .on_text(i18n::text("toggle.on"))
+                .off_text(i18n::text("toggle.off"))
+                .focused(app.focus == FocusField::TimeMode)
+                .block(toggle_block);
+            frame.render_widget(toggle_widget, toggle_area);
 
+            if app.relative_mode {
+                let area = top_row[column];
+                column += 1;
+                let block = input_block(
+                    i18n::text("title.relative_range"),
+                    app.focus == FocusField::RelativeRange,
+                );
+                let style = if app.focus == FocusField::RelativeRange {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let label = app.current_relative_option().label;
+                let lines = vec![Line::from(Span::styled(label, style))];
+                let widget = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+                frame.render_widget(widget, area);
+            } else {
+                render_input_field(
+                    frame,
+                    top_row[column],
+                    &i18n::fill("title.from", &[("format", time_format::hint())]),
+                    app.focus == FocusField::From,
+                    &app.from_input,
+                );
+                column += 1;
+
+                render_input_field(
+                    frame,
+                    top_row[column],
+                    &i18n::fill("title.to", &[("format", time_format::hint())]),
+                    app.focus == FocusField::To,
+                    &app.to_input,
+                );
+                column += 1;
+            }
+        }
+
+        if show_log_group {
+            let log_group_title = match app.log_group_count() {
+                count if count > 1 => mnemonic_title(
+                    app,
+                    &i18n::fill("title.log_groups", &[("count", &count.to_string())]),
+                    'G',
+                ),
+                _ => mnemonic_title(app, i18n::text("title.log_group"), 'G'),
+            };
             render_input_field(
                 frame,
                 top_row[column],
-                "To (local)",
-                app.focus == FocusField::To,
-                &app.to_input,
+                &log_group_title,
+                app.focus == FocusField::LogGroup,
+                &app.log_group_input,
             );
-            column += 1;
+            if app.focus == FocusField::LogGroup {
+                if let Some(rest) = app.log_group_completion() {
+                    // Ghost text after the cursor; Right or End accepts it.
+                    let inner = input_block(&log_group_title, true).inner(top_row[column]);
+                    let typed = app.log_group_input.value().chars().count() as u16;
+                    if typed < inner.width {
+                        let area = Rect {
+                            x: inner.x + typed,
+                            width: inner.width - typed,
+                            ..inner
+                        };
+                        frame
+                            .render_widget(Paragraph::new(Span::styled(rest, theme::hint())), area);
+                    }
+                }
+            }
         }
-
-        render_input_field(
-            frame,
-            top_row[column],
-            "Log group",
-            app.focus == FocusField::LogGroup,
-            &app.log_group_input,
-        );
     }
 
-    let query_row = if let Some(query_chunk) = query_chunk {
-        let row = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(query_chunk);
-
+    let (query_area, filter_area) = match query_chunk {
+        Some(chunk) if show_query && show_filter => {
+            let row = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunk);
+            (Some(row[0]), Some(row[1]))
+        }
+        Some(chunk) if show_query => (Some(chunk), None),
+        chunk => (None, chunk),
+    };
+    if let Some(query_area) = query_area {
         app.query_area.set_cursor_line_style(Style::default());
-        let query_title = app.query_block_title();
-        let query_block = input_block(Cow::Owned(query_title), app.focus == FocusField::Query);
+        let mut query_title = app.query_block_title();
+        if zoomed {
+            query_title.push_str(" — zoomed (Esc/F11 returns)");
+        }
+        let mut query_block = input_block(Cow::Owned(query_title), app.focus == FocusField::Query);
+        if let Some(hint) = app
+            .query_function_hint()
+            .filter(|_| app.focus == FocusField::Query)
+        {
+            query_block = query_block.title(
+                Title::from(Span::styled(format!(" {hint} "), theme::hint()))
+                    .position(Position::Bottom),
+            );
+        }
         if app.focus == FocusField::Query {
             app.query_area
                 .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
@@ -242,8 +466,18 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             app.query_area.set_cursor_style(hidden_style);
         }
         app.query_area.set_block(query_block.clone());
-        frame.render_widget(app.query_area.widget(), row[0]);
-        let inner = query_block.inner(row[0]);
+        frame.render_widget(app.query_area.widget(), query_area);
+        let inner = query_block.inner(query_area);
+        if let Some(caret) = app.query_block.filter(|_| app.focus == FocusField::Query) {
+            render_block_caret(frame, app, inner, caret);
+        }
+        if app.focus == FocusField::Query {
+            if let Some(pair) =
+                query_pairs::matching_bracket(app.query_area.lines(), app.query_area.cursor())
+            {
+                render_bracket_match(frame, app, inner, pair);
+            }
+        }
         if inner.width > 0 && inner.height > 0 {
             let (cursor_row, cursor_col) = app.query_area.cursor();
             app.query_scroll_row =
@@ -251,10 +485,7 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             app.query_scroll_col =
                 next_scroll_position(app.query_scroll_col, cursor_col, inner.width);
         }
-        Some(row)
-    } else {
-        None
-    };
+    }
     let inner_height = results_area.height.saturating_sub(2) as usize;
     let has_table_rows = !app.results.rows.is_empty() && !app.filtered_indices.is_empty();
     let rows_height = if has_table_rows {
@@ -265,26 +496,75 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
     app.update_results_view_height(rows_height.max(1));
     let total_rows = app.results.rows.len();
     let visible_rows = app.filtered_indices.len();
-    let results_title = if total_rows > 0 {
+    let mut results_title = if total_rows > 0 {
         let mut metrics = vec![format!("{visible_rows}/{total_rows}")];
+        if let Some(every) = app.sample_every {
+            metrics.push(format!("sample 1/{every}"));
+        }
         if let Some(selected) = app
             .selected_filtered_index
             .filter(|_| !app.filtered_indices.is_empty())
         {
             metrics.push(format!("row {}", selected + 1));
         }
-        format!("Query results ({})", metrics.join(" · "))
+        if !app.marked_rows.is_empty() {
+            metrics.push(format!("{} marked", app.marked_rows.len()));
+        }
+        if app.baseline.is_some() {
+            metrics.push(format!("{} new vs baseline", app.new_pattern_rows.len()));
+        }
+        let spilled = app.results.spilled_rows();
+        if spilled > 0 {
+            metrics.push(format!("{spilled} on disk"));
+        }
+        i18n::fill(
+            "title.query_results_metrics",
+            &[("metrics", &metrics.join(" · "))],
+        )
     } else {
-        "Query results".to_string()
+        i18n::text("title.query_results").to_string()
     };
-    let mut results_block = Block::default().title(results_title).borders(Borders::ALL);
-    if app.focus == FocusField::Results {
-        results_block = results_block.border_style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+    // Always visible, even when the inputs and status rows are hidden.
+    if let Some(progress) = app.submission_progress() {
+        results_title = format!("{results_title} — {progress}");
+    }
+    if app.key_macro.is_recording() {
+        results_title = format!("{results_title} — recording macro (q stops)");
+    }
+    let mut results_block = theme::block().title(results_title);
+    if let Some(preset) = app.current_preset() {
+        results_block = results_block.title(
+            Title::from(Span::styled(
+                format!(" {} ", preset.name.to_uppercase()),
+                theme::environment(preset.is_production()),
+            ))
+            .alignment(Alignment::Center),
         );
     }
+    if app.results_stale() {
+        let badge = i18n::fill("hint.stale", &[("run", &app.run_key_hint())]);
+        results_block = results_block
+            .title(Title::from(Span::styled(badge, theme::error())).alignment(Alignment::Right));
+    }
+    if let Some(badge) = app.cached_results_badge() {
+        results_block = results_block.title(
+            Title::from(Span::styled(
+                format!(" {badge} ({REFRESH_KEY_HINT} to refresh) "),
+                theme::hint(),
+            ))
+            .alignment(Alignment::Right),
+        );
+    }
+    if let Some(last_run) = app.last_run.as_ref() {
+        results_block = results_block.title(
+            Title::from(Span::styled(last_run.summary(), theme::hint()))
+                .position(Position::Bottom)
+                .alignment(Alignment::Right),
+        );
+    }
+    if app.focus == FocusField::Results {
+        results_block = results_block.border_style(theme::focus_border());
+    }
 
     if app.results.rows.is_empty() {
         let message = if app.results_initialized {
@@ -320,39 +600,59 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
         let start = app.results_scroll.min(filtered_len.saturating_sub(1));
         let end = (start + view_height).min(filtered_len);
         let visible_slice = &app.filtered_indices[start..end];
-        let rows: Vec<Row> = visible_slice
-            .iter()
-            .enumerate()
-            .map(|(offset, &idx)| {
-                let position = start + offset;
+        let mut rows: Vec<Row> = Vec::with_capacity(visible_slice.len());
+        let mut selected_row = None;
+        for (offset, &idx) in visible_slice.iter().enumerate() {
+            let position = start + offset;
+            for marker in app.markers_before(position) {
+                rows.push(marker_row(&marker.name, visible_columns.len()));
+            }
+            if Some(position) == selected_idx {
+                selected_row = Some(rows.len());
+            }
+            rows.push({
                 let row = &app.results.rows[idx];
+                let cells = app.results.cells(idx).unwrap_or_default();
                 let lens_active = Some(position) == selected_idx;
+                let noted = app.row_notes.contains_key(&idx);
                 let row_cells: Vec<Cell> = visible_columns
                     .iter()
-                    .filter_map(|&col_idx| row.cells.get(col_idx))
-                    .map(|value| {
-                        if lens_active {
-                            let style = Style::default()
-                                .fg(Color::Black)
-                                .add_modifier(Modifier::BOLD);
-                            Cell::from(value.clone()).style(style)
+                    .filter_map(|&col_idx| Some((col_idx, cells.get(col_idx)?)))
+                    .enumerate()
+                    .map(|(cell_idx, (col_idx, value))| {
+                        let highlight = app.cell_highlight(col_idx, value);
+                        let value = match row.preview(col_idx) {
+                            Some(preview) => Cow::Borrowed(preview),
+                            None => app.display_cell(col_idx, value),
+                        };
+                        let text = if noted && cell_idx == 0 {
+                            format!("{NOTE_MARKER}{value}")
                         } else {
-                            Cell::from(value.clone())
+                            value.into_owned()
+                        };
+                        match highlight {
+                            Some(color) => Cell::from(text).style(theme::highlight(color)),
+                            None if lens_active => Cell::from(text).style(theme::selection_cell()),
+                            None => Cell::from(text),
                         }
                     })
                     .collect();
                 let mut table_row = Row::new(row_cells);
                 if lens_active {
-                    table_row = table_row.style(
-                        Style::default()
-                            .bg(Color::Rgb(255, 246, 199))
-                            .fg(Color::Black)
-                            .add_modifier(Modifier::BOLD),
-                    );
+                    table_row = table_row.style(theme::selection());
+                } else if app.marked_rows.contains(&idx) {
+                    table_row = table_row.style(theme::marked());
+                } else if app.new_pattern_rows.contains(&idx) {
+                    table_row = table_row.style(theme::new_pattern());
                 }
                 table_row
-            })
-            .collect();
+            });
+        }
+        // Marker lines take room from the page; drop rows off the top to keep the selection shown.
+        let overflow = rows.len().saturating_sub(view_height);
+        let skip = selected_row.map_or(0, |selected| (selected + 1).saturating_sub(view_height));
+        rows.drain(..skip.min(overflow));
+        rows.truncate(view_height);
         let widths: Vec<Constraint> = visible_columns
             .iter()
             .map(|&col| {
@@ -370,28 +670,88 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
         frame.render_widget(table, results_area);
     }
 
-    if let Some(query_row) = &query_row {
+    if let Some(filter_area) = filter_area {
         if app.filter_active {
+            let mut title = mnemonic_title(app, i18n::text("title.filter"), 'F');
+            if app.filter_job.is_some() {
+                title = i18n::fill("title.filter_running", &[("title", &title)]);
+            } else if let Some((matches, total)) = app.filter_preview {
+                title = i18n::fill(
+                    "title.filter_preview",
+                    &[
+                        ("title", &title),
+                        ("matches", &matches.to_string()),
+                        ("total", &total.to_string()),
+                    ],
+                );
+            }
             render_input_field(
                 frame,
-                query_row[1],
-                "Filter",
+                filter_area,
+                &title,
                 app.focus == FocusField::Filter,
                 &app.filter_input,
             );
         } else {
             // Clear the right-hand side when the filter is hidden
-            let empty_block = Block::default().title("Filter").borders(Borders::ALL);
-            frame.render_widget(empty_block, query_row[1]);
+            let empty_block =
+                theme::block().title(mnemonic_title(app, i18n::text("title.filter"), 'F'));
+            frame.render_widget(empty_block, filter_area);
         }
     }
 
-    if app.help_open {
+    if let Some(prompt) = app.quit_prompt.as_deref() {
+        render_confirm_prompt(
+            frame,
+            i18n::text("title.quit"),
+            prompt,
+            i18n::text("hint.quit"),
+        );
+    } else if let Some(prompt) = app.run_prompt.as_deref() {
+        render_confirm_prompt(frame, "Production", prompt, "y/Enter: Run • n/Esc: Cancel");
+    } else if let Some(input) = app.rename_input.as_ref() {
+        render_input_dialog(
+            frame,
+            "Rename workspace",
+            input,
+            "Enter: Save • Esc: Cancel • empty: automatic title",
+        );
+    } else if let Some(input) = app.value_field_input.as_ref() {
+        render_input_dialog(
+            frame,
+            "Field to pick values from",
+            input,
+            "Enter: Sample values • Esc: Cancel",
+        );
+    } else if let Some(input) = app.variables_input.as_ref() {
+        render_input_dialog(
+            frame,
+            "Workspace variables",
+            input,
+            "name = value; name = value • used as {{name}} • Enter: Save • Esc: Cancel",
+        );
+    } else if let Some(input) = app.computed_input.as_ref() {
+        render_input_dialog(
+            frame,
+            "Computed column (Name = expression)",
+            input,
+            "e.g. Seconds = col(\"Elapsed\") / 1000 • Name = removes • Enter: Add • Esc: Cancel",
+        );
+    } else if let Some(picker) = app.value_picker.as_ref() {
+        render_value_picker(frame, picker);
+    } else if let Some((_, input)) = app.note_input.as_ref() {
+        render_input_dialog(
+            frame,
+            "Row note",
+            input,
+            "Enter: Save • Esc: Cancel • empty: remove note",
+        );
+    } else if app.help_open {
         let overlay = centered_rect(80, 85, frame.size());
         frame.render_widget(Clear, overlay);
 
         let heading_style = Style::default().add_modifier(Modifier::BOLD);
-        let help_lines: Vec<Line> = help::HELP_TEXT
+        let help_lines: Vec<Line> = i18n::help_text()
             .lines()
             .map(|line| {
                 if let Some(text) = line.strip_prefix("## ") {
@@ -405,9 +765,8 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             .collect();
 
         let help = Paragraph::new(help_lines).wrap(Wrap { trim: false }).block(
-            Block::default()
-                .title("Help")
-                .borders(Borders::ALL)
+            theme::block()
+                .title(i18n::text("title.help"))
                 .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
         );
         frame.render_widget(help, overlay);
@@ -419,39 +778,76 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             let widget = ColumnVisibilityModal::new(headers.as_slice());
             frame.render_stateful_widget(widget, overlay, state);
         }
+    } else if let Some(state) = app.input_pane_picker.as_mut() {
+        let overlay = centered_rect(50, 40, frame.size());
+        frame.render_widget(Clear, overlay);
+        let labels: Vec<String> = InputPane::ALL
+            .iter()
+            .map(|pane| pane.label().to_string())
+            .collect();
+        let widget = ColumnVisibilityModal::new(&labels).title(i18n::text("title.input_panes"));
+        frame.render_stateful_widget(widget, overlay, state);
     } else if app.open_dialog_active() {
         render_open_dialog(frame, app);
     } else if app.save_dialog_active() {
         render_save_dialog(frame, app);
+    } else if app.palette_active() {
+        render_palette(frame, app);
+    } else if let Some(diff) = app.query_diff.as_ref() {
+        render_query_diff(frame, diff);
+    } else if let Some(scroll) = app.trace_scroll {
+        render_query_trace(frame, app, scroll);
+    } else if let Some((_, stats)) = app.column_stats.as_ref() {
+        render_column_stats(frame, stats);
+    } else if let Some(view) = app.clusters.as_ref() {
+        render_clusters(frame, view);
+    } else if app.date_picker_active() {
+        render_date_picker(frame, app);
     } else if app.modal_open {
         if let Some(details) = app.selected_row_data() {
             let overlay = centered_rect(80, 70, frame.size());
             frame.render_widget(Clear, overlay);
 
             let mut detail_lines: Vec<Line> = Vec::new();
+            if let Some(note) = app.selected_row_note() {
+                detail_lines.push(Line::from(Span::styled(
+                    format!("Note: {note}"),
+                    theme::marked(),
+                )));
+            }
             detail_lines.push(Line::from(""));
-            for (header, value) in details.iter() {
-                let header_span = Span::styled(
-                    format!("{header}:"),
-                    Style::default().add_modifier(Modifier::BOLD),
-                );
-                let rendered = if header == "@message" {
-                    format_modal_message(value)
+            let mut selected_line = 0;
+            for (field_idx, (header, value)) in details.iter().enumerate() {
+                let selected = field_idx == app.modal_field;
+                if selected {
+                    selected_line = detail_lines.len();
+                }
+                let header_style = if selected {
+                    theme::selection()
                 } else {
-                    format_modal_value(value)
+                    Style::default().add_modifier(Modifier::BOLD)
                 };
+                let header_span = Span::styled(format!("{header}:"), header_style);
+                let rendered = app.modal_value_lines(header, value);
+                let plugin = header == "@message" && app.modal_renderer().is_some();
+                let json = !app.modal_raw && !plugin && looks_like_json(value);
                 if rendered.is_empty() {
                     detail_lines.push(Line::from(vec![header_span.clone(), Span::raw(" <empty>")]));
                 } else {
                     for (idx, line) in rendered.iter().enumerate() {
-                        if idx == 0 {
-                            detail_lines.push(Line::from(vec![
-                                header_span.clone(),
-                                Span::raw(format!(" {line}")),
-                            ]));
+                        let mut spans = if idx == 0 {
+                            vec![header_span.clone(), Span::raw(" ")]
+                        } else {
+                            vec![Span::raw("    ")]
+                        };
+                        if json {
+                            spans.extend(json_tokens(line).into_iter().map(|(token, text)| {
+                                Span::styled(text.to_string(), theme::json(token))
+                            }));
                         } else {
-                            detail_lines.push(Line::from(format!("    {line}")));
+                            spans.push(Span::raw(line.clone()));
                         }
+                        detail_lines.push(Line::from(spans));
                     }
                 }
                 detail_lines.push(Line::from(""));
@@ -462,35 +858,69 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             }
 
             detail_lines.push(Line::from(""));
+            let scroll_hint = if app.modal_wrap {
+                ""
+            } else {
+                i18n::text("hint.row_detail_scroll")
+            };
+            let link_hint = if app.modal_url().is_some() {
+                i18n::text("hint.row_detail_link")
+            } else {
+                ""
+            };
+            let wrap_hint = if app.modal_wrap {
+                i18n::text("hint.no_wrap")
+            } else {
+                i18n::text("hint.wrap")
+            };
             detail_lines.push(Line::from(Span::styled(
-                "C: Copy • Enter/Esc: Close",
-                Style::default().fg(Color::DarkGray),
+                i18n::fill(
+                    "hint.row_detail",
+                    &[
+                        ("scroll", scroll_hint),
+                        ("link", link_hint),
+                        ("wrap", wrap_hint),
+                    ],
+                ),
+                theme::hint(),
             )));
 
-            let modal = Paragraph::new(detail_lines)
-                .wrap(Wrap { trim: false })
-                .block(
-                    Block::default()
-                        .title("Row detail")
-                        .borders(Borders::ALL)
-                        .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
-                );
+            // Keep the selected field in view; wrapped lines make this approximate.
+            let visible = overlay.height.saturating_sub(4) as usize;
+            let scroll = selected_line.saturating_sub(visible / 3);
+            let mut modal = Paragraph::new(detail_lines).scroll((scroll as u16, app.modal_hscroll));
+            if app.modal_wrap {
+                modal = modal.wrap(Wrap { trim: false });
+            }
+            let modal = modal.block(
+                theme::block()
+                    .title(if app.modal_raw {
+                        i18n::text("title.row_detail_raw").to_string()
+                    } else if let Some(plugin) = app.modal_renderer() {
+                        i18n::fill("title.row_detail_plugin", &[("plugin", plugin)])
+                    } else {
+                        i18n::text("title.row_detail").to_string()
+                    })
+                    .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
+            );
             frame.render_widget(modal, overlay);
         }
     }
 }
 
+fn mnemonic_title(app: &App, title: &str, key: char) -> String {
+    if app.show_mnemonics {
+        format!("{title} [Alt+{key}]")
+    } else {
+        title.to_string()
+    }
+}
+
 fn input_block<'a>(title: impl Into<Cow<'a, str>>, focused: bool) -> Block<'a> {
     let title_cow: Cow<'a, str> = title.into();
-    let base = Block::default()
-        .title(Line::from(title_cow.into_owned()))
-        .borders(Borders::ALL);
+    let base = theme::block().title(Line::from(title_cow.into_owned()));
     if focused {
-        base.border_style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
+        base.border_style(theme::focus_border())
     } else {
         base
     }
@@ -556,8 +986,11 @@ fn render_save_dialog(frame: &mut Frame, app: &mut App) {
     };
     let title = match state.mode {
         SaveDialogMode::Save => "Save query",
+        SaveDialogMode::Snapshot => "Save results snapshot",
+        SaveDialogMode::Export => "Export filtered results",
+        SaveDialogMode::S3Export => "Export log group to S3",
     };
-    let block = Block::default().title(title).borders(Borders::ALL);
+    let block = theme::block().title(title);
     let inner = block.inner(overlay);
     frame.render_widget(block, overlay);
     if inner.width == 0 || inner.height == 0 {
@@ -571,17 +1004,25 @@ fn render_save_dialog(frame: &mut Frame, app: &mut App) {
             Constraint::Length(1),
         ])
         .split(inner);
-    render_dialog_input(frame, chunks[0], "File name", &state.input);
+    let input_title = match state.mode {
+        SaveDialogMode::S3Export => "Destination (bucket[/prefix])",
+        _ => "File name",
+    };
+    render_dialog_input(frame, chunks[0], input_title, &state.input);
     render_save_dialog_list(frame, chunks[1], state);
-    let hint = Paragraph::new("↑/↓ select existing • Enter: Save • Esc: Cancel")
-        .style(Style::default().fg(Color::DarkGray));
+    let hint_text = match state.mode {
+        SaveDialogMode::Export => Cow::Owned(format!(
+            "Type sets format ({SUPPORTED_EXTENSIONS}) • Enter: Export • Esc: Cancel"
+        )),
+        SaveDialogMode::S3Export => Cow::Borrowed("Enter: Start export task • Esc: Cancel"),
+        _ => Cow::Borrowed("↑/↓ select existing • Enter: Save • Esc: Cancel"),
+    };
+    let hint = Paragraph::new(hint_text).style(theme::hint());
     frame.render_widget(hint, chunks[2]);
 }
 
 fn render_save_dialog_list(frame: &mut Frame, area: Rect, state: &mut SaveDialogState) {
-    let list_block = Block::default()
-        .title("Existing files")
-        .borders(Borders::ALL);
+    let list_block = theme::block().title("Existing files");
     let inner = list_block.inner(area);
     frame.render_widget(list_block, area);
     if inner.width == 0 || inner.height == 0 {
@@ -589,10 +1030,16 @@ fn render_save_dialog_list(frame: &mut Frame, area: Rect, state: &mut SaveDialog
     }
     let mut lines: Vec<Line> = Vec::new();
     if state.entries.is_empty() {
-        lines.push(Line::from(Span::styled(
-            "No saved queries found",
-            Style::default().fg(Color::DarkGray),
-        )));
+        let message = match state.mode {
+            SaveDialogMode::Save => "No saved queries found",
+            SaveDialogMode::Snapshot => "No saved snapshots found",
+            SaveDialogMode::Export => "No previous exports found",
+            SaveDialogMode::S3Export => {
+                "Creates a CloudWatch Logs export task for the log group and time range of the \
+                 last query. The bucket policy must allow logs.amazonaws.com to write."
+            }
+        };
+        lines.push(Line::from(Span::styled(message, theme::hint())));
     } else {
         let view_height = inner.height.max(1) as usize;
         let (start, end) = state.visible_bounds(view_height);
@@ -601,10 +1048,7 @@ fn render_save_dialog_list(frame: &mut Frame, area: Rect, state: &mut SaveDialog
                 let selected = state.selected_index == Some(idx);
                 let prefix = if selected { ">" } else { " " };
                 let style = if selected {
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Rgb(255, 246, 199))
-                        .add_modifier(Modifier::BOLD)
+                    theme::selection()
                 } else {
                     Style::default()
                 };
@@ -615,7 +1059,7 @@ fn render_save_dialog_list(frame: &mut Frame, area: Rect, state: &mut SaveDialog
             }
         }
     }
-    let list = Paragraph::new(lines);
+    let list = Paragraph::new(lines).wrap(Wrap { trim: true });
     frame.render_widget(list, inner);
 }
 
@@ -625,9 +1069,12 @@ fn render_open_dialog(frame: &mut Frame, app: &mut App) {
     let Some(state) = app.open_dialog_state_mut() else {
         return;
     };
-    let block = Block::default()
-        .title("Open query")
-        .borders(Borders::ALL)
+    let title = match state.mode {
+        OpenDialogMode::Query => "Open query",
+        OpenDialogMode::Snapshot => "Open results snapshot",
+    };
+    let block = theme::block()
+        .title(title)
         .padding(ratatui::widgets::Padding::new(1, 1, 1, 1));
     let inner = block.inner(overlay);
     frame.render_widget(block, overlay);
@@ -646,14 +1093,16 @@ fn render_open_dialog(frame: &mut Frame, app: &mut App) {
     let list_area = chunks[1];
     render_open_dialog_list(frame, list_area, state);
     let hint = Paragraph::new("↑/↓ select • Type to filter • Enter: Open • Esc: Cancel")
-        .style(Style::default().fg(Color::DarkGray));
+        .style(theme::hint());
     frame.render_widget(hint, chunks[2]);
 }
 
 fn render_open_dialog_list(frame: &mut Frame, area: Rect, state: &mut OpenDialogState) {
-    let list_block = Block::default()
-        .title("Saved queries")
-        .borders(Borders::ALL);
+    let list_title = match state.mode {
+        OpenDialogMode::Query => "Saved queries",
+        OpenDialogMode::Snapshot => "Saved snapshots",
+    };
+    let list_block = theme::block().title(list_title);
     let inner = list_block.inner(area);
     frame.render_widget(list_block, area);
     if inner.width == 0 || inner.height == 0 {
@@ -662,8 +1111,8 @@ fn render_open_dialog_list(frame: &mut Frame, area: Rect, state: &mut OpenDialog
     let mut lines: Vec<Line> = Vec::new();
     if state.filtered_indices.is_empty() {
         lines.push(Line::from(Span::styled(
-            "No saved queries match the filter",
-            Style::default().fg(Color::DarkGray),
+            "No saved files match the filter",
+            theme::hint(),
         )));
     } else {
         let view_height = inner.height.max(1) as usize;
@@ -682,10 +1131,7 @@ fn render_open_dialog_list(frame: &mut Frame, area: Rect, state: &mut OpenDialog
                     " "
                 };
                 let style = if Some(filtered_idx) == selected {
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Rgb(255, 246, 199))
-                        .add_modifier(Modifier::BOLD)
+                    theme::selection()
                 } else {
                     Style::default()
                 };
@@ -700,8 +1146,461 @@ fn render_open_dialog_list(frame: &mut Frame, area: Rect, state: &mut OpenDialog
     frame.render_widget(list, inner);
 }
 
+fn render_confirm_prompt(frame: &mut Frame, title: &str, prompt: &str, hint: &str) {
+    let area = frame.size();
+    let width = (prompt.chars().count() as u16 + 4).max(30).min(area.width);
+    let height = 4.min(area.height);
+    let overlay = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, overlay);
+    let lines = vec![
+        Line::from(prompt.to_string()),
+        Line::from(Span::styled(hint.to_string(), theme::hint())),
+    ];
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        theme::block()
+            .title(title.to_string())
+            .border_style(theme::focus_border()),
+    );
+    frame.render_widget(paragraph, overlay);
+}
+
+fn render_column_stats(frame: &mut Frame, stats: &ColumnStats) {
+    let mut lines: Vec<Line> = stats.lines().into_iter().map(Line::from).collect();
+    lines.push(Line::from(Span::styled(
+        "Left/Right: Column • s/Esc: Close",
+        theme::hint(),
+    )));
+    let area = frame.size();
+    let width = 72.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let overlay = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, overlay);
+    let block = theme::block()
+        .title(format!("Stats: {}", stats.header))
+        .border_style(theme::focus_border());
+    frame.render_widget(Paragraph::new(lines).block(block), overlay);
+}
+
+fn render_cloudtrail(frame: &mut Frame, area: Rect, pane: &CloudTrailPane) {
+    let log_group = [("log_group", pane.log_group.as_str())];
+    let (title, lines) = match &pane.events {
+        None => (
+            i18n::fill("title.cloudtrail_running", &log_group),
+            Vec::new(),
+        ),
+        Some(Err(err)) => (
+            i18n::fill("title.cloudtrail", &log_group),
+            vec![Line::from(Span::styled(err.clone(), theme::error()))],
+        ),
+        Some(Ok(events)) if events.is_empty() => (
+            i18n::fill("title.cloudtrail", &log_group),
+            vec![Line::from(Span::styled(
+                i18n::text("hint.cloudtrail_empty"),
+                theme::hint(),
+            ))],
+        ),
+        Some(Ok(events)) => (
+            i18n::fill(
+                "title.cloudtrail_changes",
+                &[
+                    ("log_group", pane.log_group.as_str()),
+                    ("count", &events.len().to_string()),
+                ],
+            ),
+            events
+                .iter()
+                .flat_map(|event| {
+                    [
+                        Line::from(Span::styled(event.time.clone(), theme::hint())),
+                        Line::from(format!("  {}", event.summary)),
+                    ]
+                })
+                .collect(),
+        ),
+    };
+    let widget = Paragraph::new(lines)
+        .block(theme::block().title(title))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(widget, area);
+}
+
+fn render_explain(frame: &mut Frame, area: Rect, pane: &ExplainPane) {
+    let mut lines = vec![Line::from(Span::styled(
+        pane.subject.clone(),
+        theme::hint(),
+    ))];
+    match &pane.reply {
+        None => lines.push(Line::from(Span::styled(
+            "Waiting for a reply…",
+            theme::hint(),
+        ))),
+        Some(Err(err)) => lines.push(Line::from(Span::styled(err.clone(), theme::error()))),
+        Some(Ok(reply)) => lines.extend(reply.lines().map(|line| Line::from(line.to_string()))),
+    }
+    let widget = Paragraph::new(lines)
+        .block(theme::block().title(i18n::text("title.explanation")))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(widget, area);
+}
+
+fn render_query_trace(frame: &mut Frame, app: &App, scroll: usize) {
+    let trace = app.query_watch.trace();
+    let mut lines = Vec::new();
+    for call in &trace {
+        let timing = match call.elapsed {
+            Some(elapsed) => format!("{} ms", elapsed.as_millis()),
+            None => "waiting".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("+{:>7.3}s  ", call.at.as_secs_f64())),
+            Span::styled(call.call, Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  {timing}"), theme::hint()),
+        ]));
+        if !call.request.is_empty() {
+            lines.push(Line::from(format!("    → {}", call.request)));
+        }
+        if !call.response.is_empty() {
+            lines.push(Line::from(format!("    ← {}", call.response)));
+        }
+    }
+    let overlay = centered_rect(80, 70, frame.size());
+    frame.render_widget(Clear, overlay);
+    let block = theme::block()
+        .title(match trace.len() {
+            1 => i18n::text("title.query_trace_one").to_string(),
+            count => i18n::fill("title.query_trace", &[("count", &count.to_string())]),
+        })
+        .title(
+            Title::from(Span::styled(
+                " Up/Down/PgUp/PgDn: Scroll • Esc: Close ",
+                theme::hint(),
+            ))
+            .position(Position::Bottom),
+        )
+        .border_style(theme::focus_border());
+    let scroll = scroll.min(lines.len().saturating_sub(1)) as u16;
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0));
+    frame.render_widget(widget, overlay);
+}
+
+fn render_query_diff(frame: &mut Frame, diff: &QueryDiff) {
+    let lines: Vec<Line> = diff
+        .lines
+        .iter()
+        .map(|line| match line {
+            DiffLine::Hunk(header) => Line::from(Span::styled(header.as_str(), theme::hint())),
+            DiffLine::Context(text) => Line::from(format!(" {text}")),
+            DiffLine::Removed(text) => {
+                Line::from(Span::styled(format!("-{text}"), theme::diff(false)))
+            }
+            DiffLine::Added(text) => {
+                Line::from(Span::styled(format!("+{text}"), theme::diff(true)))
+            }
+        })
+        .collect();
+    let overlay = centered_rect(80, 70, frame.size());
+    frame.render_widget(Clear, overlay);
+    let block = theme::block()
+        .title(i18n::fill(
+            "title.query_diff",
+            &[
+                ("file", diff.file.as_str()),
+                ("added", &diff.added().to_string()),
+                ("removed", &diff.removed().to_string()),
+            ],
+        ))
+        .title(
+            Title::from(Span::styled(
+                " Up/Down/PgUp/PgDn: Scroll • r: Revert to file • Esc: Close ",
+                theme::hint(),
+            ))
+            .position(Position::Bottom),
+        )
+        .border_style(theme::focus_border());
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .scroll((diff.scroll as u16, 0));
+    frame.render_widget(widget, overlay);
+}
+
+/// Most clusters the popover lists at once; the list scrolls with the selection.
+const CLUSTER_ROWS: usize = 15;
+
+fn render_clusters(frame: &mut Frame, view: &ClusterView) {
+    let area = frame.size();
+    let width = 100.min(area.width);
+    let text_width = width.saturating_sub(12) as usize;
+    let scroll = (view.selected + 1).saturating_sub(CLUSTER_ROWS);
+    let mut lines: Vec<Line> = view
+        .clusters
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(CLUSTER_ROWS)
+        .map(|(idx, cluster)| {
+            let shown: String = cluster.fingerprint.chars().take(text_width).collect();
+            let line = Line::from(format!("{:>7}  {shown}", cluster.count));
+            if idx == view.selected {
+                line.style(theme::selection())
+            } else {
+                line
+            }
+        })
+        .collect();
+    if let Some(cluster) = view.selected_cluster() {
+        let example: String = cluster.example.chars().take(text_width).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("e.g. {example}"),
+            theme::hint(),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "Up/Down: Cluster • Enter: Go to example • g/Esc: Close",
+        theme::hint(),
+    )));
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let overlay = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, overlay);
+    let block = theme::block()
+        .title(format!(
+            "{} clusters of {}",
+            view.clusters.len(),
+            view.header
+        ))
+        .border_style(theme::focus_border());
+    frame.render_widget(Paragraph::new(lines).block(block), overlay);
+}
+
+/// Most value rows the picker shows at once; the list scrolls with the selection.
+const VALUE_PICKER_ROWS: usize = 15;
+
+fn render_value_picker(frame: &mut Frame, picker: &ValuePicker) {
+    let mut lines = Vec::new();
+    match picker.values.as_ref() {
+        None => lines.push(Line::from(format!(
+            "Sampling the last {} minutes of the range...",
+            value_picker::SAMPLE_WINDOW_MINUTES
+        ))),
+        Some(values) => {
+            let scroll = (picker.selected + 1).saturating_sub(VALUE_PICKER_ROWS);
+            for (idx, (value, count)) in values
+                .iter()
+                .enumerate()
+                .skip(scroll)
+                .take(VALUE_PICKER_ROWS)
+            {
+                let mark = if picker.is_chosen(idx) { "[x]" } else { "[ ]" };
+                let shown: String = value.chars().take(50).collect();
+                let line = Line::from(format!("{mark} {shown}  ({count})"));
+                lines.push(if idx == picker.selected {
+                    line.style(theme::selection())
+                } else {
+                    line
+                });
+            }
+        }
+    }
+    lines.push(Line::from(Span::styled(
+        "Space: Tick • Enter: Insert filter • Esc: Cancel",
+        theme::hint(),
+    )));
+    let area = frame.size();
+    let width = 72.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let overlay = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, overlay);
+    let block = theme::block()
+        .title(format!("Values of {}", picker.field))
+        .border_style(theme::focus_border());
+    frame.render_widget(Paragraph::new(lines).block(block), overlay);
+}
+
+/// Emphasise a bracket pair that is on screen.
+fn render_bracket_match(frame: &mut Frame, app: &App, inner: Rect, pair: [(usize, usize); 2]) {
+    let (scroll_row, scroll_col) = (app.query_scroll_row as usize, app.query_scroll_col as usize);
+    for (row, col) in pair {
+        let (Some(y), Some(x)) = (row.checked_sub(scroll_row), col.checked_sub(scroll_col)) else {
+            continue;
+        };
+        if y < inner.height as usize && x < inner.width as usize {
+            let cell = frame
+                .buffer_mut()
+                .get_mut(inner.x + x as u16, inner.y + y as u16);
+            cell.set_style(theme::bracket_match());
+        }
+    }
+}
+
+/// Mark the block-edit column on every line of the block that is on screen.
+fn render_block_caret(frame: &mut Frame, app: &App, inner: Rect, caret: BlockCaret) {
+    let (scroll_row, scroll_col) = (app.query_scroll_row as usize, app.query_scroll_col as usize);
+    let Some(x) = caret
+        .col()
+        .checked_sub(scroll_col)
+        .filter(|&offset| offset < inner.width as usize)
+        .map(|offset| inner.x + offset as u16)
+    else {
+        return;
+    };
+    for row in caret.rows() {
+        let Some(offset) = row
+            .checked_sub(scroll_row)
+            .filter(|&offset| offset < inner.height as usize)
+        else {
+            continue;
+        };
+        let cell = frame.buffer_mut().get_mut(x, inner.y + offset as u16);
+        cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+}
+
+/// Full-width separator naming a timeline marker.
+fn marker_row(name: &str, columns: usize) -> Row<'static> {
+    let cells = (0..columns).map(|column| {
+        if column == 0 {
+            Cell::from(format!("── {name} ──────────"))
+        } else {
+            Cell::from("─".repeat(200))
+        }
+    });
+    Row::new(cells).style(theme::timeline_marker())
+}
+
+/// Single-line text prompt centered over the screen.
+fn render_input_dialog(frame: &mut Frame, title: &str, input: &SingleLineInput, hint: &str) {
+    let area = frame.size();
+    let width = 60.min(area.width);
+    let height = 4.min(area.height);
+    let overlay = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    frame.render_widget(Clear, overlay);
+    let block = theme::block()
+        .title(title)
+        .border_style(theme::focus_border());
+    let inner = block.inner(overlay);
+    let lines = vec![
+        Line::from(input.value().to_string()),
+        Line::from(Span::styled(hint, theme::hint())),
+    ];
+    frame.render_widget(Paragraph::new(lines).block(block), overlay);
+    if inner.width > 0 && inner.height > 0 {
+        let cursor = input.visual_cursor().min(inner.width as usize - 1);
+        frame.set_cursor(inner.x + cursor as u16, inner.y);
+    }
+}
+
+fn render_date_picker(frame: &mut Frame, app: &mut App) {
+    let Some((field, state)) = app.date_picker.as_mut() else {
+        return;
+    };
+    let (width, height) = DATE_PICKER_SIZE;
+    let area = frame.size();
+    let overlay = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+    frame.render_widget(Clear, overlay);
+    let title = if *field == FocusField::To {
+        "Pick To (local)"
+    } else {
+        "Pick From (local)"
+    };
+    frame.render_stateful_widget(DatePicker::new(title), overlay, state);
+}
+
+fn render_palette(frame: &mut Frame, app: &mut App) {
+    let overlay = centered_rect(60, 60, frame.size());
+    frame.render_widget(Clear, overlay);
+    let Some(state) = app.palette.as_mut() else {
+        return;
+    };
+    let block = theme::block()
+        .title(i18n::text("title.command_palette"))
+        .padding(ratatui::widgets::Padding::new(1, 1, 1, 1));
+    let inner = block.inner(overlay);
+    frame.render_widget(block, overlay);
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+    render_dialog_input(frame, chunks[0], "Search commands", &state.filter_input);
+    render_palette_list(frame, chunks[1], state);
+    let hint = Paragraph::new("↑/↓ select • Type to search • Enter: Run • Esc: Cancel")
+        .style(theme::hint());
+    frame.render_widget(hint, chunks[2]);
+}
+
+fn render_palette_list(frame: &mut Frame, area: Rect, state: &mut PaletteState) {
+    if state.matches.is_empty() {
+        let empty = Paragraph::new(Span::styled("No matching commands", theme::hint()));
+        frame.render_widget(empty, area);
+        return;
+    }
+    let (start, end) = state.visible_bounds(area.height as usize);
+    let width = area.width as usize;
+    let lines: Vec<Line> = (start..end)
+        .map(|idx| {
+            let action = state.matches[idx];
+            let selected = idx == state.selected;
+            let prefix = if selected { ">" } else { " " };
+            let label = format!("{prefix} {}", action.label());
+            let shortcut = action.shortcut().unwrap_or_default();
+            let gap = width
+                .saturating_sub(label.chars().count() + shortcut.chars().count())
+                .max(1);
+            let style = if selected {
+                theme::selection()
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(format!("{label}{}", " ".repeat(gap)), style),
+                Span::styled(shortcut.to_string(), theme::hint()),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
 fn render_dialog_input(frame: &mut Frame, area: Rect, title: &str, input: &SingleLineInput) {
-    let block = Block::default().title(title).borders(Borders::ALL);
+    let block = theme::block().title(title);
     let inner = block.inner(area);
     let widget = Paragraph::new(input.value()).block(block.clone());
     frame.render_widget(widget, area);
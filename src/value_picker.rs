@@ -0,0 +1,123 @@
+use crate::log_fetcher::LogRecord;
+
+/// The sampling query covers this much time, ending where the chosen range ends.
+pub const SAMPLE_WINDOW_MINUTES: i64 = 60;
+/// Distinct values the sampling query asks for, most frequent first.
+const SAMPLE_LIMIT: usize = 50;
+/// Column the sampling query counts into.
+const COUNT_COLUMN: &str = "samples";
+
+/// `stats count(*) by field` query listing the field's most common values.
+pub fn sample_query(field: &str) -> String {
+    format!(
+        "stats count(*) as {COUNT_COLUMN} by {}\n| sort {COUNT_COLUMN} desc\n| limit {SAMPLE_LIMIT}",
+        quote_field(field)
+    )
+}
+
+/// Field names outside `[A-Za-z0-9_.@]` need backticks in Logs Insights.
+fn quote_field(field: &str) -> String {
+    if field
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '@'))
+    {
+        field.to_string()
+    } else {
+        format!("`{}`", field.replace('`', ""))
+    }
+}
+
+/// Distinct values of a field from a quick sampling query, picked with Space
+/// and inserted into the query as a `filter field in [...]` clause.
+pub struct ValuePicker {
+    pub field: String,
+    /// `None` while the sampling query runs.
+    pub values: Option<Vec<(String, usize)>>,
+    chosen: Vec<bool>,
+    pub selected: usize,
+}
+
+impl ValuePicker {
+    pub fn new(field: String) -> Self {
+        Self {
+            field,
+            values: None,
+            chosen: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Read the sampling query's rows; events without the field are left out.
+    pub fn load(&mut self, records: &[LogRecord]) {
+        let values: Vec<(String, usize)> = records
+            .iter()
+            .filter_map(|record| {
+                let mut value = None;
+                let mut count = 0;
+                for field in record {
+                    match field.name.as_deref() {
+                        Some(name) if name == COUNT_COLUMN => {
+                            count = field.value.trim().parse().unwrap_or(0);
+                        }
+                        Some(name) if name == self.field => value = Some(field.value.clone()),
+                        _ => {}
+                    }
+                }
+                value
+                    .filter(|value| !value.is_empty())
+                    .map(|value| (value, count))
+            })
+            .collect();
+        self.chosen = vec![false; values.len()];
+        self.selected = 0;
+        self.values = Some(values);
+    }
+
+    pub fn is_chosen(&self, idx: usize) -> bool {
+        self.chosen.get(idx).copied().unwrap_or(false)
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        let len = self.chosen.len();
+        if len == 0 {
+            return;
+        }
+        self.selected = (self.selected as i32 + delta).clamp(0, len as i32 - 1) as usize;
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some(chosen) = self.chosen.get_mut(self.selected) {
+            *chosen = !*chosen;
+        }
+    }
+
+    /// `filter field in [...]` for the ticked values, or the highlighted one
+    /// when nothing is ticked. Numbers stay bare so they compare as numbers.
+    pub fn clause(&self) -> Option<String> {
+        let values = self.values.as_ref()?;
+        let mut picked: Vec<&str> = values
+            .iter()
+            .zip(&self.chosen)
+            .filter(|(_, chosen)| **chosen)
+            .map(|((value, _), _)| value.as_str())
+            .collect();
+        if picked.is_empty() {
+            picked.push(values.get(self.selected)?.0.as_str());
+        }
+        let literals: Vec<String> = picked
+            .iter()
+            .map(|value| {
+                if value.parse::<f64>().is_ok() {
+                    value.to_string()
+                } else {
+                    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+                }
+            })
+            .collect();
+        Some(format!(
+            "filter {} in [{}]",
+            quote_field(&self.field),
+            literals.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+/// Replace each `{{name}}` in `text` with the workspace variable of that name.
+pub fn expand(text: &str, variables: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        let value = variables.get(name).ok_or_else(|| {
+            format!("The query uses {{{{{name}}}}}, which is not set in this workspace's variables")
+        })?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(value);
+        rest = &rest[start + 2 + len + 2..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// True when the text has at least one `{{name}}` placeholder.
+pub fn has_placeholders(text: &str) -> bool {
+    text.find("{{")
+        .is_some_and(|start| text[start..].contains("}}"))
+}
+
+/// Read `name = value; name = value`, as typed in the variables dialog.
+pub fn parse(line: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut variables = BTreeMap::new();
+    for entry in line.split(';').filter(|entry| !entry.trim().is_empty()) {
+        let (name, value) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("Write '{}' as name = value", entry.trim()))?;
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!(
+                "'{name}' is not a variable name (letters, digits, _)"
+            ));
+        }
+        variables.insert(name.to_string(), value.trim().to_string());
+    }
+    Ok(variables)
+}
+
+/// The dialog form of `parse`'s input.
+pub fn format(variables: &BTreeMap<String, String>) -> String {
+    variables
+        .iter()
+        .map(|(name, value)| format!("{name} = {value}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
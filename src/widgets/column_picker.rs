@@ -1,8 +1,10 @@
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::Style;
 use ratatui::text::Span;
-use ratatui::widgets::{Block, Borders, StatefulWidget, Widget};
+use ratatui::widgets::{StatefulWidget, Widget};
+
+use crate::theme;
 
 #[derive(Clone, Debug)]
 pub struct ColumnPickerState {
@@ -24,6 +26,14 @@ impl ColumnPickerState {
         self.selections
     }
 
+    pub fn selections(&self) -> &[bool] {
+        &self.selections
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
     pub fn move_selection(&mut self, delta: i32) {
         if self.selections.is_empty() {
             return;
@@ -83,11 +93,20 @@ impl ColumnPickerState {
 
 pub struct ColumnVisibilityModal<'a> {
     headers: &'a [String],
+    title: &'a str,
 }
 
 impl<'a> ColumnVisibilityModal<'a> {
     pub fn new(headers: &'a [String]) -> Self {
-        Self { headers }
+        Self {
+            headers,
+            title: "Select columns",
+        }
+    }
+
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
     }
 }
 
@@ -99,9 +118,7 @@ impl StatefulWidget for ColumnVisibilityModal<'_> {
             return;
         }
 
-        let block = Block::default()
-            .title("Select columns")
-            .borders(Borders::ALL);
+        let block = theme::block().title(self.title);
         let inner = block.inner(area);
         block.render(area, buf);
 
@@ -142,13 +159,11 @@ impl StatefulWidget for ColumnVisibilityModal<'_> {
             };
             let display = format!("[{}] {}", checked, header);
 
-            let mut style = Style::default();
-            if idx == state.selected {
-                style = style
-                    .fg(Color::Black)
-                    .bg(Color::Rgb(255, 246, 199))
-                    .add_modifier(Modifier::BOLD);
-            }
+            let style = if idx == state.selected {
+                theme::selection()
+            } else {
+                Style::default()
+            };
 
             let span = Span::styled(display, style);
             buf.set_span(
@@ -163,7 +178,7 @@ impl StatefulWidget for ColumnVisibilityModal<'_> {
             if area.height > 0 {
                 let hint = Span::styled(
                     "↑/↓ move • Space toggle • Enter apply • Esc cancel",
-                    Style::default().fg(Color::DarkGray),
+                    theme::hint(),
                 );
                 buf.set_span(area.x, area.y, &hint, area.width);
             }
@@ -0,0 +1,202 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{StatefulWidget, Widget};
+
+use crate::theme;
+
+/// Width and height the picker needs, including its border.
+pub const DATE_PICKER_SIZE: (u16, u16) = (30, 14);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickerPart {
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+#[derive(Clone, Debug)]
+pub struct DatePickerState {
+    date: NaiveDate,
+    time: NaiveTime,
+    part: PickerPart,
+}
+
+impl DatePickerState {
+    pub fn new(initial: NaiveDateTime) -> Self {
+        Self {
+            date: initial.date(),
+            time: initial.time().with_nanosecond(0).unwrap_or(NaiveTime::MIN),
+            part: PickerPart::Day,
+        }
+    }
+
+    pub fn value(&self) -> NaiveDateTime {
+        self.date.and_time(self.time)
+    }
+
+    pub fn part(&self) -> PickerPart {
+        self.part
+    }
+
+    pub fn cycle_part(&mut self, forward: bool) {
+        let parts = [
+            PickerPart::Day,
+            PickerPart::Hour,
+            PickerPart::Minute,
+            PickerPart::Second,
+        ];
+        let current = parts.iter().position(|p| *p == self.part).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % parts.len()
+        } else {
+            (current + parts.len() - 1) % parts.len()
+        };
+        self.part = parts[next];
+    }
+
+    pub fn move_days(&mut self, delta: i64) {
+        if let Some(date) = self.date.checked_add_signed(Duration::days(delta)) {
+            self.date = date;
+        }
+    }
+
+    pub fn move_months(&mut self, delta: i32) {
+        let months = Months::new(delta.unsigned_abs());
+        let moved = if delta >= 0 {
+            self.date.checked_add_months(months)
+        } else {
+            self.date.checked_sub_months(months)
+        };
+        if let Some(date) = moved {
+            self.date = date;
+        }
+    }
+
+    /// Spin the focused time component, wrapping within its range.
+    pub fn adjust_time(&mut self, delta: i32) {
+        let (value, modulus) = match self.part {
+            PickerPart::Day => return,
+            PickerPart::Hour => (self.time.hour(), 24),
+            PickerPart::Minute => (self.time.minute(), 60),
+            PickerPart::Second => (self.time.second(), 60),
+        };
+        let next = (value as i32 + delta).rem_euclid(modulus) as u32;
+        let updated = match self.part {
+            PickerPart::Hour => self.time.with_hour(next),
+            PickerPart::Minute => self.time.with_minute(next),
+            PickerPart::Second => self.time.with_second(next),
+            PickerPart::Day => None,
+        };
+        if let Some(time) = updated {
+            self.time = time;
+        }
+    }
+
+    fn days_in_month(&self) -> u32 {
+        let first = self.date.with_day(1).unwrap_or(self.date);
+        first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .map(|last| last.day())
+            .unwrap_or(28)
+    }
+}
+
+pub struct DatePicker<'a> {
+    title: &'a str,
+}
+
+impl<'a> DatePicker<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self { title }
+    }
+}
+
+impl StatefulWidget for DatePicker<'_> {
+    type State = DatePickerState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let block = theme::block().title(self.title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let day_focused = state.part == PickerPart::Day;
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("{:^20}", state.date.format("%B %Y").to_string()),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled("Mo Tu We Th Fr Sa Su", theme::hint())),
+        ];
+
+        let first = state.date.with_day(1).unwrap_or(state.date);
+        let offset = first.weekday().num_days_from_monday() as usize;
+        let days = state.days_in_month() as usize;
+        let mut week: Vec<Span> = vec![Span::raw("   "); offset];
+        for day in 1..=days {
+            let label = format!("{day:>2}");
+            let style = if day as u32 == state.date.day() {
+                if day_focused {
+                    theme::selection()
+                } else {
+                    Style::default().add_modifier(Modifier::UNDERLINED)
+                }
+            } else {
+                Style::default()
+            };
+            week.push(Span::styled(label, style));
+            week.push(Span::raw(" "));
+            if week.len() >= 14 {
+                lines.push(Line::from(std::mem::take(&mut week)));
+            }
+        }
+        if !week.is_empty() {
+            lines.push(Line::from(week));
+        }
+        while lines.len() < 8 {
+            lines.push(Line::default());
+        }
+
+        let component = |value: u32, part: PickerPart| {
+            let style = if state.part == part {
+                theme::selection()
+            } else {
+                Style::default()
+            };
+            Span::styled(format!("{value:02}"), style)
+        };
+        lines.push(Line::from(vec![
+            Span::raw("Time  "),
+            component(state.time.hour(), PickerPart::Hour),
+            Span::raw(":"),
+            component(state.time.minute(), PickerPart::Minute),
+            Span::raw(":"),
+            component(state.time.second(), PickerPart::Second),
+        ]));
+        lines.push(Line::default());
+        let hint = if day_focused {
+            "←→↑↓ day • PgUp/PgDn month"
+        } else {
+            "↑/↓ change value"
+        };
+        lines.push(Line::from(Span::styled(hint, theme::hint())));
+        lines.push(Line::from(Span::styled(
+            "Tab part • Enter set • Esc",
+            theme::hint(),
+        )));
+
+        for (offset, line) in lines.iter().enumerate().take(inner.height as usize) {
+            buf.set_line(inner.x, inner.y + offset as u16, line, inner.width);
+        }
+    }
+}
@@ -1,2 +1,4 @@
 pub mod column_picker;
+pub mod date_picker;
+pub mod tab_bar;
 pub mod toggle;
@@ -0,0 +1,99 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+
+use crate::theme;
+
+/// Longest title shown before it is cut with an ellipsis.
+const MAX_TITLE_CHARS: usize = 28;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TabState {
+    Idle,
+    Running,
+    Complete,
+    Failed,
+}
+
+impl TabState {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Running => "running",
+            Self::Complete => "complete",
+            Self::Failed => "error",
+        }
+    }
+
+    /// Glyphs differ per state so monochrome terminals still tell them apart.
+    fn indicator(self) -> &'static str {
+        match self {
+            Self::Idle => "○",
+            Self::Running => "◐",
+            Self::Complete => "●",
+            Self::Failed => "✗",
+        }
+    }
+}
+
+pub struct TabItem {
+    pub title: String,
+    pub state: TabState,
+    /// Results arrived while the tab was in the background.
+    pub unseen: bool,
+    /// Currently in the highlighted half of a flash.
+    pub flashing: bool,
+}
+
+/// One-line strip of workspace tabs with a state indicator per tab.
+pub struct TabBar<'a> {
+    tabs: &'a [TabItem],
+    active: usize,
+}
+
+impl<'a> TabBar<'a> {
+    pub fn new(tabs: &'a [TabItem], active: usize) -> Self {
+        Self { tabs, active }
+    }
+}
+
+impl<'a> Widget for TabBar<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let mut spans = Vec::new();
+        for (idx, tab) in self.tabs.iter().enumerate() {
+            let title = if tab.title.chars().count() > MAX_TITLE_CHARS {
+                let cut: String = tab.title.chars().take(MAX_TITLE_CHARS - 1).collect();
+                format!("{cut}…")
+            } else {
+                tab.title.clone()
+            };
+            let title_style = if idx == self.active {
+                theme::active_tab()
+            } else if tab.flashing {
+                theme::attention()
+            } else if tab.unseen {
+                theme::active_tab()
+            } else {
+                theme::hint()
+            };
+            let marker = if tab.unseen { " (new)" } else { "" };
+            spans.push(Span::styled(
+                format!(" {} ", tab.state.indicator()),
+                theme::tab_state(tab.state),
+            ));
+            spans.push(Span::styled(
+                format!("{}. {title}{marker}", idx + 1),
+                title_style,
+            ));
+            spans.push(Span::raw("  "));
+        }
+        if self.tabs.iter().any(|tab| tab.unseen) {
+            spans.push(Span::styled("Alt+N: jump to new results", theme::hint()));
+        }
+        Line::from(spans).render(area, buf);
+    }
+}
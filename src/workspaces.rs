@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use chrono::DateTime;
+use tokio::sync::mpsc;
+use tui_input::Input as SingleLineInput;
+
+use crate::app::{format_local_timestamp, App};
+use crate::log_fetcher::{QueryParams, QueryReport};
+use crate::widgets::tab_bar::TabItem;
+
+/// Query report tagged with the workspace that submitted it.
+pub type TaggedReport = (u64, QueryReport);
+
+/// One tab: its own inputs and results, plus the sender its queries report back on.
+pub struct Workspace {
+    id: u64,
+    pub app: App,
+    pub tx: mpsc::UnboundedSender<QueryReport>,
+}
+
+/// Open workspaces in tab order. Queries keep running in background tabs and
+/// land in the workspace that started them.
+pub struct Workspaces {
+    items: Vec<Workspace>,
+    active: usize,
+    next_id: u64,
+    reports: mpsc::UnboundedSender<TaggedReport>,
+}
+
+impl Workspaces {
+    pub fn new(app: App, reports: mpsc::UnboundedSender<TaggedReport>) -> Self {
+        let mut workspaces = Self {
+            items: Vec::new(),
+            active: 0,
+            next_id: 0,
+            reports,
+        };
+        let workspace = workspaces.workspace(app);
+        workspaces.items.push(workspace);
+        workspaces
+    }
+
+    pub fn active(&mut self) -> &mut Workspace {
+        &mut self.items[self.active]
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn active_id(&self) -> u64 {
+        self.items[self.active].id
+    }
+
+    pub fn is_active(&self, id: u64) -> bool {
+        self.items[self.active].id == id
+    }
+
+    pub fn apps_mut(&mut self) -> impl Iterator<Item = &mut App> {
+        self.items.iter_mut().map(|workspace| &mut workspace.app)
+    }
+
+    pub fn app_by_id(&mut self, id: u64) -> Option<&mut App> {
+        self.items
+            .iter_mut()
+            .find(|workspace| workspace.id == id)
+            .map(|workspace| &mut workspace.app)
+    }
+
+    pub fn tabs(&self) -> Vec<TabItem> {
+        self.items
+            .iter()
+            .map(|workspace| TabItem {
+                title: workspace.app.workspace_title(),
+                state: workspace.app.workspace_state(),
+                unseen: workspace.app.results_unseen_since.is_some(),
+                flashing: workspace.app.tab_flash_on(),
+            })
+            .collect()
+    }
+
+    /// Open a tab next to the current one, keeping its region and profile.
+    pub fn open(&mut self) {
+        let current = &self.items[self.active].app;
+        let mut app = App {
+            linear_mode: current.linear_mode,
+            keyboard_enhanced: current.keyboard_enhanced,
+            hidden_inputs: current.hidden_inputs.clone(),
+            query_limiter: Arc::clone(&current.query_limiter),
+            stream_enricher: Arc::clone(&current.stream_enricher),
+            production_confirmed: Arc::clone(&current.production_confirmed),
+            aws_region_input: current.aws_region_input.clone(),
+            aws_profiles: current.aws_profiles.clone(),
+            profiles_loading: current.profiles_loading,
+            profile_accounts: current.profile_accounts.clone(),
+            selected_profile: current.selected_profile.clone(),
+            ..App::default()
+        };
+        app.apply_default_query();
+        app.sync_metadata();
+        app.set_status("New workspace");
+        let workspace = self.workspace(app);
+        self.items.insert(self.active + 1, workspace);
+        self.activate(self.active + 1);
+    }
+
+    /// Open a tab next to the current one set up to run a drill-down query over
+    /// the same log group, region, and absolute time range.
+    pub fn open_drill_down(&mut self, title: String, params: QueryParams) {
+        let current = &self.items[self.active].app;
+        let mut app = App {
+            linear_mode: current.linear_mode,
+            keyboard_enhanced: current.keyboard_enhanced,
+            hidden_inputs: current.hidden_inputs.clone(),
+            query_limiter: Arc::clone(&current.query_limiter),
+            stream_enricher: Arc::clone(&current.stream_enricher),
+            production_confirmed: Arc::clone(&current.production_confirmed),
+            aws_region_input: SingleLineInput::new(params.region.clone()),
+            aws_profiles: current.aws_profiles.clone(),
+            profiles_loading: current.profiles_loading,
+            profile_accounts: current.profile_accounts.clone(),
+            selected_profile: current.selected_profile.clone(),
+            variables: current.variables.clone(),
+            log_group_input: SingleLineInput::new(params.log_group.clone()),
+            relative_mode: false,
+            workspace_name: Some(format!("↳ {title}")),
+            ..App::default()
+        };
+        let bounds = DateTime::from_timestamp_millis(params.start_millis)
+            .zip(DateTime::from_timestamp_millis(params.end_millis));
+        if let Some((start, end)) = bounds {
+            app.from_input = SingleLineInput::new(format_local_timestamp(start));
+            app.to_input = SingleLineInput::new(format_local_timestamp(end));
+        }
+        app.replace_query_text(params.query);
+        app.query_baseline = app.query_text();
+        app.sync_metadata();
+        let workspace = self.workspace(app);
+        self.items.insert(self.active + 1, workspace);
+        self.activate(self.active + 1);
+    }
+
+    pub fn close_active(&mut self) {
+        if self.items.len() == 1 {
+            self.active().app.set_status("Only one workspace is open");
+            return;
+        }
+        // A query still running there reports to a closed channel and is dropped.
+        self.items.remove(self.active);
+        self.activate(self.active.min(self.items.len() - 1));
+    }
+
+    pub fn cycle(&mut self, delta: isize) {
+        let count = self.items.len() as isize;
+        self.activate((self.active as isize + delta).rem_euclid(count) as usize);
+    }
+
+    /// Switch to the next tab, in tab order, holding results not yet looked at.
+    pub fn next_unseen(&mut self) {
+        let count = self.items.len();
+        let next = (1..count)
+            .map(|offset| (self.active + offset) % count)
+            .find(|&idx| self.items[idx].app.results_unseen_since.is_some());
+        match next {
+            Some(idx) => self.activate(idx),
+            None => self.active().app.set_status("No other tab has new results"),
+        }
+    }
+
+    fn activate(&mut self, idx: usize) {
+        self.active = idx;
+        self.items[idx].app.results_unseen_since = None;
+    }
+
+    fn workspace(&mut self, app: App) -> Workspace {
+        let id = self.next_id;
+        self.next_id += 1;
+        let (tx, mut rx) = mpsc::unbounded_channel::<QueryReport>();
+        let reports = self.reports.clone();
+        tokio::spawn(async move {
+            while let Some(report) = rx.recv().await {
+                if reports.send((id, report)).is_err() {
+                    break;
+                }
+            }
+        });
+        Workspace { id, app, tx }
+    }
+}